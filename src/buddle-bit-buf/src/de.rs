@@ -0,0 +1,283 @@
+//! A [`serde::Deserializer`] implemented directly over [`BitReader`],
+//! mirroring the wire format written by [`crate::ser`].
+//!
+//! The format is not self-describing, so `deserialize_any` is not
+//! supported; derived `Deserialize` impls must be driven through the
+//! concrete `deserialize_*` calls `#[derive(Deserialize)]` already emits.
+
+use alloc::string::ToString;
+
+use serde::de::{
+    self, value::U32Deserializer, DeserializeSeed, Deserializer as _, EnumAccess,
+    IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+
+use crate::{BitReader, Error};
+
+/// Deserializes a `T` from the front of `buf`.
+pub fn from_bytes<'de, T: serde::Deserialize<'de>>(buf: &'de [u8]) -> Result<T, Error> {
+    let mut reader = BitReader::new(buf);
+    T::deserialize(&mut reader)
+}
+
+macro_rules! impl_deserialize_literal {
+    ($($deserialize_fn:ident($read_fn:ident) => $visit_fn:ident),* $(,)?) => {
+        $(
+            fn $deserialize_fn<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit_fn(self.$read_fn().map_err(Error::from_reader)?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for &'a mut BitReader<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom(
+            "the bit-packed format is not self-describing; `deserialize_any` is unsupported",
+        ))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_bool(self.bool().map_err(Error::from_reader)?)
+    }
+
+    impl_deserialize_literal! {
+        deserialize_i8(i8) => visit_i8,
+        deserialize_u8(u8) => visit_u8,
+        deserialize_i16(i16) => visit_i16,
+        deserialize_u16(u16) => visit_u16,
+        deserialize_i32(i32) => visit_i32,
+        deserialize_u32(u32) => visit_u32,
+        deserialize_i64(i64) => visit_i64,
+        deserialize_u64(u64) => visit_u64,
+        deserialize_u128(u128) => visit_u128,
+        deserialize_i128(i128) => visit_i128,
+        deserialize_f32(f32) => visit_f32,
+        deserialize_f64(f64) => visit_f64,
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let v = self.u32().map_err(Error::from_reader)?;
+        let c = char::from_u32(v).ok_or_else(|| de::Error::custom("invalid char value"))?;
+        visitor.visit_char(c)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let len = self.u32().map_err(Error::from_reader)? as usize;
+        let bytes = self.read_bytes(len).map_err(Error::from_reader)?;
+        let s = core::str::from_utf8(bytes).map_err(|e| de::Error::custom(e.to_string()))?;
+        visitor.visit_borrowed_str(s)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let len = self.u32().map_err(Error::from_reader)? as usize;
+        let bytes = self.read_bytes(len).map_err(Error::from_reader)?;
+        visitor.visit_borrowed_bytes(bytes)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.bool().map_err(Error::from_reader)? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let remaining = self.u32().map_err(Error::from_reader)? as usize;
+        visitor.visit_seq(Access {
+            reader: self,
+            remaining,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(Access {
+            reader: self,
+            remaining: len,
+        })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let remaining = self.u32().map_err(Error::from_reader)? as usize;
+        visitor.visit_map(Access {
+            reader: self,
+            remaining,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(Enum { reader: self })
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_u32(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Drives sequential reads of a known element count for `deserialize_seq`,
+/// `deserialize_tuple`, and `deserialize_map`.
+struct Access<'a, 'de> {
+    reader: &'a mut BitReader<'de>,
+    remaining: usize,
+}
+
+impl<'de, 'a> SeqAccess<'de> for Access<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.reader).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de, 'a> MapAccess<'de> for Access<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.reader).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        seed.deserialize(&mut *self.reader)
+    }
+}
+
+/// Drives the opcode-prefixed reads for `deserialize_enum`.
+struct Enum<'a, 'de> {
+    reader: &'a mut BitReader<'de>,
+}
+
+impl<'de, 'a> EnumAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let index = self.reader.u32().map_err(Error::from_reader)?;
+        let deserializer: U32Deserializer<Error> = index.into_deserializer();
+        let value = seed.deserialize(deserializer)?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, 'a> VariantAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self.reader)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.reader.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.reader.deserialize_tuple(fields.len(), visitor)
+    }
+}
@@ -0,0 +1,83 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// An error that occurred while (de)serializing a value through a
+/// [`BitReader`](crate::BitReader) or [`BitWriter`](crate::BitWriter),
+/// whether directly or via `serde`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error(String);
+
+impl Error {
+    /// Wraps any displayable error (an `anyhow::Error` under the `std`
+    /// feature, or this very type on `no_std` builds) as an [`Error`].
+    pub(crate) fn from_reader(err: impl fmt::Display) -> Self {
+        Self(err.to_string())
+    }
+
+    /// Constructs an error carrying a plain, formatted message.
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn msg(msg: impl fmt::Display) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// A distinct marker for the specific failure of requesting more bits from a
+/// [`BitReader`](crate::BitReader) than remain in the buffer, mirroring the
+/// intent of `std::io::ErrorKind::UnexpectedEof`.
+///
+/// Unlike an arbitrary formatted [`Error`], this carries no message of its
+/// own to compare against; under the `std` feature it can be recovered from
+/// the returned `anyhow::Error` via `downcast_ref`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnexpectedEof;
+
+impl fmt::Display for UnexpectedEof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("premature EOF while trying to read data")
+    }
+}
+
+impl core::error::Error for UnexpectedEof {}
+
+impl From<UnexpectedEof> for Error {
+    fn from(eof: UnexpectedEof) -> Self {
+        Self(eof.to_string())
+    }
+}
+
+/// The result type used by the fallible bit-reading methods on
+/// [`BitReader`](crate::BitReader) when built without the `std` feature.
+///
+/// The `std` build instead uses `anyhow::Result`, since it is already a
+/// dependency of consumers linking against `std`.
+#[cfg(not(feature = "std"))]
+pub(crate) type Result<T> = core::result::Result<T, Error>;
+
+#[cfg(not(feature = "std"))]
+macro_rules! bail {
+    ($($arg:tt)*) => {
+        return Err($crate::error::Error::msg(alloc::format!($($arg)*)))
+    };
+}
+#[cfg(not(feature = "std"))]
+pub(crate) use bail;
@@ -5,16 +5,33 @@
 //! a byte towards the MSB. The exception are whole units of bytes,
 //! which will be written in proper little-endian ordering.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(
     rust_2018_idioms,
     rustdoc::broken_intra_doc_links,
     unsafe_op_in_unsafe_fn
 )]
 
+extern crate alloc;
+
 mod reader;
 pub use reader::BitReader;
 
 mod writer;
 pub use writer::{BitWriter, LengthPrefix};
 
+mod varint;
+
 mod util;
+
+mod error;
+pub use error::{Error, UnexpectedEof};
+
+mod ser;
+pub use ser::to_bytes;
+
+mod de;
+pub use de::from_bytes;
+
+mod value;
+pub use value::{read_value, skip_value, write_value, Value};
@@ -1,12 +1,18 @@
-use anyhow::bail;
+#[cfg(feature = "std")]
+use anyhow::{bail, Error, Result};
 use bitvec::{domain::Domain, prelude::*};
 use buddle_utils::mem::align_down;
+#[cfg(not(feature = "std"))]
+use crate::error::{bail, Result};
+#[cfg(not(feature = "std"))]
+use crate::Error;
+use crate::UnexpectedEof;
 use funty::Integral;
 
 #[cold]
 #[inline(never)]
-fn premature_eof() -> anyhow::Error {
-    anyhow::anyhow!("premature EOF while trying to read data")
+fn premature_eof() -> Error {
+    Error::from(UnexpectedEof)
 }
 
 macro_rules! impl_read_literal {
@@ -14,7 +20,7 @@ macro_rules! impl_read_literal {
         $(
             $(#[$doc])*
             #[inline]
-            pub fn $read_fn(&mut self) -> anyhow::Result<$ty> {
+            pub fn $read_fn(&mut self) -> Result<$ty> {
                 self.realign_to_byte();
                 self.read_bitint::<$ty>(<$ty>::BITS as _)
             }
@@ -55,7 +61,7 @@ impl<'de> BitReader<'de> {
 
     /// Reads a single bit from the buffer, if possible.
     #[inline]
-    pub fn read_bit(&mut self) -> anyhow::Result<bool> {
+    pub fn read_bit(&mut self) -> Result<bool> {
         let (first, remainder) = self.inner.split_first().ok_or_else(premature_eof)?;
         self.inner = remainder;
 
@@ -64,7 +70,7 @@ impl<'de> BitReader<'de> {
 
     /// Reads `n` bits from the buffer, if possible.
     #[inline]
-    pub fn read_bits(&mut self, n: usize) -> anyhow::Result<&'de BitSlice<u8, Lsb0>> {
+    pub fn read_bits(&mut self, n: usize) -> Result<&'de BitSlice<u8, Lsb0>> {
         if n <= self.inner.len() {
             // SAFETY: We checked that `n` is in bounds.
             let (chunk, remainder) = unsafe { self.inner.split_at_unchecked(n) };
@@ -79,7 +85,7 @@ impl<'de> BitReader<'de> {
     /// Reads a given number of bits from the buffer into an integer,
     /// if possible.
     #[inline]
-    pub fn read_bitint<I: Integral>(&mut self, bits: usize) -> anyhow::Result<I> {
+    pub fn read_bitint<I: Integral>(&mut self, bits: usize) -> Result<I> {
         if 0 < bits && bits <= I::BITS as _ {
             self.read_bits(bits).map(|bs| bs.load_le())
         } else {
@@ -99,23 +105,43 @@ impl<'de> BitReader<'de> {
     /// This will force-align the buffer to full byte boundaries before
     /// reading; effectively discarding the remaining bits until then.
     #[inline]
-    pub fn read_bytes(&mut self, n: usize) -> anyhow::Result<&'de [u8]> {
+    pub fn read_bytes(&mut self, n: usize) -> Result<&'de [u8]> {
         self.realign_to_byte();
         self.read_bits(n * u8::BITS as usize)
             .map(|bs| match bs.domain() {
                 // SAFETY: Since we're starting at byte boundary and only reading
                 // full bytes, we don't have to consider any partial elements.
                 Domain::Region { body, .. } => body,
-                Domain::Enclave(..) => unsafe { std::hint::unreachable_unchecked() },
+                Domain::Enclave(..) => unsafe { core::hint::unreachable_unchecked() },
             })
     }
 
+    /// Reads back a length prefix written by
+    /// [`BitWriter::write_length_prefix`](crate::BitWriter::write_length_prefix),
+    /// returning the number of bits remaining in the payload that follows
+    /// (i.e. excluding the length field's own width), so a caller can skip
+    /// straight over it via [`BitReader::read_bits`] without decoding it.
+    ///
+    /// Fixed at a [`u32`] width, matching how this crate always uses a
+    /// [`u32`] count/length prefix elsewhere (see [`crate::ser`]).
+    #[inline]
+    pub fn read_length_prefix(&mut self) -> Result<usize> {
+        let before = self.len();
+        let total = self.u32()? as usize;
+        let consumed = before - self.len();
+
+        match total.checked_sub(consumed) {
+            Some(bits) => Ok(bits),
+            None => bail!("length prefix too short to cover its own field"),
+        }
+    }
+
     /// Reads a [`bool`] value from the buffer, if possible.
     ///
     /// Booleans are represented as individual bits and do not force a
     /// realign to full byte boundaries.
     #[inline]
-    pub fn bool(&mut self) -> anyhow::Result<bool> {
+    pub fn bool(&mut self) -> Result<bool> {
         self.read_bit()
     }
 
@@ -159,6 +185,22 @@ impl<'de> BitReader<'de> {
         /// This will force-align the buffer to full byte boundaries before
         /// reading; effectively discarding the remaining bits until then.
         u64() -> u64,
+        /// Reads a [`i64`] value from the buffer, if possible.
+        ///
+        /// This will force-align the buffer to full byte boundaries before
+        /// reading; effectively discarding the remaining bits until then.
+        i64() -> i64,
+
+        /// Reads a [`u128`] value from the buffer, if possible.
+        ///
+        /// This will force-align the buffer to full byte boundaries before
+        /// reading; effectively discarding the remaining bits until then.
+        u128() -> u128,
+        /// Reads a [`i128`] value from the buffer, if possible.
+        ///
+        /// This will force-align the buffer to full byte boundaries before
+        /// reading; effectively discarding the remaining bits until then.
+        i128() -> i128,
     }
 
     /// Reads a [`f32`] value from the buffer, if possible.
@@ -166,7 +208,7 @@ impl<'de> BitReader<'de> {
     /// This will force-align the buffer to full byte boundaries before
     /// reading; effectively discarding the remaining bits until then.
     #[inline]
-    pub fn f32(&mut self) -> anyhow::Result<f32> {
+    pub fn f32(&mut self) -> Result<f32> {
         self.u32().map(f32::from_bits)
     }
 
@@ -175,7 +217,7 @@ impl<'de> BitReader<'de> {
     /// This will force-align the buffer to full byte boundaries before
     /// reading; effectively discarding the remaining bits until then.
     #[inline]
-    pub fn f64(&mut self) -> anyhow::Result<f64> {
+    pub fn f64(&mut self) -> Result<f64> {
         self.u64().map(f64::from_bits)
     }
 }
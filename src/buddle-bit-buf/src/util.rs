@@ -23,6 +23,6 @@ macro_rules! impl_intcast_from_usize {
 }
 
 impl_intcast_from_usize! {
-    i8, i16, i32, i64, isize,
-    u8, u16, u32, u64, usize,
+    i8, i16, i32, i64, i128, isize,
+    u8, u16, u32, u64, u128, usize,
 }
@@ -0,0 +1,246 @@
+//! A small self-describing, length-prefixed typed-value layer on top of
+//! [`BitWriter`]/[`BitReader`].
+//!
+//! Unlike the `serde` (de)serializers in [`crate::ser`]/[`crate::de`], which
+//! require both sides to already agree on the shape of the data, every
+//! [`Value`] written here is preceded by a one-byte [`Mark`] identifying its
+//! kind and, for variable-length kinds, a length prefix (reusing
+//! [`BitWriter::reserve_length_prefix`]/[`BitWriter::write_length_prefix`])
+//! covering the bits of the payload that follows. A reader that doesn't
+//! recognize a field - an unexpected map key, a trailing list element from
+//! a newer writer - can [`skip_value`] it using nothing but its mark and
+//! length, without understanding its contents; this is the approach used by
+//! formats like mbon, and is what lets a blob gain new fields while staying
+//! readable by old code.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use anyhow::{bail, Result};
+#[cfg(not(feature = "std"))]
+use crate::error::{bail, Result};
+use crate::{BitReader, BitWriter};
+
+/// Identifies the kind of a [`Value`] on the wire; written as a single byte
+/// immediately before its payload.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Mark {
+    Bool = 0,
+    U8 = 1,
+    I8 = 2,
+    U16 = 3,
+    I16 = 4,
+    U32 = 5,
+    I32 = 6,
+    U64 = 7,
+    I64 = 8,
+    U128 = 9,
+    I128 = 10,
+    F32 = 11,
+    F64 = 12,
+    Bytes = 13,
+    List = 14,
+    Map = 15,
+}
+
+impl Mark {
+    fn from_u8(v: u8) -> Result<Self> {
+        Ok(match v {
+            0 => Self::Bool,
+            1 => Self::U8,
+            2 => Self::I8,
+            3 => Self::U16,
+            4 => Self::I16,
+            5 => Self::U32,
+            6 => Self::I32,
+            7 => Self::U64,
+            8 => Self::I64,
+            9 => Self::U128,
+            10 => Self::I128,
+            11 => Self::F32,
+            12 => Self::F64,
+            13 => Self::Bytes,
+            14 => Self::List,
+            15 => Self::Map,
+            _ => bail!("unknown value mark {v}"),
+        })
+    }
+
+    /// Whether this kind's payload is preceded by a length prefix.
+    ///
+    /// This is the invariant [`skip_value`] relies on: every variable-length
+    /// mark is immediately followed by its length, so skipping never needs
+    /// to understand the payload itself.
+    fn is_variable_length(self) -> bool {
+        matches!(self, Self::Bytes | Self::List | Self::Map)
+    }
+}
+
+/// A dynamically-typed value in the typed-value format, as produced and
+/// consumed by [`write_value`]/[`read_value`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    I64(i64),
+    U128(u128),
+    I128(i128),
+    F32(f32),
+    F64(f64),
+    Bytes(Vec<u8>),
+    List(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+}
+
+impl Value {
+    fn mark(&self) -> Mark {
+        match self {
+            Self::Bool(_) => Mark::Bool,
+            Self::U8(_) => Mark::U8,
+            Self::I8(_) => Mark::I8,
+            Self::U16(_) => Mark::U16,
+            Self::I16(_) => Mark::I16,
+            Self::U32(_) => Mark::U32,
+            Self::I32(_) => Mark::I32,
+            Self::U64(_) => Mark::U64,
+            Self::I64(_) => Mark::I64,
+            Self::U128(_) => Mark::U128,
+            Self::I128(_) => Mark::I128,
+            Self::F32(_) => Mark::F32,
+            Self::F64(_) => Mark::F64,
+            Self::Bytes(_) => Mark::Bytes,
+            Self::List(_) => Mark::List,
+            Self::Map(_) => Mark::Map,
+        }
+    }
+}
+
+/// Writes `value` to `writer`, preceded by its one-byte [`Mark`] and, for
+/// variable-length kinds, a length prefix covering the payload that
+/// follows (see the module docs).
+pub fn write_value(writer: &mut BitWriter, value: &Value) {
+    writer.u8(value.mark() as u8);
+
+    match value {
+        Value::Bool(v) => writer.bool(*v),
+        Value::U8(v) => writer.u8(*v),
+        Value::I8(v) => writer.i8(*v),
+        Value::U16(v) => writer.u16(*v),
+        Value::I16(v) => writer.i16(*v),
+        Value::U32(v) => writer.u32(*v),
+        Value::I32(v) => writer.i32(*v),
+        Value::U64(v) => writer.u64(*v),
+        Value::I64(v) => writer.i64(*v),
+        Value::U128(v) => writer.u128(*v),
+        Value::I128(v) => writer.i128(*v),
+        Value::F32(v) => writer.f32(*v),
+        Value::F64(v) => writer.f64(*v),
+        Value::Bytes(bytes) => {
+            let prefix = writer.reserve_length_prefix::<u32>();
+            writer.write_bytes(bytes);
+            writer.write_length_prefix(prefix);
+        }
+        Value::List(items) => {
+            let prefix = writer.reserve_length_prefix::<u32>();
+            writer.write_var_u32(items.len() as u32);
+            items.iter().for_each(|item| write_value(writer, item));
+            writer.write_length_prefix(prefix);
+        }
+        Value::Map(entries) => {
+            let prefix = writer.reserve_length_prefix::<u32>();
+            writer.write_var_u32(entries.len() as u32);
+            entries.iter().for_each(|(k, v)| {
+                write_value(writer, k);
+                write_value(writer, v);
+            });
+            writer.write_length_prefix(prefix);
+        }
+    }
+}
+
+/// Reads a [`Value`] from `reader`, the inverse of [`write_value`].
+pub fn read_value(reader: &mut BitReader<'_>) -> Result<Value> {
+    let mark = Mark::from_u8(reader.u8()?)?;
+
+    Ok(match mark {
+        Mark::Bool => Value::Bool(reader.bool()?),
+        Mark::U8 => Value::U8(reader.u8()?),
+        Mark::I8 => Value::I8(reader.i8()?),
+        Mark::U16 => Value::U16(reader.u16()?),
+        Mark::I16 => Value::I16(reader.i16()?),
+        Mark::U32 => Value::U32(reader.u32()?),
+        Mark::I32 => Value::I32(reader.i32()?),
+        Mark::U64 => Value::U64(reader.u64()?),
+        Mark::I64 => Value::I64(reader.i64()?),
+        Mark::U128 => Value::U128(reader.u128()?),
+        Mark::I128 => Value::I128(reader.i128()?),
+        Mark::F32 => Value::F32(reader.f32()?),
+        Mark::F64 => Value::F64(reader.f64()?),
+        Mark::Bytes => {
+            let payload_bits = reader.read_length_prefix()?;
+            Value::Bytes(reader.read_bytes(payload_bits / 8)?.to_vec())
+        }
+        Mark::List => {
+            let _payload_bits = reader.read_length_prefix()?;
+            let count = reader.read_var_u32()? as usize;
+
+            let mut items = Vec::with_capacity(count);
+            for _ in 0..count {
+                items.push(read_value(reader)?);
+            }
+            Value::List(items)
+        }
+        Mark::Map => {
+            let _payload_bits = reader.read_length_prefix()?;
+            let count = reader.read_var_u32()? as usize;
+
+            let mut entries = Vec::with_capacity(count);
+            for _ in 0..count {
+                let key = read_value(reader)?;
+                let value = read_value(reader)?;
+                entries.push((key, value));
+            }
+            Value::Map(entries)
+        }
+    })
+}
+
+/// Skips a single value in `reader` without decoding it, using nothing but
+/// its [`Mark`] and, for variable-length kinds, its length prefix.
+///
+/// This is the forward-compatibility primitive the module exists for: a
+/// reader that doesn't need (or know about) a particular value can discard
+/// it wholesale instead of having to understand its contents.
+pub fn skip_value(reader: &mut BitReader<'_>) -> Result<()> {
+    let mark = Mark::from_u8(reader.u8()?)?;
+
+    if mark.is_variable_length() {
+        let payload_bits = reader.read_length_prefix()?;
+        reader.read_bits(payload_bits)?;
+        return Ok(());
+    }
+
+    match mark {
+        Mark::Bool => reader.bool().map(drop),
+        Mark::U8 => reader.u8().map(drop),
+        Mark::I8 => reader.i8().map(drop),
+        Mark::U16 => reader.u16().map(drop),
+        Mark::I16 => reader.i16().map(drop),
+        Mark::U32 => reader.u32().map(drop),
+        Mark::I32 => reader.i32().map(drop),
+        Mark::U64 => reader.u64().map(drop),
+        Mark::I64 => reader.i64().map(drop),
+        Mark::U128 => reader.u128().map(drop),
+        Mark::I128 => reader.i128().map(drop),
+        Mark::F32 => reader.f32().map(drop),
+        Mark::F64 => reader.f64().map(drop),
+        Mark::Bytes | Mark::List | Mark::Map => unreachable!("handled above"),
+    }
+}
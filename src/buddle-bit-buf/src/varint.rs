@@ -0,0 +1,143 @@
+//! Byte-aligned base-128 varint (LEB128-style) support.
+//!
+//! Unsigned values are split into 7-bit groups, low bits first, with the
+//! continuation bit `0x80` set on every byte except the last. Signed
+//! values are zig-zag encoded first so that small negatives stay as
+//! compact as small positives.
+
+#[cfg(feature = "std")]
+use anyhow::{bail, Result};
+#[cfg(not(feature = "std"))]
+use crate::error::{bail, Result};
+use crate::{BitReader, BitWriter};
+
+const MAX_BYTES_32: usize = 5;
+const MAX_BYTES_64: usize = 10;
+
+#[inline]
+const fn zigzag_encode_32(v: i32) -> u32 {
+    ((v << 1) ^ (v >> 31)) as u32
+}
+
+#[inline]
+const fn zigzag_decode_32(v: u32) -> i32 {
+    ((v >> 1) as i32) ^ -((v & 1) as i32)
+}
+
+#[inline]
+const fn zigzag_encode_64(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+#[inline]
+const fn zigzag_decode_64(v: u64) -> i64 {
+    ((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+impl BitWriter {
+    /// Writes `v` as an unsigned base-128 varint.
+    ///
+    /// Flushes to a byte boundary first, since varint groups must be
+    /// byte-aligned.
+    pub fn write_var_u32(&mut self, mut v: u32) {
+        loop {
+            let group = (v & 0x7F) as u8;
+            v >>= 7;
+
+            if v == 0 {
+                self.u8(group);
+                break;
+            } else {
+                self.u8(group | 0x80);
+            }
+        }
+    }
+
+    /// Writes `v` as a zig-zag encoded, signed base-128 varint.
+    pub fn write_var_i32(&mut self, v: i32) {
+        self.write_var_u32(zigzag_encode_32(v));
+    }
+
+    /// Writes `v` as an unsigned base-128 varint.
+    pub fn write_var_u64(&mut self, mut v: u64) {
+        loop {
+            let group = (v & 0x7F) as u8;
+            v >>= 7;
+
+            if v == 0 {
+                self.u8(group);
+                break;
+            } else {
+                self.u8(group | 0x80);
+            }
+        }
+    }
+
+    /// Writes `v` as a zig-zag encoded, signed base-128 varint.
+    pub fn write_var_i64(&mut self, v: i64) {
+        self.write_var_u64(zigzag_encode_64(v));
+    }
+}
+
+impl<'de> BitReader<'de> {
+    /// Reads an unsigned base-128 varint.
+    ///
+    /// Flushes to a byte boundary first, since varint groups must be
+    /// byte-aligned. Errors when more than 5 groups are read without
+    /// terminating, since that would overflow a [`u32`].
+    pub fn read_var_u32(&mut self) -> Result<u32> {
+        let mut result: u32 = 0;
+
+        for i in 0..MAX_BYTES_32 {
+            let byte = self.u8()?;
+            let group = (byte & 0x7F) as u32;
+
+            if i == MAX_BYTES_32 - 1 && (group & !0xF) != 0 {
+                bail!("varint overflows u32");
+            }
+
+            result |= group << (i * 7);
+
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+
+        bail!("varint exceeds maximum of {MAX_BYTES_32} bytes for u32");
+    }
+
+    /// Reads a zig-zag encoded, signed base-128 varint.
+    pub fn read_var_i32(&mut self) -> Result<i32> {
+        self.read_var_u32().map(zigzag_decode_32)
+    }
+
+    /// Reads an unsigned base-128 varint.
+    ///
+    /// Errors when more than 10 groups are read without terminating,
+    /// since that would overflow a [`u64`].
+    pub fn read_var_u64(&mut self) -> Result<u64> {
+        let mut result: u64 = 0;
+
+        for i in 0..MAX_BYTES_64 {
+            let byte = self.u8()?;
+            let group = (byte & 0x7F) as u64;
+
+            if i == MAX_BYTES_64 - 1 && (group & !0x1) != 0 {
+                bail!("varint overflows u64");
+            }
+
+            result |= group << (i * 7);
+
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+
+        bail!("varint exceeds maximum of {MAX_BYTES_64} bytes for u64");
+    }
+
+    /// Reads a zig-zag encoded, signed base-128 varint.
+    pub fn read_var_i64(&mut self) -> Result<i64> {
+        self.read_var_u64().map(zigzag_decode_64)
+    }
+}
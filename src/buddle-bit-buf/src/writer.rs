@@ -1,5 +1,6 @@
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
+use alloc::vec::Vec;
 use bitvec::prelude::*;
 use buddle_utils::mem::align_up;
 use funty::Integral;
@@ -228,6 +229,17 @@ impl BitWriter {
         /// This will force-align the buffer to full byte boundaries before
         /// writing; effectively filling remaining bits with zeroes.
         i64(i64),
+
+        /// Writes a given [`u128`] value to the buffer.
+        ///
+        /// This will force-align the buffer to full byte boundaries before
+        /// writing; effectively filling remaining bits with zeroes.
+        u128(u128),
+        /// Writes a given [`i128`] value to the buffer.
+        ///
+        /// This will force-align the buffer to full byte boundaries before
+        /// writing; effectively filling remaining bits with zeroes.
+        i128(i128),
     }
 
     /// Writes the bits of a given [`f32`] value to the buffer.
@@ -0,0 +1,49 @@
+use buddle_bit_buf::{BitReader, BitWriter};
+
+#[test]
+fn test_u128_roundtrip() {
+    for v in [0u128, 1, u128::MAX, u128::MAX / 3] {
+        let mut writer = BitWriter::new();
+        writer.u128(v);
+
+        let bytes = writer.into_vec();
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(reader.u128().unwrap(), v);
+    }
+}
+
+#[test]
+fn test_i128_roundtrip() {
+    for v in [0i128, -1, i128::MIN, i128::MAX] {
+        let mut writer = BitWriter::new();
+        writer.i128(v);
+
+        let bytes = writer.into_vec();
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(reader.i128().unwrap(), v);
+    }
+}
+
+#[test]
+fn test_u128_length_prefix_roundtrip() {
+    let mut writer = BitWriter::new();
+
+    let prefix = writer.reserve_length_prefix::<u128>();
+    writer.u8(1);
+    writer.u8(2);
+    writer.u8(3);
+    writer.write_length_prefix(prefix);
+
+    let bytes = writer.into_vec();
+    let mut reader = BitReader::new(&bytes);
+
+    // Mirrors `BitReader::read_length_prefix`, but for a 128-bit prefix
+    // instead of its hardcoded `u32` width.
+    let total = reader.read_bitint::<u128>(u128::BITS as _).unwrap() as usize;
+    let payload_bits = total - u128::BITS as usize;
+    assert_eq!(payload_bits, reader.len());
+
+    assert_eq!(reader.u8().unwrap(), 1);
+    assert_eq!(reader.u8().unwrap(), 2);
+    assert_eq!(reader.u8().unwrap(), 3);
+}
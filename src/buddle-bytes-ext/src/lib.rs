@@ -1,12 +1,15 @@
-//! This crate extends the [`bytes`] crate with fallible read
-//! operations on [`bytes::Buf`]s.
+//! This crate extends the [`bytes`] crate with fallible read and write
+//! operations on [`bytes::Buf`]/[`bytes::BufMut`].
 
 #![deny(rust_2018_idioms, rustdoc::broken_intra_doc_links)]
 #![forbid(unsafe_code)]
 
-use std::mem::size_of;
+use std::{
+    fmt::{self, Display},
+    mem::size_of,
+};
 
-use bytes::Buf;
+use bytes::{Buf, BufMut};
 
 macro_rules! read_checked {
     ($source:ident.$fn:ident() -> $ty:ty) => {
@@ -14,6 +17,12 @@ macro_rules! read_checked {
     };
 }
 
+macro_rules! write_checked {
+    ($dest:ident.$fn:ident($value:expr) -> $ty:ty) => {
+        ($dest.remaining_mut() >= size_of::<$ty>()).then(|| $dest.$fn($value))
+    };
+}
+
 /// Provides fallible read operations for arbitrary [`Buf`]s.
 pub trait CheckedBuf: Buf {
     /// Attempts to get an [`i8`] from `self`.
@@ -109,3 +118,278 @@ pub trait CheckedBuf: Buf {
 
 impl<B: Buf> CheckedBuf for B {}
 impl CheckedBuf for dyn Buf {}
+
+/// Provides fallible write operations for arbitrary [`BufMut`]s.
+pub trait CheckedBufMut: BufMut {
+    /// Attempts to put an [`i8`] into `self`.
+    fn try_put_i8(&mut self, value: i8) -> Option<()> {
+        write_checked!(self.put_i8(value) -> i8)
+    }
+
+    /// Attempts to put an [`u8`] into `self`.
+    fn try_put_u8(&mut self, value: u8) -> Option<()> {
+        write_checked!(self.put_u8(value) -> u8)
+    }
+
+    /// Attempts to put an [`i16`] into `self` in big-endian byte order.
+    fn try_put_i16(&mut self, value: i16) -> Option<()> {
+        write_checked!(self.put_i16(value) -> i16)
+    }
+
+    /// Attempts to put an [`i16`] into `self` in little-endian byte order.
+    fn try_put_i16_le(&mut self, value: i16) -> Option<()> {
+        write_checked!(self.put_i16_le(value) -> i16)
+    }
+
+    /// Attempts to put an [`u16`] into `self` in big-endian byte order.
+    fn try_put_u16(&mut self, value: u16) -> Option<()> {
+        write_checked!(self.put_u16(value) -> u16)
+    }
+
+    /// Attempts to put an [`u16`] into `self` in little-endian byte order.
+    fn try_put_u16_le(&mut self, value: u16) -> Option<()> {
+        write_checked!(self.put_u16_le(value) -> u16)
+    }
+
+    /// Attempts to put an [`i32`] into `self` in big-endian byte order.
+    fn try_put_i32(&mut self, value: i32) -> Option<()> {
+        write_checked!(self.put_i32(value) -> i32)
+    }
+
+    /// Attempts to put an [`i32`] into `self` in little-endian byte order.
+    fn try_put_i32_le(&mut self, value: i32) -> Option<()> {
+        write_checked!(self.put_i32_le(value) -> i32)
+    }
+
+    /// Attempts to put an [`u32`] into `self` in big-endian byte order.
+    fn try_put_u32(&mut self, value: u32) -> Option<()> {
+        write_checked!(self.put_u32(value) -> u32)
+    }
+
+    /// Attempts to put an [`u32`] into `self` in little-endian byte order.
+    fn try_put_u32_le(&mut self, value: u32) -> Option<()> {
+        write_checked!(self.put_u32_le(value) -> u32)
+    }
+
+    /// Attempts to put an [`i64`] into `self` in big-endian byte order.
+    fn try_put_i64(&mut self, value: i64) -> Option<()> {
+        write_checked!(self.put_i64(value) -> i64)
+    }
+
+    /// Attempts to put an [`i64`] into `self` in little-endian byte order.
+    fn try_put_i64_le(&mut self, value: i64) -> Option<()> {
+        write_checked!(self.put_i64_le(value) -> i64)
+    }
+
+    /// Attempts to put an [`u64`] into `self` in big-endian byte order.
+    fn try_put_u64(&mut self, value: u64) -> Option<()> {
+        write_checked!(self.put_u64(value) -> u64)
+    }
+
+    /// Attempts to put an [`u64`] into `self` in little-endian byte order.
+    fn try_put_u64_le(&mut self, value: u64) -> Option<()> {
+        write_checked!(self.put_u64_le(value) -> u64)
+    }
+
+    /// Attempts to put an [`f32`] into `self` in big-endian byte order.
+    fn try_put_f32(&mut self, value: f32) -> Option<()> {
+        write_checked!(self.put_f32(value) -> f32)
+    }
+
+    /// Attempts to put an [`f32`] into `self` in little-endian byte order.
+    fn try_put_f32_le(&mut self, value: f32) -> Option<()> {
+        write_checked!(self.put_f32_le(value) -> f32)
+    }
+
+    /// Attempts to put an [`f64`] into `self` in big-endian byte order.
+    fn try_put_f64(&mut self, value: f64) -> Option<()> {
+        write_checked!(self.put_f64(value) -> f64)
+    }
+
+    /// Attempts to put an [`f64`] into `self` in little-endian byte order.
+    fn try_put_f64_le(&mut self, value: f64) -> Option<()> {
+        write_checked!(self.put_f64_le(value) -> f64)
+    }
+}
+
+impl<B: BufMut> CheckedBufMut for B {}
+impl CheckedBufMut for dyn BufMut {}
+
+/// A located, diagnosable "not enough bytes" failure from a [`TryRead`] —
+/// the checked-read equivalent of a positioned
+/// [`std::io::ErrorKind::UnexpectedEof`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnderrunError {
+    /// The byte offset into the original buffer at which the read was
+    /// attempted.
+    pub offset: usize,
+    /// The number of bytes the read required.
+    pub required: usize,
+    /// The number of bytes that were actually available at `offset`.
+    pub available: usize,
+}
+
+impl Display for UnderrunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "buffer underrun at offset {}: needed {} bytes but only {} were available",
+            self.offset, self.required, self.available
+        )
+    }
+}
+
+impl std::error::Error for UnderrunError {}
+
+macro_rules! try_read {
+    ($self:ident.$fn:ident() -> $ty:ty) => {{
+        let required = size_of::<$ty>();
+        let available = $self.inner.remaining();
+
+        if available < required {
+            return Err(UnderrunError {
+                offset: $self.offset,
+                required,
+                available,
+            });
+        }
+
+        let value = $self.inner.$fn();
+        $self.offset += required;
+        Ok(value)
+    }};
+}
+
+/// Wraps a [`Buf`] and tracks how many bytes have been consumed from it,
+/// so a short read can be reported as a located [`UnderrunError`] instead
+/// of a bare [`None`].
+///
+/// For hot paths that don't need the diagnostic, use [`CheckedBuf`]'s
+/// `Option`-returning methods on the wrapped buffer directly instead.
+pub struct TryRead<B> {
+    inner: B,
+    offset: usize,
+}
+
+impl<B: Buf> TryRead<B> {
+    /// Wraps `inner`, starting the offset counter at zero.
+    pub fn new(inner: B) -> Self {
+        Self { inner, offset: 0 }
+    }
+
+    /// Consumes this wrapper and returns the underlying buffer.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    /// The number of bytes consumed from the buffer so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Attempts to read an [`i8`] from the buffer.
+    pub fn try_get_i8(&mut self) -> Result<i8, UnderrunError> {
+        try_read!(self.get_i8() -> i8)
+    }
+
+    /// Attempts to read an [`u8`] from the buffer.
+    pub fn try_get_u8(&mut self) -> Result<u8, UnderrunError> {
+        try_read!(self.get_u8() -> u8)
+    }
+
+    /// Attempts to read an [`i16`] from the buffer in big-endian byte
+    /// order.
+    pub fn try_get_i16(&mut self) -> Result<i16, UnderrunError> {
+        try_read!(self.get_i16() -> i16)
+    }
+
+    /// Attempts to read an [`i16`] from the buffer in little-endian byte
+    /// order.
+    pub fn try_get_i16_le(&mut self) -> Result<i16, UnderrunError> {
+        try_read!(self.get_i16_le() -> i16)
+    }
+
+    /// Attempts to read an [`u16`] from the buffer in big-endian byte
+    /// order.
+    pub fn try_get_u16(&mut self) -> Result<u16, UnderrunError> {
+        try_read!(self.get_u16() -> u16)
+    }
+
+    /// Attempts to read an [`u16`] from the buffer in little-endian byte
+    /// order.
+    pub fn try_get_u16_le(&mut self) -> Result<u16, UnderrunError> {
+        try_read!(self.get_u16_le() -> u16)
+    }
+
+    /// Attempts to read an [`i32`] from the buffer in big-endian byte
+    /// order.
+    pub fn try_get_i32(&mut self) -> Result<i32, UnderrunError> {
+        try_read!(self.get_i32() -> i32)
+    }
+
+    /// Attempts to read an [`i32`] from the buffer in little-endian byte
+    /// order.
+    pub fn try_get_i32_le(&mut self) -> Result<i32, UnderrunError> {
+        try_read!(self.get_i32_le() -> i32)
+    }
+
+    /// Attempts to read an [`u32`] from the buffer in big-endian byte
+    /// order.
+    pub fn try_get_u32(&mut self) -> Result<u32, UnderrunError> {
+        try_read!(self.get_u32() -> u32)
+    }
+
+    /// Attempts to read an [`u32`] from the buffer in little-endian byte
+    /// order.
+    pub fn try_get_u32_le(&mut self) -> Result<u32, UnderrunError> {
+        try_read!(self.get_u32_le() -> u32)
+    }
+
+    /// Attempts to read an [`i64`] from the buffer in big-endian byte
+    /// order.
+    pub fn try_get_i64(&mut self) -> Result<i64, UnderrunError> {
+        try_read!(self.get_i64() -> i64)
+    }
+
+    /// Attempts to read an [`i64`] from the buffer in little-endian byte
+    /// order.
+    pub fn try_get_i64_le(&mut self) -> Result<i64, UnderrunError> {
+        try_read!(self.get_i64_le() -> i64)
+    }
+
+    /// Attempts to read an [`u64`] from the buffer in big-endian byte
+    /// order.
+    pub fn try_get_u64(&mut self) -> Result<u64, UnderrunError> {
+        try_read!(self.get_u64() -> u64)
+    }
+
+    /// Attempts to read an [`u64`] from the buffer in little-endian byte
+    /// order.
+    pub fn try_get_u64_le(&mut self) -> Result<u64, UnderrunError> {
+        try_read!(self.get_u64_le() -> u64)
+    }
+
+    /// Attempts to read an [`f32`] from the buffer in big-endian byte
+    /// order.
+    pub fn try_get_f32(&mut self) -> Result<f32, UnderrunError> {
+        try_read!(self.get_f32() -> f32)
+    }
+
+    /// Attempts to read an [`f32`] from the buffer in little-endian byte
+    /// order.
+    pub fn try_get_f32_le(&mut self) -> Result<f32, UnderrunError> {
+        try_read!(self.get_f32_le() -> f32)
+    }
+
+    /// Attempts to read an [`f64`] from the buffer in big-endian byte
+    /// order.
+    pub fn try_get_f64(&mut self) -> Result<f64, UnderrunError> {
+        try_read!(self.get_f64() -> f64)
+    }
+
+    /// Attempts to read an [`f64`] from the buffer in little-endian byte
+    /// order.
+    pub fn try_get_f64_le(&mut self) -> Result<f64, UnderrunError> {
+        try_read!(self.get_f64_le() -> f64)
+    }
+}
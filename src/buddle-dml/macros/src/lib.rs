@@ -0,0 +1,62 @@
+//! Procedural macros for use with [`buddle-dml`].
+//!
+//! There is no need to directly add this crate to application
+//! dependencies as these macros are already re-exported by
+//! [`buddle-dml`].
+//!
+//! [`buddle-dml`]: ../buddle_dml/
+
+#![deny(rust_2018_idioms, rustdoc::broken_intra_doc_links)]
+#![forbid(unsafe_code)]
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod message;
+mod protocol;
+mod utils;
+
+/// Derives [`BinaryEncoding`][crate::BinaryEncoding] for a message struct
+/// whose fields are each tagged with their DML wire type, e.g.
+/// `#[dml(STR)]` or `#[dml(GID)]`.
+///
+/// Fields are read and written in declaration order by threading each one
+/// through its own [`BinaryEncoding`][crate::BinaryEncoding] implementation.
+/// The generated `read_versioned`/`write_versioned` thread the negotiated
+/// protocol version through to each field the same way, so a field whose
+/// own type gates on it still works correctly when nested in a derived
+/// message. This only derives [`BinaryEncoding`][crate::BinaryEncoding];
+/// the rest of the [`Message`][crate::Message] trait (name, description,
+/// access level, dispatching, ...) still has to be implemented by hand.
+#[proc_macro_derive(Message, attributes(dml))]
+pub fn derive_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    message::derive(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Generates the `read_message` dispatch of a
+/// [`Protocol`][crate::Protocol] implementation from a list of
+/// `order => MessageType` pairs.
+///
+/// Every `MessageType` must implement [`BinaryEncoding`][crate::BinaryEncoding]
+/// and [`Message`][crate::Message]. This expands to the `fn read_message`
+/// required by the trait, and is meant to be placed among the other,
+/// hand-written methods of the `impl`:
+///
+/// ```ignore
+/// impl Protocol for LoginProtocol {
+///     // ... other trivial methods ...
+///
+///     buddle_dml::protocol! {
+///         0 => LoginRequest,
+///         1 => LoginResponse,
+///     }
+/// }
+/// ```
+#[proc_macro]
+pub fn protocol(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as protocol::Input);
+    protocol::expand(input).into()
+}
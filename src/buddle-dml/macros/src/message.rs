@@ -0,0 +1,105 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, Ident, Result};
+
+use crate::utils::default_crate_path;
+
+fn validate_dml_type(field: &syn::Field) -> Result<()> {
+    let mut found = false;
+
+    for attr in &field.attrs {
+        if attr.path.is_ident("dml") {
+            if found {
+                return Err(Error::new_spanned(attr, "duplicate #[dml] attribute found"));
+            }
+
+            // Validate that the attribute carries a single identifier,
+            // e.g. `#[dml(STR)]`, without attaching meaning to which one;
+            // dispatch is always driven by the field's actual Rust type.
+            attr.parse_args::<Ident>()?;
+            found = true;
+        }
+    }
+
+    if found {
+        Ok(())
+    } else {
+        Err(Error::new_spanned(
+            field,
+            "every field of a #[derive(Message)] struct must carry a #[dml(..)] \
+             attribute denoting its wire type",
+        ))
+    }
+}
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let path = default_crate_path();
+
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    ty,
+                    "Message can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                ty,
+                "Message can only be derived for structs",
+            ))
+        }
+    };
+
+    for field in fields {
+        validate_dml_type(field)?;
+    }
+
+    let idents: Vec<_> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+
+    Ok(quote! {
+        impl #impl_generics #path::BinaryEncoding for #ty #ty_generics #where_clause {
+            fn binary_size(&self) -> ::std::primitive::usize {
+                0 #(+ #path::BinaryEncoding::binary_size(&self.#idents))*
+            }
+
+            fn read(
+                source: &mut #path::bytes::Bytes,
+                limit: ::std::option::Option<::std::primitive::usize>,
+            ) -> ::std::option::Option<Self>
+            where
+                Self: ::std::marker::Sized,
+            {
+                ::std::option::Option::Some(Self {
+                    #(#idents: #path::BinaryEncoding::read(source, limit)?,)*
+                })
+            }
+
+            fn write(&self, dest: &mut #path::bytes::BytesMut) {
+                #(#path::BinaryEncoding::write(&self.#idents, dest);)*
+            }
+
+            fn read_versioned(
+                source: &mut #path::bytes::Bytes,
+                version: ::std::primitive::i32,
+                limit: ::std::option::Option<::std::primitive::usize>,
+            ) -> ::std::option::Option<Self>
+            where
+                Self: ::std::marker::Sized,
+            {
+                ::std::option::Option::Some(Self {
+                    #(#idents: #path::BinaryEncoding::read_versioned(source, version, limit)?,)*
+                })
+            }
+
+            fn write_versioned(&self, dest: &mut #path::bytes::BytesMut, version: ::std::primitive::i32) {
+                #(#path::BinaryEncoding::write_versioned(&self.#idents, dest, version);)*
+            }
+        }
+    })
+}
@@ -0,0 +1,63 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    LitInt, Result, Token, Type,
+};
+
+use crate::utils::default_crate_path;
+
+struct Entry {
+    order: LitInt,
+    ty: Type,
+}
+
+impl Parse for Entry {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let order = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let ty = input.parse()?;
+
+        Ok(Self { order, ty })
+    }
+}
+
+pub struct Input {
+    entries: Punctuated<Entry, Token![,]>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        Ok(Self {
+            entries: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+pub fn expand(input: Input) -> TokenStream {
+    let path = default_crate_path();
+
+    let orders = input.entries.iter().map(|e| &e.order);
+    let tys = input.entries.iter().map(|e| &e.ty);
+
+    quote! {
+        fn read_message(
+            &self,
+            order: ::std::primitive::u8,
+            buf: &mut #path::bytes::Bytes,
+            limit: ::std::option::Option<::std::primitive::usize>,
+        ) -> ::std::option::Option<::std::boxed::Box<dyn #path::Message>> {
+            let version = self.version();
+
+            match order {
+                #(
+                    #orders => <#tys as #path::BinaryEncoding>::read_versioned(buf, version, limit).map(|msg| {
+                        ::std::boxed::Box::new(msg) as ::std::boxed::Box<dyn #path::Message>
+                    }),
+                )*
+                _ => ::std::option::Option::None,
+            }
+        }
+    }
+}
@@ -8,12 +8,21 @@ use roxmltree::Document;
 mod field;
 pub use field::Field;
 
+mod property;
+pub use property::{decode_class, encode_class};
+
 mod protocol;
 pub use protocol::Protocol;
 
 mod record;
 pub use record::Record;
 
+mod registry;
+pub use registry::{ServiceRegistry, ServiceRegistryBuilder};
+
+mod value;
+pub use value::Value;
+
 /// Parses a DML protocol from its XML description given as a string.
 pub fn parse_protocol(input: &str) -> anyhow::Result<Protocol> {
     let proto = Document::parse(input)?;
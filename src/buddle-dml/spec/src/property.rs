@@ -0,0 +1,193 @@
+use std::io::{Read, Write};
+
+use anyhow::{anyhow, bail};
+use buddle_object_property::{
+    cpp::{RawString, RawWideString},
+    type_info::PropertyList,
+    PropertyClass, Type,
+};
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+
+use crate::{field::Field, record::Record};
+
+/// Encodes `obj`'s reflected properties to `writer` according to
+/// `record`'s field order and declared DML types, the same wire layout
+/// [`Record::encode`] produces from a [`Value`][crate::Value] slice.
+///
+/// Each visible field (see [`Record::iter_visible_fields`]) is looked up
+/// by name on `obj`'s [`PropertyList`] and its reflected leaf value is
+/// coerced to the field's declared DML type; `NOXFER` fields are skipped
+/// entirely, since they never cross the wire.
+///
+/// # Errors
+///
+/// Fails if a visible field has no matching property on `obj`, has no
+/// (or an unrecognized) DML type declared, or if the property's
+/// reflected type does not match the declared DML type.
+pub fn encode_class<W: Write>(
+    record: &Record,
+    obj: &dyn PropertyClass,
+    writer: &mut W,
+) -> anyhow::Result<()> {
+    let list = obj.property_list();
+
+    for field in record.iter_visible_fields() {
+        let (owner, view) = list
+            .property_recursive(obj, field.name())
+            .ok_or_else(|| anyhow!("no property named `{}` on `{}`", field.name(), list.type_name()))?;
+
+        encode_leaf(field, owner.property(view), writer)?;
+    }
+
+    Ok(())
+}
+
+/// Decodes a fresh instance of the [`PropertyClass`] described by `list`
+/// out of `reader`, reading one value per visible field of `record`, in
+/// declared order.
+///
+/// The instance is default-constructed via [`PropertyList::make_default`]
+/// before its visible fields are overwritten in place; `NOXFER` fields are
+/// skipped, so they keep whatever value the default construction gave
+/// them.
+///
+/// # Errors
+///
+/// Fails if a visible field has no matching property on the decoded
+/// type, has no (or an unrecognized) DML type declared, or if `reader`
+/// runs out before every field is read.
+pub fn decode_class<R: Read>(
+    record: &Record,
+    list: &'static PropertyList,
+    reader: &mut R,
+) -> anyhow::Result<Box<dyn PropertyClass>> {
+    let mut obj = list.make_default();
+
+    for field in record.iter_visible_fields() {
+        let (_, view) = list
+            .property_recursive(obj.as_ref(), field.name())
+            .ok_or_else(|| anyhow!("no property named `{}` on `{}`", field.name(), list.type_name()))?;
+
+        decode_leaf(field, obj.property_mut(view), reader)?;
+    }
+
+    Ok(obj)
+}
+
+// Writes a single property's reflected value as `field`'s declared DML
+// type. `value` is the leaf currently stored in the `PropertyClass`, not
+// an intermediate `Value`, which is what sets this apart from
+// `Record::encode`'s `encode_value`.
+fn encode_leaf<W: Write>(field: &Field, value: &dyn Type, writer: &mut W) -> anyhow::Result<()> {
+    let ty = field
+        .dml_type()
+        .ok_or_else(|| anyhow!("field `{}` has no DML type declared", field.name()))?;
+
+    macro_rules! write_as {
+        ($rust_ty:ty, $write:ident) => {
+            match value.downcast_ref::<$rust_ty>() {
+                Some(v) => writer.$write::<LE>(*v)?,
+                None => return Err(mismatch(field, ty)),
+            }
+        };
+    }
+
+    match ty {
+        "GID" => write_as!(u64, write_u64),
+        "INT" => write_as!(i32, write_i32),
+        "UINT" => write_as!(u32, write_u32),
+        "FLT" => write_as!(f32, write_f32),
+        "DBL" => write_as!(f64, write_f64),
+        "BYT" => match value.downcast_ref::<i8>() {
+            Some(v) => writer.write_i8(*v)?,
+            None => return Err(mismatch(field, ty)),
+        },
+        "STR" => match value.downcast_ref::<RawString>() {
+            Some(v) => write_bytes(writer, v)?,
+            None => return Err(mismatch(field, ty)),
+        },
+        "WSTR" => match value.downcast_ref::<RawWideString>() {
+            Some(v) => write_wide_str(writer, v)?,
+            None => return Err(mismatch(field, ty)),
+        },
+        ty => bail!("field `{}` has unsupported DML type `{ty}`", field.name()),
+    }
+
+    Ok(())
+}
+
+// Reads a single property's reflected value out of `reader` as `field`'s
+// declared DML type, overwriting `value` in place.
+fn decode_leaf<R: Read>(field: &Field, value: &mut dyn Type, reader: &mut R) -> anyhow::Result<()> {
+    let ty = field
+        .dml_type()
+        .ok_or_else(|| anyhow!("field `{}` has no DML type declared", field.name()))?;
+
+    macro_rules! read_as {
+        ($rust_ty:ty, $read:ident) => {
+            match value.downcast_mut::<$rust_ty>() {
+                Some(slot) => *slot = reader.$read::<LE>()?,
+                None => return Err(mismatch(field, ty)),
+            }
+        };
+    }
+
+    match ty {
+        "GID" => read_as!(u64, read_u64),
+        "INT" => read_as!(i32, read_i32),
+        "UINT" => read_as!(u32, read_u32),
+        "FLT" => read_as!(f32, read_f32),
+        "DBL" => read_as!(f64, read_f64),
+        "BYT" => match value.downcast_mut::<i8>() {
+            Some(slot) => *slot = reader.read_i8()?,
+            None => return Err(mismatch(field, ty)),
+        },
+        "STR" => match value.downcast_mut::<RawString>() {
+            Some(slot) => *slot = RawString(read_bytes(reader)?),
+            None => return Err(mismatch(field, ty)),
+        },
+        "WSTR" => match value.downcast_mut::<RawWideString>() {
+            Some(slot) => *slot = RawWideString(read_wide_str(reader)?),
+            None => return Err(mismatch(field, ty)),
+        },
+        ty => bail!("field `{}` has unsupported DML type `{ty}`", field.name()),
+    }
+
+    Ok(())
+}
+
+// Builds a consistent error for a field whose declared DML type does not
+// match the reflected Rust type backing its property.
+fn mismatch(field: &Field, ty: &str) -> anyhow::Error {
+    anyhow!(
+        "field `{}` declares DML type `{ty}`, but its property holds a different reflected type",
+        field.name()
+    )
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> anyhow::Result<Vec<u8>> {
+    let len = reader.read_u16::<LE>()? as usize;
+    let mut buf = vec![0; len];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> anyhow::Result<()> {
+    writer.write_u16::<LE>(bytes.len().try_into()?)?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+fn read_wide_str<R: Read>(reader: &mut R) -> anyhow::Result<Vec<u16>> {
+    let len = reader.read_u16::<LE>()? as usize;
+    (0..len).map(|_| Ok(reader.read_u16::<LE>()?)).collect()
+}
+
+fn write_wide_str<W: Write>(writer: &mut W, wide: &[u16]) -> anyhow::Result<()> {
+    writer.write_u16::<LE>(wide.len().try_into()?)?;
+    for &wchar in wide {
+        writer.write_u16::<LE>(wchar)?;
+    }
+
+    Ok(())
+}
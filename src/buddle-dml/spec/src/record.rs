@@ -1,8 +1,12 @@
+use std::io::{Read, Write};
+
+use anyhow::{anyhow, bail};
 use buddle_utils::ahash::RandomState;
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
 use indexmap::IndexMap;
 use roxmltree::Node;
 
-use crate::field::Field;
+use crate::{field::Field, value::Value};
 
 /// Represents a DML record which groups [`Field`]s together.
 ///
@@ -105,4 +109,117 @@ impl Record {
             .and_then(|v| v.parse().ok())
             .unwrap_or(1)
     }
+
+    /// Decodes this record's fields out of `reader`, in declared order,
+    /// yielding one [`Value`] per field [`Record::iter_visible_fields`]
+    /// would yield.
+    ///
+    /// This only decodes the fields' values; the Service ID and order
+    /// byte a full message is framed with on the wire are a
+    /// [`ServiceRegistry`][crate::ServiceRegistry] concern, not this
+    /// record's.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `reader` runs out before every field is read, or if a
+    /// field has no (or an unrecognized) DML type declared.
+    pub fn decode<R: Read>(&self, reader: &mut R) -> anyhow::Result<Vec<Value>> {
+        self.iter_visible_fields()
+            .map(|field| decode_value(field, reader))
+            .collect()
+    }
+
+    /// Encodes `values` to `writer`, in the same order
+    /// [`Record::decode`] reads them back in.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `values` doesn't have exactly one entry per field
+    /// [`Record::iter_visible_fields`] yields, or if a value's runtime
+    /// type doesn't match the DML type declared for its field.
+    pub fn encode<W: Write>(&self, values: &[Value], writer: &mut W) -> anyhow::Result<()> {
+        let fields: Vec<_> = self.iter_visible_fields().collect();
+        if values.len() != fields.len() {
+            bail!(
+                "expected {} values to encode record `{}`, got {}",
+                fields.len(),
+                self.message_name().unwrap_or("<unknown>"),
+                values.len()
+            );
+        }
+
+        for (field, value) in fields.iter().zip(values) {
+            encode_value(field, value, writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn decode_value<R: Read>(field: &Field, reader: &mut R) -> anyhow::Result<Value> {
+    let ty = field
+        .dml_type()
+        .ok_or_else(|| anyhow!("field `{}` has no DML type declared", field.name()))?;
+
+    Ok(match ty {
+        "GID" => Value::Gid(reader.read_u64::<LE>()?),
+        "INT" => Value::Int(reader.read_i32::<LE>()?),
+        "UINT" => Value::UInt(reader.read_u32::<LE>()?),
+        "FLT" => Value::Flt(reader.read_f32::<LE>()?),
+        "DBL" => Value::Dbl(reader.read_f64::<LE>()?),
+        "BYT" => Value::Byt(reader.read_i8()?),
+        "STR" => Value::Str(read_bytes(reader)?),
+        "WSTR" => Value::WStr(read_wide_str(reader)?),
+        ty => bail!("field `{}` has unsupported DML type `{ty}`", field.name()),
+    })
+}
+
+fn encode_value<W: Write>(field: &Field, value: &Value, writer: &mut W) -> anyhow::Result<()> {
+    let ty = field
+        .dml_type()
+        .ok_or_else(|| anyhow!("field `{}` has no DML type declared", field.name()))?;
+
+    match (ty, value) {
+        ("GID", Value::Gid(v)) => writer.write_u64::<LE>(*v)?,
+        ("INT", Value::Int(v)) => writer.write_i32::<LE>(*v)?,
+        ("UINT", Value::UInt(v)) => writer.write_u32::<LE>(*v)?,
+        ("FLT", Value::Flt(v)) => writer.write_f32::<LE>(*v)?,
+        ("DBL", Value::Dbl(v)) => writer.write_f64::<LE>(*v)?,
+        ("BYT", Value::Byt(v)) => writer.write_i8(*v)?,
+        ("STR", Value::Str(bytes)) => write_bytes(writer, bytes)?,
+        ("WSTR", Value::WStr(wide)) => write_wide_str(writer, wide)?,
+        (ty, _) => bail!(
+            "field `{}` declares DML type `{ty}`, but a mismatched value was given",
+            field.name()
+        ),
+    }
+
+    Ok(())
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> anyhow::Result<Vec<u8>> {
+    let len = reader.read_u16::<LE>()? as usize;
+    let mut buf = vec![0; len];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> anyhow::Result<()> {
+    writer.write_u16::<LE>(bytes.len().try_into()?)?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+fn read_wide_str<R: Read>(reader: &mut R) -> anyhow::Result<Vec<u16>> {
+    let len = reader.read_u16::<LE>()? as usize;
+    (0..len).map(|_| Ok(reader.read_u16::<LE>()?)).collect()
+}
+
+fn write_wide_str<W: Write>(writer: &mut W, wide: &[u16]) -> anyhow::Result<()> {
+    writer.write_u16::<LE>(wide.len().try_into()?)?;
+    for &wchar in wide {
+        writer.write_u16::<LE>(wchar)?;
+    }
+
+    Ok(())
 }
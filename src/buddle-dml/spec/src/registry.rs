@@ -0,0 +1,93 @@
+use anyhow::{anyhow, bail};
+use buddle_utils::ahash::RandomState;
+use indexmap::IndexMap;
+use roxmltree::Document;
+
+use crate::protocol::Protocol;
+use crate::record::Record;
+
+/// A registry of [`Protocol`]s, keyed by the Service ID declared in each
+/// protocol's `_ProtocolInfo` record.
+///
+/// Built via [`ServiceRegistryBuilder`] from multiple XML protocol
+/// specifications, this lets a caller resolve an incoming frame's
+/// `(service_id, order)` pair straight to the [`Record`] describing the
+/// message, without having to know in advance which protocol it belongs to.
+///
+/// This only resolves the spec-level [`Record`] describing a message; it
+/// operates on parsed XML specifications, not the code-generated
+/// `Protocol`/`Message` traits `buddle-dml` dispatches through at runtime,
+/// and `buddle-net`'s frame decoder has no dependency on this crate to
+/// call into either. Driving dispatch from a resolved [`Record`] is left
+/// to whatever glues the two together.
+#[derive(Clone, Debug, Default)]
+pub struct ServiceRegistry {
+    protocols: IndexMap<u8, Protocol, RandomState>,
+}
+
+impl ServiceRegistry {
+    /// Gets an immutable reference to the [`Protocol`] registered under
+    /// `service_id`, if any.
+    pub fn protocol(&self, service_id: u8) -> Option<&Protocol> {
+        self.protocols.get(&service_id)
+    }
+
+    /// Resolves a frame's Service ID and message order to the [`Protocol`]
+    /// it belongs to and the [`Record`] describing that specific message.
+    ///
+    /// Returns [`None`] if no protocol is registered under `service_id`, or
+    /// if that protocol has no message at `order`.
+    pub fn resolve(&self, service_id: u8, order: u8) -> Option<(&Protocol, &Record)> {
+        let protocol = self.protocol(service_id)?;
+        let record = protocol
+            .iter_messages()
+            .map(|(_, record)| record)
+            .find(|record| record.message_order() == order)?;
+
+        Some((protocol, record))
+    }
+}
+
+/// A builder for assembling a [`ServiceRegistry`] from multiple XML
+/// protocol specifications.
+#[derive(Clone, Debug, Default)]
+pub struct ServiceRegistryBuilder {
+    protocols: IndexMap<u8, Protocol, RandomState>,
+}
+
+impl ServiceRegistryBuilder {
+    /// Creates a new, empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses `document` as a DML protocol and adds it to the registry
+    /// being built.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `document` cannot be parsed as a [`Protocol`], the parsed
+    /// protocol has no `_ProtocolInfo` record or Service ID, or another
+    /// protocol with the same Service ID was already added.
+    pub fn add(&mut self, document: Document<'_>) -> anyhow::Result<&mut Self> {
+        let protocol = Protocol::parse(document)?;
+        let service_id = protocol
+            .protocol_info()
+            .and_then(Record::service_id)
+            .ok_or_else(|| anyhow!("protocol `{}` has no Service ID", protocol.name()))?;
+
+        if self.protocols.contains_key(&service_id) {
+            bail!("Service ID {service_id} is already taken by another protocol");
+        }
+        self.protocols.insert(service_id, protocol);
+
+        Ok(self)
+    }
+
+    /// Finishes building and returns the assembled [`ServiceRegistry`].
+    pub fn build(&self) -> ServiceRegistry {
+        ServiceRegistry {
+            protocols: self.protocols.clone(),
+        }
+    }
+}
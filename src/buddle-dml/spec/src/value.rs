@@ -0,0 +1,21 @@
+/// A decoded DML field value, tagged by the wire type declared for its
+/// [`Field`][crate::Field].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// A `GID` value: a 64-bit object identifier.
+    Gid(u64),
+    /// An `INT` value: a signed 32-bit integer.
+    Int(i32),
+    /// A `UINT` value: an unsigned 32-bit integer.
+    UInt(u32),
+    /// A `FLT` value: a 32-bit floating-point number.
+    Flt(f32),
+    /// A `DBL` value: a 64-bit floating-point number.
+    Dbl(f64),
+    /// A `BYT` value: a signed 8-bit integer.
+    Byt(i8),
+    /// A `STR` value: a length-prefixed byte string.
+    Str(Vec<u8>),
+    /// A `WSTR` value: a length-prefixed UTF-16 string.
+    WStr(Vec<u16>),
+}
@@ -3,6 +3,8 @@ use std::mem::size_of;
 use buddle_bytes_ext::CheckedBuf;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 
+use crate::varint::{read_varint_u32, write_varint_u32};
+
 /// Defines binary encoding and decoding for DML messages and supported
 /// primitive types.
 pub trait BinaryEncoding {
@@ -10,7 +12,14 @@ pub trait BinaryEncoding {
     fn binary_size(&self) -> usize;
 
     /// Tries to read a `Self` value out of the given `source`.
-    fn read(source: &mut Bytes) -> Option<Self>
+    ///
+    /// `limit` optionally bounds the number of bytes a length-prefixed
+    /// value (e.g. a string) is allowed to declare for itself; the
+    /// declared size is checked against it *before* any memory is
+    /// allocated for decoding, so a forged length prefix cannot force an
+    /// unbounded allocation. Types without a length prefix of their own
+    /// ignore it.
+    fn read(source: &mut Bytes, limit: Option<usize>) -> Option<Self>
     where
         Self: Sized;
 
@@ -21,6 +30,35 @@ pub trait BinaryEncoding {
     /// This may panic if `dest` lacks capacity to store [`BinaryEncoding::binary_size`]
     /// more bytes.
     fn write(&self, dest: &mut BytesMut);
+
+    /// Tries to read a `Self` value out of `source`, as encoded under the
+    /// given protocol `version`.
+    ///
+    /// This exists for message types whose wire layout changed across
+    /// protocol revisions; `version` is the value negotiated for the
+    /// current session, as returned by [`Protocol::version`][crate::Protocol::version].
+    ///
+    /// The default implementation ignores `version` and delegates to
+    /// [`BinaryEncoding::read`]; implementors whose layout is
+    /// version-dependent should override this instead.
+    fn read_versioned(source: &mut Bytes, version: i32, limit: Option<usize>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let _ = version;
+        Self::read(source, limit)
+    }
+
+    /// Writes `self` to `dest`, as encoded under the given protocol
+    /// `version`.
+    ///
+    /// See [`BinaryEncoding::read_versioned`] for the rationale. The
+    /// default implementation ignores `version` and delegates to
+    /// [`BinaryEncoding::write`].
+    fn write_versioned(&self, dest: &mut BytesMut, version: i32) {
+        let _ = version;
+        self.write(dest)
+    }
 }
 
 macro_rules! impl_primitive_encoding {
@@ -31,7 +69,7 @@ macro_rules! impl_primitive_encoding {
                     size_of::<$ty>()
                 }
 
-                fn read(source: &mut Bytes) -> Option<Self>
+                fn read(source: &mut Bytes, _limit: Option<usize>) -> Option<Self>
                 where
                     Self: Sized,
                 {
@@ -62,11 +100,15 @@ impl BinaryEncoding for Vec<u8> {
         size_of::<u16>() + self.len()
     }
 
-    fn read(source: &mut Bytes) -> Option<Self>
+    fn read(source: &mut Bytes, limit: Option<usize>) -> Option<Self>
     where
         Self: Sized,
     {
         let len = source.try_get_u16_le()? as usize;
+        if limit.is_some_and(|limit| len > limit) {
+            return None;
+        }
+
         (source.remaining() >= len).then(|| {
             let mut str = vec![0; len];
             source.copy_to_slice(&mut str);
@@ -87,11 +129,15 @@ impl BinaryEncoding for Vec<u16> {
         size_of::<u16>() + self.len()
     }
 
-    fn read(source: &mut Bytes) -> Option<Self>
+    fn read(source: &mut Bytes, limit: Option<usize>) -> Option<Self>
     where
         Self: Sized,
     {
         let len = source.try_get_u16_le()? as usize;
+        if limit.is_some_and(|limit| len * WCHAR_SIZE > limit) {
+            return None;
+        }
+
         (source.remaining() >= len * WCHAR_SIZE).then(|| {
             let mut wstr = Vec::with_capacity(len);
             (0..len).for_each(|_| wstr.push(source.get_u16_le()));
@@ -104,3 +150,72 @@ impl BinaryEncoding for Vec<u16> {
         self.iter().for_each(|&wchar| dest.put_u16_le(wchar));
     }
 }
+
+/// Wraps a collection type so its length prefix is written as an unsigned
+/// [varint][crate::varint] instead of a fixed `u16`.
+///
+/// This is opt-in: the plain [`BinaryEncoding`] impls for [`Vec<u8>`] and
+/// [`Vec<u16>`] keep their fixed-width `u16` length prefix, matching the
+/// rest of the DML primitives. Use `VarintLen` for fields where most
+/// instances are short, so the length prefix itself shrinks to a single
+/// byte in the common case.
+pub struct VarintLen<T>(pub T);
+
+// The number of bytes `write_varint_u32` emits for `v`.
+fn varint_u32_size(v: u32) -> usize {
+    (32 - (v | 1).leading_zeros() as usize + 6) / 7
+}
+
+impl BinaryEncoding for VarintLen<Vec<u8>> {
+    fn binary_size(&self) -> usize {
+        varint_u32_size(self.0.len() as u32) + self.0.len()
+    }
+
+    fn read(source: &mut Bytes, limit: Option<usize>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let len = read_varint_u32(source)? as usize;
+        if limit.is_some_and(|limit| len > limit) {
+            return None;
+        }
+
+        (source.remaining() >= len).then(|| {
+            let mut bytes = vec![0; len];
+            source.copy_to_slice(&mut bytes);
+            Self(bytes)
+        })
+    }
+
+    fn write(&self, dest: &mut BytesMut) {
+        write_varint_u32(dest, self.0.len().try_into().expect("bytes too long to encode"));
+        dest.put_slice(&self.0);
+    }
+}
+
+impl BinaryEncoding for VarintLen<Vec<u16>> {
+    fn binary_size(&self) -> usize {
+        varint_u32_size(self.0.len() as u32) + self.0.len() * WCHAR_SIZE
+    }
+
+    fn read(source: &mut Bytes, limit: Option<usize>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let len = read_varint_u32(source)? as usize;
+        if limit.is_some_and(|limit| len * WCHAR_SIZE > limit) {
+            return None;
+        }
+
+        (source.remaining() >= len * WCHAR_SIZE).then(|| {
+            let mut wstr = Vec::with_capacity(len);
+            (0..len).for_each(|_| wstr.push(source.get_u16_le()));
+            Self(wstr)
+        })
+    }
+
+    fn write(&self, dest: &mut BytesMut) {
+        write_varint_u32(dest, self.0.len().try_into().expect("string too large to encode"));
+        self.0.iter().for_each(|&wchar| dest.put_u16_le(wchar));
+    }
+}
@@ -6,16 +6,21 @@
 #![deny(rust_2018_idioms, rustdoc::broken_intra_doc_links)]
 #![forbid(unsafe_code)]
 
+#[doc(inline)]
+pub use buddle_dml_macros::*;
+
 pub use bytes;
 
 mod access_level;
 pub use access_level::AccessLevel;
 
 mod encoding;
-pub use encoding::BinaryEncoding;
+pub use encoding::{BinaryEncoding, VarintLen};
 
 mod message;
 pub use message::{DispatchFuture, Message};
 
 mod protocol;
 pub use protocol::Protocol;
+
+pub mod varint;
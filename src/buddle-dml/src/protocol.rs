@@ -23,9 +23,17 @@ pub trait Protocol: fmt::Debug + Sync + 'static {
     /// When the given `order` number is not part of the protocol or
     /// reading from `buf` fails, [`None`] will be returned.
     ///
+    /// `limit` is forwarded to [`BinaryEncoding::read`][crate::BinaryEncoding::read]
+    /// and optionally bounds the size any length-prefixed field of the
+    /// message may declare for itself.
+    ///
     /// Implementors may use the [`BinaryEncoding`][crate::BinaryEncoding]
-    /// trait to read messages and supported types.
-    fn read_message(&self, order: u8, buf: &mut Bytes) -> Option<Box<dyn Message>>;
+    /// trait to read messages and supported types. Implementations should
+    /// read messages via [`BinaryEncoding::read_versioned`][crate::BinaryEncoding::read_versioned],
+    /// passing [`Protocol::version`] through, so that message types whose
+    /// layout changed across protocol revisions can gate their fields on
+    /// it.
+    fn read_message(&self, order: u8, buf: &mut Bytes, limit: Option<usize>) -> Option<Box<dyn Message>>;
 }
 
 impl fmt::Display for dyn Protocol {
@@ -0,0 +1,134 @@
+//! Byte-aligned base-128 varint (LEB128-style) support for DML messages.
+//!
+//! Unsigned values are split into 7-bit groups, low bits first, with the
+//! continuation bit `0x80` set on every byte except the last. Signed
+//! values are zig-zag encoded first so that small negatives stay as
+//! compact as small positives.
+
+use buddle_bytes_ext::CheckedBuf;
+use bytes::{BufMut, Bytes, BytesMut};
+
+const MAX_BYTES_32: usize = 5;
+const MAX_BYTES_64: usize = 10;
+
+#[inline]
+const fn zigzag_encode_32(v: i32) -> u32 {
+    ((v << 1) ^ (v >> 31)) as u32
+}
+
+#[inline]
+const fn zigzag_decode_32(v: u32) -> i32 {
+    ((v >> 1) as i32) ^ -((v & 1) as i32)
+}
+
+#[inline]
+const fn zigzag_encode_64(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+#[inline]
+const fn zigzag_decode_64(v: u64) -> i64 {
+    ((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+/// Writes `v` as an unsigned base-128 varint.
+pub fn write_varint_u32(dest: &mut BytesMut, mut v: u32) {
+    loop {
+        let group = (v & 0x7F) as u8;
+        v >>= 7;
+
+        if v == 0 {
+            dest.put_u8(group);
+            break;
+        } else {
+            dest.put_u8(group | 0x80);
+        }
+    }
+}
+
+/// Writes `v` as a zig-zag encoded, signed base-128 varint.
+pub fn write_varint_i32(dest: &mut BytesMut, v: i32) {
+    write_varint_u32(dest, zigzag_encode_32(v));
+}
+
+/// Writes `v` as an unsigned base-128 varint.
+pub fn write_varint_u64(dest: &mut BytesMut, mut v: u64) {
+    loop {
+        let group = (v & 0x7F) as u8;
+        v >>= 7;
+
+        if v == 0 {
+            dest.put_u8(group);
+            break;
+        } else {
+            dest.put_u8(group | 0x80);
+        }
+    }
+}
+
+/// Writes `v` as a zig-zag encoded, signed base-128 varint.
+pub fn write_varint_i64(dest: &mut BytesMut, v: i64) {
+    write_varint_u64(dest, zigzag_encode_64(v));
+}
+
+/// Reads an unsigned base-128 varint.
+///
+/// Returns [`None`] if `source` runs out of bytes, or if more than 5
+/// groups are read without terminating, since that would overflow a
+/// [`u32`].
+pub fn read_varint_u32(source: &mut Bytes) -> Option<u32> {
+    let mut result: u32 = 0;
+
+    for i in 0..MAX_BYTES_32 {
+        let byte = source.try_get_u8()?;
+        let group = (byte & 0x7F) as u32;
+
+        if i == MAX_BYTES_32 - 1 && (group & !0xF) != 0 {
+            return None;
+        }
+
+        result |= group << (i * 7);
+
+        if byte & 0x80 == 0 {
+            return Some(result);
+        }
+    }
+
+    None
+}
+
+/// Reads a zig-zag encoded, signed base-128 varint.
+pub fn read_varint_i32(source: &mut Bytes) -> Option<i32> {
+    read_varint_u32(source).map(zigzag_decode_32)
+}
+
+/// Reads an unsigned base-128 varint.
+///
+/// Returns [`None`] if `source` runs out of bytes, or if more than 10
+/// groups are read without terminating, since that would overflow a
+/// [`u64`].
+pub fn read_varint_u64(source: &mut Bytes) -> Option<u64> {
+    let mut result: u64 = 0;
+
+    for i in 0..MAX_BYTES_64 {
+        let byte = source.try_get_u8()?;
+        let group = (byte & 0x7F) as u64;
+
+        if i == MAX_BYTES_64 - 1 && (group & !0x1) != 0 {
+            return None;
+        }
+
+        result |= group << (i * 7);
+
+        if byte & 0x80 == 0 {
+            return Some(result);
+        }
+    }
+
+    None
+}
+
+/// Reads a zig-zag encoded, signed base-128 varint.
+pub fn read_varint_i64(source: &mut Bytes) -> Option<i64> {
+    read_varint_u64(source).map(zigzag_decode_64)
+}
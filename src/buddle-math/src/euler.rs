@@ -1,3 +1,5 @@
+use glam::{Mat3, Mat4, Quat, Vec3};
+
 /// Implementation of Euler angles.
 // https://github.com/palestar/medusa/blob/develop/Math/Euler.h
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -13,6 +15,87 @@ impl Euler {
     pub const fn new(pitch: f32, yaw: f32, roll: f32) -> Self {
         Self { pitch, yaw, roll }
     }
+
+    /// Composes this value's axis angles into an orientation [`Quat`],
+    /// according to `order`.
+    pub fn to_quat(&self, order: RotationOrder) -> Quat {
+        let pitch = Quat::from_axis_angle(Vec3::X, self.pitch);
+        let yaw = Quat::from_axis_angle(Vec3::Y, self.yaw);
+        let roll = Quat::from_axis_angle(Vec3::Z, self.roll);
+
+        match order {
+            RotationOrder::YawPitchRoll => yaw * pitch * roll,
+        }
+    }
+
+    /// Extracts the pitch/yaw/roll angles that compose `quat`'s
+    /// orientation, according to `order`.
+    ///
+    /// Near the gimbal-lock singularity, where pitch approaches a right
+    /// angle and yaw/roll rotate around the same axis, `roll` is fixed
+    /// at `0.0` and its contribution is folded into `yaw` instead.
+    pub fn from_quat(quat: Quat, order: RotationOrder) -> Self {
+        match order {
+            RotationOrder::YawPitchRoll => Self::from_mat3_ypr(Mat3::from_quat(quat)),
+        }
+    }
+
+    /// Converts this value's axis angles into a [`Mat3`] rotation matrix,
+    /// according to `order`.
+    pub fn to_mat3(&self, order: RotationOrder) -> Mat3 {
+        Mat3::from_quat(self.to_quat(order))
+    }
+
+    /// Converts this value's axis angles into a [`Mat4`] rotation matrix,
+    /// according to `order`.
+    pub fn to_mat4(&self, order: RotationOrder) -> Mat4 {
+        Mat4::from_quat(self.to_quat(order))
+    }
+
+    /// Extracts the pitch/yaw/roll angles of `mat`'s rotation, according
+    /// to `order`.
+    ///
+    /// See [`Euler::from_quat`] for how the gimbal-lock singularity is
+    /// handled.
+    pub fn from_mat3(mat: Mat3, order: RotationOrder) -> Self {
+        match order {
+            RotationOrder::YawPitchRoll => Self::from_mat3_ypr(mat),
+        }
+    }
+
+    /// Extracts the pitch/yaw/roll angles of `mat`'s rotation, according
+    /// to `order`.
+    ///
+    /// See [`Euler::from_quat`] for how the gimbal-lock singularity is
+    /// handled.
+    pub fn from_mat4(mat: Mat4, order: RotationOrder) -> Self {
+        Self::from_mat3(Mat3::from_mat4(mat), order)
+    }
+
+    // Extracts pitch/yaw/roll from a matrix composed as
+    // `Ry(yaw) * Rx(pitch) * Rz(roll)`, matching the composition order
+    // `to_quat` uses for [`RotationOrder::YawPitchRoll`].
+    fn from_mat3_ypr(mat: Mat3) -> Self {
+        let sin_pitch = (-mat.z_axis.y).clamp(-1.0, 1.0);
+        let pitch = sin_pitch.asin();
+
+        if sin_pitch.abs() > 1.0 - 1e-6 {
+            let yaw = (-mat.x_axis.z).atan2(mat.x_axis.x);
+            Self::new(pitch, yaw, 0.0)
+        } else {
+            let yaw = mat.z_axis.x.atan2(mat.z_axis.z);
+            let roll = mat.x_axis.y.atan2(mat.y_axis.y);
+            Self::new(pitch, yaw, roll)
+        }
+    }
 }
 
-// TODO: Finish this when needed.
+/// The order per-axis rotations are composed in when converting between
+/// [`Euler`] angles and an orientation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RotationOrder {
+    /// `yaw * pitch * roll`, matching the composition order used by the
+    /// engine.
+    #[default]
+    YawPitchRoll,
+}
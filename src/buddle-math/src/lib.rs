@@ -9,3 +9,6 @@
 
 #[doc(inline)]
 pub use glam::*;
+
+mod euler;
+pub use euler::{Euler, RotationOrder};
@@ -1,11 +1,18 @@
 //!
 
+mod compression;
+
 mod crypto;
-pub use crypto::EncryptionMode;
+pub use crypto::{EncryptionMode, FrameCipher};
 
 mod decoder;
 mod encoder;
 
+mod cobs;
+
+mod cobs_frame;
+pub use cobs_frame::CobsFrameCodec;
+
 pub(super) const FOOD: u16 = 0xF00D;
 
 #[inline(always)]
@@ -13,17 +20,64 @@ pub(super) const fn is_large_frame(size: usize) -> bool {
     size > i16::MAX as _
 }
 
-/// A tokio-based codec for reading and writing [`Frame`]s
+/// The default value for [`FrameCodec::with_compression_threshold`].
+///
+/// Data frames whose payload is larger than this are deflated before
+/// being sent; smaller payloads are not worth the overhead of zlib
+/// framing and are sent as-is.
+pub const DEFAULT_COMPRESSION_THRESHOLD: usize = 256;
+
+/// A tokio_util codec for reading and writing length-delimited [`Frame`]s
 /// to network sockets.
 ///
 /// [`Frame`]: crate::frame::Frame
-pub struct Codec {
+pub struct FrameCodec {
     mode: EncryptionMode,
+    cipher: Option<FrameCipher>,
+    compression_threshold: Option<usize>,
 }
 
-impl Codec {
+impl FrameCodec {
     /// Creates a new codec with the given [`EncryptionMode`].
     pub const fn new(mode: EncryptionMode) -> Self {
-        Self { mode }
+        Self {
+            mode,
+            cipher: None,
+            compression_threshold: Some(DEFAULT_COMPRESSION_THRESHOLD),
+        }
+    }
+
+    /// Overrides the size, in bytes, above which a data frame's payload
+    /// is transparently zlib-deflated before being sent.
+    ///
+    /// See [`DEFAULT_COMPRESSION_THRESHOLD`] for the default.
+    pub const fn with_compression_threshold(mut self, threshold: usize) -> Self {
+        self.compression_threshold = Some(threshold);
+        self
+    }
+
+    /// Disables transparent compression of data frame payloads entirely,
+    /// regardless of their size.
+    pub const fn without_compression(mut self) -> Self {
+        self.compression_threshold = None;
+        self
+    }
+
+    /// Installs the session's [`FrameCipher`] once the handshake has
+    /// completed, per the codec's [`EncryptionMode`].
+    ///
+    /// For [`EncryptionMode::Always`], this derives the cipher via HKDF
+    /// over `shared_secret`. For [`EncryptionMode::Cfb8`], `shared_secret`
+    /// and `salt` are ignored, since the key and IV are already carried by
+    /// the mode itself. Has no effect when the codec is running in
+    /// [`EncryptionMode::Never`].
+    pub fn complete_handshake(&mut self, shared_secret: &[u8], salt: &[u8]) -> anyhow::Result<()> {
+        self.cipher = match self.mode {
+            EncryptionMode::Always => Some(FrameCipher::from_shared_secret(shared_secret, salt)?),
+            EncryptionMode::Cfb8 { key, iv } => Some(FrameCipher::from_cfb8_key(key, iv)),
+            EncryptionMode::Never => None,
+        };
+
+        Ok(())
     }
 }
@@ -0,0 +1,99 @@
+//! Consistent Overhead Byte Stuffing for self-synchronizing packet
+//! boundaries over an unframed byte stream.
+//!
+//! Every encoded packet is terminated by a `0x00` delimiter that never
+//! occurs elsewhere in the stuffed data, so a receiver that joins
+//! mid-stream can discard everything up to the next delimiter and resync
+//! on the packet after it.
+
+/// Stuffs `data`, appending the result (including the trailing `0x00`
+/// delimiter) to `out`.
+pub(super) fn encode(data: &[u8], out: &mut Vec<u8>) {
+    let mut code_idx = out.len();
+    out.push(0);
+    let mut code: u8 = 1;
+    let mut pending = true;
+
+    for (i, &byte) in data.iter().enumerate() {
+        if byte == 0 {
+            out[code_idx] = code;
+            code = 1;
+            code_idx = out.len();
+            out.push(0);
+            pending = true;
+        } else {
+            out.push(byte);
+            code += 1;
+
+            // A run of 254 non-zero bytes needs a fresh code group even
+            // without a real zero delimiting it, since a code byte can
+            // only express up to 255 (including itself).
+            if code == 0xFF {
+                out[code_idx] = code;
+                code = 1;
+                if i + 1 < data.len() {
+                    code_idx = out.len();
+                    out.push(0);
+                    pending = true;
+                } else {
+                    // Nothing follows this run, so the reserved slot
+                    // would stay empty; dropping it keeps the encoding
+                    // minimal for inputs whose length is an exact
+                    // multiple of 254.
+                    pending = false;
+                }
+            }
+        }
+    }
+
+    if pending {
+        out[code_idx] = code;
+    }
+    out.push(0);
+}
+
+/// Destuffs a single COBS-encoded packet (without its trailing `0x00`
+/// delimiter), returning the original data.
+///
+/// Fails if `data` is not a well-formed COBS encoding.
+pub(super) fn decode(data: &[u8]) -> Result<Vec<u8>, CobsError> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut rest = data;
+
+    while !rest.is_empty() {
+        let code = rest[0] as usize;
+        if code == 0 {
+            return Err(CobsError);
+        }
+        let run = code - 1;
+
+        rest = &rest[1..];
+        if run > rest.len() {
+            return Err(CobsError);
+        }
+        out.extend_from_slice(&rest[..run]);
+        rest = &rest[run..];
+
+        // A group whose code is below the maximum implies a real zero
+        // byte followed, as long as another group still follows it; a
+        // maximal (0xFF) group never implies one, since it was only cut
+        // short by the length cap, not an actual zero in the data.
+        if code != 0xFF && !rest.is_empty() {
+            out.push(0);
+        }
+    }
+
+    Ok(out)
+}
+
+/// The error returned when destuffing a malformed COBS packet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct CobsError;
+
+impl std::fmt::Display for CobsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("malformed COBS-encoded packet")
+    }
+}
+
+impl std::error::Error for CobsError {}
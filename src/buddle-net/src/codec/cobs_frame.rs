@@ -0,0 +1,68 @@
+use std::io;
+
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use super::{cobs, FrameCodec};
+use crate::frame::Frame;
+
+/// A [`FrameCodec`] wrapper that additionally COBS-stuffs every frame for
+/// transports delivering an unframed byte stream.
+///
+/// Unlike the food-magic length-delimited framing `FrameCodec` uses on its
+/// own, COBS boundaries are self-synchronizing: a receiver that joins
+/// mid-stream, or loses sync after a corrupt read, can simply discard
+/// bytes up to the next `0x00` delimiter and resume from the packet after
+/// it, rather than having to guess where the next valid length header
+/// starts.
+pub struct CobsFrameCodec {
+    inner: FrameCodec,
+}
+
+impl CobsFrameCodec {
+    /// Wraps `inner` with COBS framing.
+    pub const fn new(inner: FrameCodec) -> Self {
+        Self { inner }
+    }
+}
+
+impl Decoder for CobsFrameCodec {
+    type Item = Frame;
+    type Error = io::Error;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(delimiter) = buf.iter().position(|&byte| byte == 0) else {
+            return Ok(None);
+        };
+
+        let packet = buf.split_to(delimiter);
+        buf.advance(1); // Discard the trailing 0x00 delimiter itself.
+
+        let unstuffed = cobs::decode(&packet)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let mut unstuffed = BytesMut::from(&unstuffed[..]);
+        match self.inner.decode(&mut unstuffed)? {
+            Some(frame) => Ok(Some(frame)),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "destuffed COBS packet did not contain a complete frame",
+            )),
+        }
+    }
+}
+
+impl Encoder<Frame> for CobsFrameCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, frame: Frame, buf: &mut BytesMut) -> Result<(), Self::Error> {
+        let mut plain = BytesMut::new();
+        self.inner.encode(frame, &mut plain)?;
+
+        let mut stuffed = Vec::with_capacity(plain.len() + plain.len() / 254 + 2);
+        cobs::encode(&plain, &mut stuffed);
+        buf.extend_from_slice(&stuffed);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+//! zlib (de)compression of data frame payloads.
+
+use std::io::{self, Write};
+
+use flate2::{write::ZlibEncoder, Compression};
+
+/// Deflates `data` into a new buffer.
+pub(super) fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .expect("writing to an in-memory buffer cannot fail");
+    encoder.finish().expect("flushing an in-memory buffer cannot fail")
+}
+
+/// Inflates `data`, failing if it is not a well-formed zlib stream.
+pub(super) fn inflate(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut decoder = flate2::read::ZlibDecoder::new(data);
+    let mut out = Vec::new();
+    io::Read::read_to_end(&mut decoder, &mut out)?;
+    Ok(out)
+}
@@ -4,10 +4,11 @@ use bytes::{Buf, BytesMut};
 use chrono::{TimeZone, Utc};
 use tokio_util::codec::Decoder;
 
-use super::{is_large_frame, Codec, FOOD};
+use super::compression::inflate;
+use super::{is_large_frame, FrameCodec, FOOD};
 use crate::{control::*, frame::Frame};
 
-impl Decoder for Codec {
+impl Decoder for FrameCodec {
     type Item = Frame;
     type Error = io::Error;
 
@@ -46,18 +47,50 @@ impl Decoder for Codec {
         // Read the frame body.
         let is_control_frame = buf.get_u8() != 0;
         let opcode = is_control_frame.then_some(buf.get_u8());
-        buf.get_u16(); // Reserved.
+        let flags = buf.get_u16_le();
 
         // Read the payload depending on the body type.
         if let Some(opcode) = opcode {
             let frame = Frame::Control(read_control_message(buf, opcode)?);
             Ok(Some(frame))
         } else {
-            todo!()
+            self.read_data_frame(buf, flags).map(Some)
         }
     }
 }
 
+impl FrameCodec {
+    /// Reads a data frame's DML header and payload, decrypting and
+    /// inflating it back into its original, isolated message body.
+    fn read_data_frame(&mut self, buf: &mut BytesMut, flags: u16) -> io::Result<Frame> {
+        let service_id = buf.get_u8();
+        let order = buf.get_u8();
+        let payload_size = buf.get_u16_le() as usize;
+
+        let size_bytes = (payload_size as u16).to_le_bytes();
+        let header = [service_id, order, size_bytes[0], size_bytes[1]];
+        let mut wire = buf.split_to(payload_size).to_vec();
+
+        if let Some(cipher) = &mut self.cipher {
+            cipher
+                .open(&header, &mut wire)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+
+        let payload = if flags & Frame::FLAG_COMPRESSED != 0 {
+            inflate(&wire)?
+        } else {
+            wire
+        };
+
+        Ok(Frame::Data {
+            service_id,
+            order,
+            payload: payload.into(),
+        })
+    }
+}
+
 fn read_control_message(buf: &mut BytesMut, opcode: u8) -> io::Result<ControlMessage> {
     match opcode {
         OP_SESSION_OFFER => Ok(read_session_offer(buf)),
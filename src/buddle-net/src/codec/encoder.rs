@@ -4,61 +4,113 @@ use bytes::{BufMut, BytesMut};
 use chrono::{DateTime, Utc};
 use tokio_util::codec::Encoder;
 
-use super::{is_large_frame, Codec, FOOD};
+use super::compression::deflate;
+use super::{is_large_frame, FrameCodec, FrameCipher, FOOD};
 use crate::{control::*, frame::Frame};
 
 const RESERVED: u16 = 0;
 
-impl Encoder<Frame> for Codec {
+impl Encoder<Frame> for FrameCodec {
     type Error = io::Error;
 
     fn encode(&mut self, frame: Frame, buf: &mut BytesMut) -> Result<(), Self::Error> {
-        // Reserve enough space in `buf` so it fits the entire frame.
-        // We optimistically reserve enough memory for a large header.
-        let size = frame.binary_size();
-        buf.reserve(Frame::LARGE_HEADER_SIZE + size);
-
-        // Write the frame header.
-        buf.put_u16_le(FOOD);
-        if is_large_frame(size) {
-            buf.put_u16_le(i16::MAX as u16 + 1);
-            buf.put_u32_le(size as u32);
-        } else {
-            buf.put_u16_le(size as u16);
-        }
-
-        // Write the frame body and the message payload.
-        write_frame_body(buf, frame.opcode());
+        // Build the frame body (and, for data frames, the compressed and
+        // encrypted payload) separately first, since its final size isn't
+        // known upfront for a data frame and must be written into the
+        // outer frame header before the body itself.
+        let mut body = BytesMut::with_capacity(Frame::BODY_SIZE + frame.binary_size());
         match frame {
             Frame::Control(ctrl) => {
+                write_frame_body(&mut body, Some(ctrl.opcode()), 0);
                 match ctrl {
                     ControlMessage::SessionOffer {
                         session_id,
                         datetime,
-                    } => write_session_offer(buf, session_id, datetime),
+                    } => write_session_offer(&mut body, session_id, datetime),
 
                     ControlMessage::ClientKeepAlive(ka)
-                    | ControlMessage::ClientKeepAliveRsp(ka) => write_client_keep_alive(buf, ka),
+                    | ControlMessage::ClientKeepAliveRsp(ka) => write_client_keep_alive(&mut body, ka),
 
                     ControlMessage::ServerKeepAlive(ka)
-                    | ControlMessage::ServerKeepAliveRsp(ka) => write_server_keep_alive(buf, ka),
+                    | ControlMessage::ServerKeepAliveRsp(ka) => write_server_keep_alive(&mut body, ka),
 
                     ControlMessage::SessionAccept {
                         session_id,
                         datetime,
-                    } => write_session_accept(buf, session_id, datetime),
+                    } => write_session_accept(&mut body, session_id, datetime),
                 }
             }
+            Frame::Data {
+                service_id,
+                order,
+                payload,
+            } => self.write_data_frame(&mut body, service_id, order, &payload)?,
         }
 
+        // Write the outer frame header now that the body's final size is
+        // known, followed by the body itself.
+        let size = body.len();
+        buf.reserve(Frame::LARGE_HEADER_SIZE + size);
+        buf.put_u16_le(FOOD);
+        if is_large_frame(size) {
+            buf.put_u16_le(i16::MAX as u16 + 1);
+            buf.put_u32_le(size as u32);
+        } else {
+            buf.put_u16_le(size as u16);
+        }
+        buf.extend_from_slice(&body);
+
         Ok(())
     }
 }
 
-fn write_frame_body(buf: &mut BytesMut, opcode: Option<u8>) {
+impl FrameCodec {
+    /// Writes a data frame's DML header and payload into `body`,
+    /// transparently deflating the payload above the configured
+    /// compression threshold and encrypting it if a [`super::FrameCipher`]
+    /// has been installed.
+    fn write_data_frame(
+        &mut self,
+        body: &mut BytesMut,
+        service_id: u8,
+        order: u8,
+        payload: &[u8],
+    ) -> io::Result<()> {
+        let compressed = self
+            .compression_threshold
+            .is_some_and(|threshold| payload.len() > threshold);
+        let mut wire = if compressed { deflate(payload) } else { payload.to_vec() };
+
+        let mut header = [service_id, order, 0, 0];
+        let len_on_wire = wire.len() + self.cipher.as_ref().map_or(0, FrameCipher::tag_size);
+        header[2..].copy_from_slice(
+            &u16::try_from(len_on_wire)
+                .expect("data frame payload too large to encode")
+                .to_le_bytes(),
+        );
+
+        if let Some(cipher) = &mut self.cipher {
+            cipher
+                .seal(&header, &mut wire)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+
+        write_frame_body(
+            body,
+            None,
+            if compressed { Frame::FLAG_COMPRESSED } else { 0 },
+        );
+        body.extend_from_slice(&header);
+        body.extend_from_slice(&wire);
+
+        Ok(())
+    }
+}
+
+fn write_frame_body(buf: &mut BytesMut, opcode: Option<u8>, flags: u16) {
     buf.put_u8(opcode.is_some() as u8);
     buf.put_u8(opcode.unwrap_or(0));
-    buf.put_u16_le(RESERVED);
+    buf.put_u16_le(flags);
 }
 
 fn write_session_offer(buf: &mut BytesMut, session_id: u16, datetime: DateTime<Utc>) {
@@ -1,7 +1,11 @@
 //! Implementation of control messages used for connection
 //! management.
+//!
+//! The message types here only need `core`/`alloc` plus `chrono`'s date
+//! handling; only the `Utc::now()`-backed convenience constructors
+//! require a wall clock and are gated behind the `std` feature.
 
-use std::{fmt, time::Duration};
+use core::{fmt, time::Duration};
 
 use chrono::{DateTime, Utc};
 
@@ -48,6 +52,7 @@ pub struct ClientKeepAlive {
 impl ClientKeepAlive {
     /// Creates a new client-sided Keep Alive payload
     /// given the raw session details.
+    #[cfg(feature = "std")]
     pub fn new(session_id: u16, session_start: DateTime<Utc>) -> Self {
         let now = Utc::now();
         Self {
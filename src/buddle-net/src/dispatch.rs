@@ -0,0 +1,60 @@
+//! Routing of inbound data frames to per-service handlers.
+
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
+use crate::frame::Frame;
+
+/// A handler for data frames addressed to a particular Service ID.
+///
+/// Implementors typically own a DML `Protocol` for their service and
+/// decode `payload` against it by `order`; this crate has no dependency
+/// on `buddle-dml`, so that decoding happens on the other side of the
+/// trait.
+pub trait DataFrameHandler: Send + Sync {
+    /// Handles a single data frame's `order` and raw `payload`.
+    fn handle(&self, order: u8, payload: Bytes);
+}
+
+/// Routes inbound [`Frame::Data`] frames to the [`DataFrameHandler`]
+/// registered for their `service_id`.
+#[derive(Default)]
+pub struct Dispatcher {
+    handlers: HashMap<u8, Box<dyn DataFrameHandler>>,
+}
+
+impl Dispatcher {
+    /// Creates a new, empty [`Dispatcher`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` to receive data frames addressed to `service_id`.
+    ///
+    /// Replaces any handler previously registered for the same `service_id`.
+    pub fn register(&mut self, service_id: u8, handler: Box<dyn DataFrameHandler>) {
+        self.handlers.insert(service_id, handler);
+    }
+
+    /// Routes `frame` to its registered handler, if any.
+    ///
+    /// Returns whether a handler was found and invoked. Control frames
+    /// and data frames for an unregistered `service_id` are ignored.
+    pub fn dispatch(&self, frame: Frame) -> bool {
+        match frame {
+            Frame::Data {
+                service_id,
+                order,
+                payload,
+            } => match self.handlers.get(&service_id) {
+                Some(handler) => {
+                    handler.handle(order, payload);
+                    true
+                }
+                None => false,
+            },
+            Frame::Control(..) => false,
+        }
+    }
+}
@@ -2,18 +2,42 @@
 //!
 //! Frames either carry game-defined data or generic
 //! control messages for session management.
+//!
+//! Like [`crate::control`], this module only needs `core`/`alloc`; the
+//! constructors that stamp a current timestamp via `Utc::now()` are
+//! gated behind the `std` feature.
 
-use std::fmt;
+use core::fmt;
 
+use bytes::Bytes;
+#[cfg(feature = "std")]
 use chrono::{DateTime, Utc};
 
 use crate::control::{ClientKeepAlive, ControlMessage, ServerKeepAlive};
 
-/// A data frame in the protocol.
+/// A frame exchanged over the protocol.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Frame {
     /// A control message.
     Control(ControlMessage),
+
+    /// A DML message bound for a particular service.
+    Data {
+        /// The Service ID the message is addressed to.
+        service_id: u8,
+        /// The order number identifying the message inside its service.
+        order: u8,
+        /// The isolated, already decompressed and decrypted message body.
+        ///
+        /// Framing-level compression and encryption are transparent to the
+        /// caller; this is the same kind of body a DML `Protocol` would
+        /// read a message out of. It is handed back raw rather than
+        /// decoded into a `Box<dyn Message>` here, since resolving
+        /// `order` against a `Protocol` is a DML-layer concern and
+        /// `buddle-net` has no dependency on `buddle-dml` to call into;
+        /// callers that own a `Protocol` decode the payload themselves.
+        payload: Bytes,
+    },
 }
 
 impl Frame {
@@ -21,20 +45,28 @@ impl Frame {
     pub(crate) const SMALL_HEADER_SIZE: usize = 2 + 2;
     // food + marker + body_size
     pub(crate) const LARGE_HEADER_SIZE: usize = 2 + 2 + 4;
-    // is_control_message + opcode + reserved
+    // is_control_message + opcode + flags
     pub(crate) const BODY_SIZE: usize = 1 + 1 + 2;
     // service_id + order + payload_size
     pub(crate) const DML_HEADER_SIZE: usize = 1 + 1 + 2;
+    // the trailing AEAD tag appended to an encrypted data frame's payload
+    pub(crate) const AEAD_TAG_SIZE: usize = 16;
+
+    // Marks a data frame's payload as zlib-deflated on the wire, set in
+    // the generic body's otherwise-reserved flags field.
+    pub(crate) const FLAG_COMPRESSED: u16 = 1 << 0;
 
     pub(crate) fn binary_size(&self) -> usize {
         match self {
             Self::Control(ctrl) => ctrl.binary_size(),
+            Self::Data { payload, .. } => Self::DML_HEADER_SIZE + payload.len(),
         }
     }
 
     /// Constructs a new *Session Offer* frame given the
     /// required parameters.
     #[inline]
+    #[cfg(feature = "std")]
     pub fn session_offer(session_id: u16) -> Self {
         Self::Control(ControlMessage::SessionOffer {
             session_id,
@@ -45,6 +77,7 @@ impl Frame {
     /// Constructs a new *Session Accept* frame given the
     /// required parameters.
     #[inline]
+    #[cfg(feature = "std")]
     pub fn session_accept(session_id: u16) -> Self {
         Self::Control(ControlMessage::SessionAccept {
             session_id,
@@ -55,6 +88,7 @@ impl Frame {
     /// Constructs a new client-sided *Keep Alive* frame
     /// given the required parameters.
     #[inline]
+    #[cfg(feature = "std")]
     pub fn keep_alive(session_id: u16, session_start: DateTime<Utc>) -> Self {
         Self::Control(ControlMessage::ClientKeepAlive(ClientKeepAlive::new(
             session_id,
@@ -69,11 +103,23 @@ impl Frame {
         Self::Control(ControlMessage::ServerKeepAliveRsp(payload))
     }
 
+    /// Constructs a new data frame carrying a DML message for the given
+    /// `service_id` and `order`.
+    #[inline]
+    pub fn data(service_id: u8, order: u8, payload: Bytes) -> Self {
+        Self::Data {
+            service_id,
+            order,
+            payload,
+        }
+    }
+
     /// Whether this [`Frame`] is a control frame.
     #[inline]
     pub fn is_control(&self) -> bool {
         match self {
             Self::Control(..) => true,
+            Self::Data { .. } => false,
         }
     }
 
@@ -84,6 +130,7 @@ impl Frame {
     pub fn opcode(&self) -> Option<u8> {
         match self {
             Self::Control(ctrl) => Some(ctrl.opcode()),
+            Self::Data { .. } => None,
         }
     }
 
@@ -92,6 +139,17 @@ impl Frame {
     pub fn is_data(&self) -> bool {
         !self.is_control()
     }
+
+    /// Gets the Service ID this [`Frame`] is addressed to.
+    ///
+    /// This method returns [`None`] for control frames.
+    #[inline]
+    pub fn service_id(&self) -> Option<u8> {
+        match self {
+            Self::Control(..) => None,
+            Self::Data { service_id, .. } => Some(*service_id),
+        }
+    }
 }
 
 impl From<ControlMessage> for Frame {
@@ -104,6 +162,9 @@ impl fmt::Display for Frame {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Frame::Control(ctrl) => write!(f, "{ctrl}"),
+            Frame::Data {
+                service_id, order, ..
+            } => write!(f, "Data (service {service_id}, order {order})"),
         }
     }
 }
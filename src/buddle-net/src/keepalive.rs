@@ -0,0 +1,105 @@
+//! Adaptive keep-alive liveness tracking via a TCP-style smoothed
+//! round-trip-time estimate.
+
+use std::time::{Duration, Instant};
+
+/// The floor a computed [`RttEstimator::rto`] is clamped to, mirroring
+/// TCP's retransmission timer granularity floor (RFC 6298).
+pub const MIN_RTO: Duration = Duration::from_secs(1);
+
+/// Tracks round-trip samples for a single in-flight Keep Alive and
+/// maintains a smoothed RTT estimate (`srtt`/`rttvar`) used to derive a
+/// retransmission timeout (`RTO`) and declare a peer dead when no
+/// response arrives within it.
+///
+/// Only one Keep Alive is assumed outstanding at a time, matching how
+/// [`SERVER_HEARTBEAT_INTERVAL`](crate::control::SERVER_HEARTBEAT_INTERVAL)/
+/// [`CLIENT_HEARTBEAT_INTERVAL`](crate::control::CLIENT_HEARTBEAT_INTERVAL)
+/// pace a single periodic Keep Alive per connection. Smoothing follows
+/// RFC 6298: on the first sample `srtt = R` and `rttvar = R/2`; on later
+/// samples `rttvar = 0.75*rttvar + 0.25*|srtt - R|` then
+/// `srtt = 0.875*srtt + 0.125*R`.
+#[derive(Clone, Debug)]
+pub struct RttEstimator {
+    sent_at: Option<Instant>,
+    srtt: Option<Duration>,
+    rttvar: Duration,
+}
+
+impl RttEstimator {
+    /// Creates a new estimator with no samples taken yet.
+    pub fn new() -> Self {
+        Self {
+            sent_at: None,
+            srtt: None,
+            rttvar: Duration::ZERO,
+        }
+    }
+
+    /// Records that a Keep Alive was just sent, starting the clock for
+    /// its matching Rsp.
+    pub fn on_send(&mut self) {
+        self.sent_at = Some(Instant::now());
+    }
+
+    /// Records the matching Keep Alive Rsp's arrival, folding the
+    /// observed round-trip time into the smoothed estimate.
+    ///
+    /// Does nothing if no Keep Alive is currently in flight.
+    pub fn on_response(&mut self) {
+        let Some(sent_at) = self.sent_at.take() else {
+            return;
+        };
+        let sample = sent_at.elapsed();
+
+        self.rttvar = match self.srtt {
+            None => sample / 2,
+            Some(srtt) => {
+                let diff = srtt.max(sample) - srtt.min(sample);
+                (self.rttvar * 3 + diff) / 4
+            }
+        };
+        self.srtt = Some(match self.srtt {
+            None => sample,
+            Some(srtt) => (srtt * 7 + sample) / 8,
+        });
+    }
+
+    /// The current smoothed round-trip time estimate, if any samples
+    /// have been taken yet.
+    pub fn srtt(&self) -> Option<Duration> {
+        self.srtt
+    }
+
+    /// The current retransmission timeout: `srtt + 4 * rttvar`, clamped
+    /// to [`MIN_RTO`]. Falls back to [`MIN_RTO`] before the first sample.
+    pub fn rto(&self) -> Duration {
+        let rto = match self.srtt {
+            Some(srtt) => srtt + self.rttvar * 4,
+            None => MIN_RTO,
+        };
+        rto.max(MIN_RTO)
+    }
+
+    /// Whether the in-flight Keep Alive, if any, has been outstanding
+    /// longer than the current [`RttEstimator::rto`], indicating the
+    /// peer should be considered dead.
+    pub fn is_dead(&self) -> bool {
+        match self.sent_at {
+            Some(sent_at) => sent_at.elapsed() >= self.rto(),
+            None => false,
+        }
+    }
+
+    /// The interval to wait before sending the next Keep Alive: the
+    /// current `srtt` once known, otherwise `default`.
+    pub fn next_interval(&self, default: Duration) -> Duration {
+        self.srtt.unwrap_or(default)
+    }
+}
+
+impl Default for RttEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
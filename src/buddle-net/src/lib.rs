@@ -8,4 +8,8 @@ pub mod codec;
 
 pub mod control;
 
+pub mod dispatch;
+
 pub mod frame;
+
+pub mod keepalive;
@@ -54,15 +54,49 @@ pub struct HeaderString(#[br(parse_with = parse::version)] pub u32);
 #[derive(Clone, Debug, Default, PartialEq, BinRead)]
 pub struct LineString(#[br(parse_with = parse::line_string)] pub String);
 
+/// Downcasts a raw [`NiObject`] block to a concrete or abstract block type.
+///
+/// Implemented for every block type [`Ptr`] and [`Ref`] can be parameterized
+/// over, so their `get` methods can hand back `T` directly instead of
+/// forcing every caller to match the [`NiObject`] enum by hand.
+pub trait FromNiObject: Sized {
+    /// Returns `obj` downcast to `Self`, or [`None`] if `obj` is not
+    /// actually a block of this type.
+    fn from_ni_object(obj: &NiObject) -> Option<&Self>;
+}
+
+/// Returns an iterator over every block in `blocks` that is of type `T`,
+/// for traversals like "all `NiTriShape` children of this node" that only
+/// care about one block type at a time.
+pub fn of_type<T: FromNiObject>(blocks: &[NiObject]) -> impl Iterator<Item = &T> {
+    blocks.iter().filter_map(T::from_ni_object)
+}
+
+/// Error returned by [`Ptr::get`] when the referenced block is not of the
+/// expected type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WrongBlockType;
+
+impl std::fmt::Display for WrongBlockType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("referenced block is not of the expected type")
+    }
+}
+
+impl std::error::Error for WrongBlockType {}
+
 /// A signed 32-bit integer, used to refer to another object.
 #[derive(Clone, Copy, Debug, Default, PartialEq, BinRead)]
 pub struct Ptr<T: 'static>(pub u32, PhantomData<T>);
 
-impl<T: 'static> Ptr<T> {
-    /// Gets the referenced type as a raw [`NiObject`] out of
-    /// the full block list.
-    pub fn get<'b>(&self, blocks: &'b [NiObject]) -> &'b NiObject {
-        &blocks[self.0 as usize]
+impl<T: FromNiObject + 'static> Ptr<T> {
+    /// Gets the referenced type out of the full block list, downcast to
+    /// `T`.
+    ///
+    /// Fails with [`WrongBlockType`] if the referenced block isn't actually
+    /// of type `T`.
+    pub fn get<'b>(&self, blocks: &'b [NiObject]) -> Result<&'b T, WrongBlockType> {
+        T::from_ni_object(&blocks[self.0 as usize]).ok_or(WrongBlockType)
     }
 }
 
@@ -70,10 +104,35 @@ impl<T: 'static> Ptr<T> {
 #[derive(Clone, Copy, Debug, Default, PartialEq, BinRead)]
 pub struct Ref<T: 'static>(pub i32, PhantomData<T>);
 
+impl<T: FromNiObject + 'static> Ref<T> {
+    /// Gets the referenced type out of the full block list, downcast to
+    /// `T`.
+    ///
+    /// Returns [`None`] if this reference is null, or if the referenced
+    /// block isn't actually of type `T`.
+    pub fn get<'b>(&self, blocks: &'b [NiObject]) -> Option<&'b T> {
+        if self.0 < 0 {
+            return None;
+        }
+
+        T::from_ni_object(&blocks[self.0 as usize])
+    }
+
+    /// Like [`get`](Self::get), but turns a missing or wrong-typed
+    /// reference into the given `err` instead of [`None`].
+    pub fn get_or<E>(&self, blocks: &[NiObject], err: E) -> Result<&T, E> {
+        self.get(blocks).ok_or(err)
+    }
+}
+
 impl<T: 'static> Ref<T> {
-    /// Gets the referenced type as a raw [`NiObject`] out of
-    /// the full block list.
-    pub fn get<'b>(&self, blocks: &'b [NiObject]) -> Option<&'b NiObject> {
+    /// Gets the raw [`NiObject`] block this reference points to, without
+    /// downcasting it to `T`.
+    ///
+    /// Useful for references into abstract base types like `NiAVObject`,
+    /// where the concrete block can be any number of subtypes and the
+    /// caller needs to match on [`NiObject`] itself to tell them apart.
+    pub fn raw<'b>(&self, blocks: &'b [NiObject]) -> Option<&'b NiObject> {
         (self.0 >= 0).then(|| &blocks[self.0 as usize])
     }
 }
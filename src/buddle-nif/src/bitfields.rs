@@ -0,0 +1,245 @@
+//! Bit-level reading and writing for the arbitrary-width integer types
+//! ([`buddle_utils::bitint`]) that packed NIF bitfields are stored as.
+//!
+//! [`binrw`]'s [`BinRead`][binrw::BinRead]/[`BinWrite`][binrw::BinWrite]
+//! traits read and write whole bytes at a time and have no notion of a
+//! sub-byte cursor persisting across several field reads. [`BitCursor`]
+//! and [`BitCursorWriter`] supply that: they wrap a reader/writer and
+//! track a partially consumed byte and a bit offset, so several adjacent
+//! bitfields can be read out of (or packed into) the same byte(s)
+//! MSB-first, matching how the game engine packs them.
+//!
+//! A struct with packed bitfields wraps its stream with
+//! `#[br(map_stream = BitCursor::new)]` (or the `BinWrite` equivalent),
+//! then reads each bitfield with one of the `read_*`/`write_*` functions
+//! below via `#[br(parse_with = ...)]`/`#[bw(write_with = ...)]`. Once a
+//! run of bitfields ends, a following whole-byte field realigns the
+//! cursor automatically: an ordinary [`Read`] call through the cursor
+//! discards whatever partial byte is pending first, and an ordinary
+//! [`Write`] call pads the pending partial byte with zero bits and
+//! writes it out first.
+
+use std::io::{Read, Result as IoResult, Seek, SeekFrom, Write};
+
+use binrw::{BinResult, Endian};
+use buddle_utils::bitint::*;
+
+/// Wraps a reader and tracks a partially consumed byte and bit offset,
+/// so bitfields can be read MSB-first across several calls without
+/// losing track of the underlying stream's position.
+pub struct BitCursor<R> {
+    inner: R,
+    byte: u8,
+    bits_left: u8,
+}
+
+impl<R> BitCursor<R> {
+    /// Wraps `inner` in a fresh cursor with no bits buffered yet.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            byte: 0,
+            bits_left: 0,
+        }
+    }
+
+    /// Discards any unread bits of the currently buffered byte, so the
+    /// next read starts at a fresh byte boundary.
+    pub fn align_to_byte(&mut self) {
+        self.bits_left = 0;
+    }
+}
+
+impl<R: Read> BitCursor<R> {
+    /// Reads `width` bits (1..=32) MSB-first, pulling a fresh byte from
+    /// the underlying reader whenever the buffered one runs out.
+    pub fn read_bits(&mut self, width: u8) -> IoResult<u32> {
+        let mut value = 0u32;
+        let mut remaining = width;
+
+        while remaining > 0 {
+            if self.bits_left == 0 {
+                let mut buf = [0u8; 1];
+                self.inner.read_exact(&mut buf)?;
+                self.byte = buf[0];
+                self.bits_left = 8;
+            }
+
+            let take = remaining.min(self.bits_left);
+            let shift = self.bits_left - take;
+            let mask = ((1u16 << take) - 1) as u8;
+            let bits = (self.byte >> shift) & mask;
+
+            value = (value << take) | u32::from(bits);
+            self.bits_left -= take;
+            remaining -= take;
+        }
+
+        Ok(value)
+    }
+}
+
+impl<R: Read> Read for BitCursor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        self.align_to_byte();
+        self.inner.read(buf)
+    }
+}
+
+impl<R: Seek> Seek for BitCursor<R> {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        self.align_to_byte();
+        self.inner.seek(pos)
+    }
+}
+
+/// Wraps a writer and packs bitfields MSB-first into it, mirroring
+/// [`BitCursor`] for the write direction.
+pub struct BitCursorWriter<W> {
+    inner: W,
+    byte: u8,
+    bits_filled: u8,
+}
+
+impl<W> BitCursorWriter<W> {
+    /// Wraps `inner` in a fresh cursor with nothing buffered yet.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            byte: 0,
+            bits_filled: 0,
+        }
+    }
+}
+
+impl<W: Write> BitCursorWriter<W> {
+    /// Packs the low `width` bits (1..=32) of `value` MSB-first,
+    /// flushing completed bytes to the underlying writer as they fill
+    /// up.
+    pub fn write_bits(&mut self, value: u32, width: u8) -> IoResult<()> {
+        let mut remaining = width;
+
+        while remaining > 0 {
+            let space = 8 - self.bits_filled;
+            let take = remaining.min(space);
+            let shift = remaining - take;
+            let bits = ((value >> shift) & ((1u32 << take) - 1)) as u8;
+
+            self.byte |= bits << (space - take);
+            self.bits_filled += take;
+            remaining -= take;
+
+            if self.bits_filled == 8 {
+                self.flush_byte()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pads any partially filled byte with zero bits and writes it out,
+    /// so a following whole-byte field starts cleanly aligned.
+    pub fn align_to_byte(&mut self) -> IoResult<()> {
+        if self.bits_filled > 0 {
+            self.flush_byte()?;
+        }
+
+        Ok(())
+    }
+
+    fn flush_byte(&mut self) -> IoResult<()> {
+        self.inner.write_all(&[self.byte])?;
+        self.byte = 0;
+        self.bits_filled = 0;
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for BitCursorWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.align_to_byte()?;
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.align_to_byte()?;
+        self.inner.flush()
+    }
+}
+
+macro_rules! impl_bit_uint_rw {
+    ($ty:ident, $raw:ident, $read_fn:ident, $write_fn:ident, $bits:expr) => {
+        /// Reads a packed
+        #[doc = concat!("[`", stringify!($ty), "`]")]
+        /// out of a stream already wrapped in a [`BitCursor`].
+        pub fn $read_fn<R: Read + Seek>(
+            reader: &mut BitCursor<R>,
+            _endian: Endian,
+            _args: (),
+        ) -> BinResult<$ty> {
+            let value = reader.read_bits($bits)? as $raw;
+            Ok(<$ty>::new(value))
+        }
+
+        /// Writes a packed
+        #[doc = concat!("[`", stringify!($ty), "`]")]
+        /// to a stream already wrapped in a [`BitCursorWriter`].
+        pub fn $write_fn<W: Write + Seek>(
+            value: &$ty,
+            writer: &mut BitCursorWriter<W>,
+            _endian: Endian,
+            _args: (),
+        ) -> BinResult<()> {
+            writer.write_bits(u32::from(<$raw>::from(*value)), $bits)?;
+            Ok(())
+        }
+    };
+}
+
+macro_rules! impl_bit_int_rw {
+    ($ty:ident, $uraw:ident, $raw:ident, $read_fn:ident, $write_fn:ident, $bits:expr) => {
+        /// Reads a packed
+        #[doc = concat!("[`", stringify!($ty), "`]")]
+        /// out of a stream already wrapped in a [`BitCursor`], recovering
+        /// the sign via [`sign_extend!`].
+        pub fn $read_fn<R: Read + Seek>(
+            reader: &mut BitCursor<R>,
+            _endian: Endian,
+            _args: (),
+        ) -> BinResult<$ty> {
+            let value = reader.read_bits($bits)? as $uraw;
+            Ok(<$ty>::new(sign_extend!($raw, value, $bits)))
+        }
+
+        /// Writes a packed
+        #[doc = concat!("[`", stringify!($ty), "`]")]
+        /// to a stream already wrapped in a [`BitCursorWriter`].
+        pub fn $write_fn<W: Write + Seek>(
+            value: &$ty,
+            writer: &mut BitCursorWriter<W>,
+            _endian: Endian,
+            _args: (),
+        ) -> BinResult<()> {
+            writer.write_bits(<$raw>::from(*value) as $uraw as u32, $bits)?;
+            Ok(())
+        }
+    };
+}
+
+impl_bit_uint_rw!(u1, u8, read_u1, write_u1, 1);
+impl_bit_uint_rw!(u2, u8, read_u2, write_u2, 2);
+impl_bit_uint_rw!(u3, u8, read_u3, write_u3, 3);
+impl_bit_uint_rw!(u4, u8, read_u4, write_u4, 4);
+impl_bit_uint_rw!(u5, u8, read_u5, write_u5, 5);
+impl_bit_uint_rw!(u6, u8, read_u6, write_u6, 6);
+impl_bit_uint_rw!(u7, u8, read_u7, write_u7, 7);
+impl_bit_uint_rw!(u24, u32, read_u24, write_u24, 24);
+
+impl_bit_int_rw!(i1, u8, i8, read_i1, write_i1, 1);
+impl_bit_int_rw!(i2, u8, i8, read_i2, write_i2, 2);
+impl_bit_int_rw!(i3, u8, i8, read_i3, write_i3, 3);
+impl_bit_int_rw!(i4, u8, i8, read_i4, write_i4, 4);
+impl_bit_int_rw!(i5, u8, i8, read_i5, write_i5, 5);
+impl_bit_int_rw!(i6, u8, i8, read_i6, write_i6, 6);
+impl_bit_int_rw!(i7, u8, i8, read_i7, write_i7, 7);
+impl_bit_int_rw!(i24, u32, i32, read_i24, write_i24, 24);
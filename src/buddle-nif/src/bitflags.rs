@@ -20,38 +20,38 @@ bitflags! {
     /// Describes the options for the accum root on NiControllerSequence.
     #[derive(BinRead)]
     pub struct AccumFlags: u32 {
-        const ACCUM_X_TRANS = 0;
-        const ACCUM_Y_TRANS = 1;
-        const ACCUM_Z_TRANS = 2;
-        const ACCUM_X_ROT = 3;
-        const ACCUM_Y_ROT = 4;
-        const ACCUM_Z_ROT = 5;
-        const ACCUM_X_FRONT = 6;
-        const ACCUM_Y_FRONT = 7;
-        const ACCUM_Z_FRONT = 8;
-        const ACCUM_NEG_FRONT = 9;
+        const ACCUM_X_TRANS = 1 << 0;
+        const ACCUM_Y_TRANS = 1 << 1;
+        const ACCUM_Z_TRANS = 1 << 2;
+        const ACCUM_X_ROT = 1 << 3;
+        const ACCUM_Y_ROT = 1 << 4;
+        const ACCUM_Z_ROT = 1 << 5;
+        const ACCUM_X_FRONT = 1 << 6;
+        const ACCUM_Y_FRONT = 1 << 7;
+        const ACCUM_Z_FRONT = 1 << 8;
+        const ACCUM_NEG_FRONT = 1 << 9;
     }
 }
 
 bitflags! {
     #[derive(BinRead)]
     pub struct PathFlags: u16 {
-        const NIPI_CVDataNeedsUpdate = 0;
-        const NIPI_CurveTypeOpen = 1;
-        const NIPI_AllowFlip = 2;
-        const NIPI_Bank = 3;
-        const NIPI_ConstantVelocity = 4;
-        const NIPI_Follow = 5;
-        const NIPI_Flip = 6;
+        const NIPI_CVDataNeedsUpdate = 1 << 0;
+        const NIPI_CurveTypeOpen = 1 << 1;
+        const NIPI_AllowFlip = 1 << 2;
+        const NIPI_Bank = 1 << 3;
+        const NIPI_ConstantVelocity = 1 << 4;
+        const NIPI_Follow = 1 << 5;
+        const NIPI_Flip = 1 << 6;
     }
 }
 
 bitflags! {
     #[derive(BinRead)]
     pub struct LookAtFlags: u16 {
-        const LOOK_FLIP = 0;
-        const LOOK_Y_AXIS = 1;
-        const LOOK_Z_AXIS = 2;
+        const LOOK_FLIP = 1 << 0;
+        const LOOK_Y_AXIS = 1 << 1;
+        const LOOK_Z_AXIS = 1 << 2;
     }
 }
 
@@ -59,85 +59,85 @@ bitflags! {
     /// Flags for NiSwitchNode.
     #[derive(BinRead)]
     pub struct NiSwitchFlags: u16 {
-        const UpdateOnlyActiveChild = 0;
-        const UpdateControllers = 1;
+        const UpdateOnlyActiveChild = 1 << 0;
+        const UpdateControllers = 1 << 1;
     }
 }
 
 bitflags! {
     #[derive(BinRead)]
     pub struct NxBodyFlag: u32 {
-        const NX_BF_DISABLE_GRAVITY = 0;
-        const NX_BF_FROZEN_POS_X = 1;
-        const NX_BF_FROZEN_POS_Y = 2;
-        const NX_BF_FROZEN_POS_Z = 3;
-        const NX_BF_FROZEN_ROT_X = 4;
-        const NX_BF_FROZEN_ROT_Y = 5;
-        const NX_BF_FROZEN_ROT_Z = 6;
-        const NX_BF_KINEMATIC = 7;
-        const NX_BF_VISUALIZATION = 8;
-        const NX_BF_POSE_SLEEP_TEST = 9;
-        const NX_BF_FILTER_SLEEP_VEL = 10;
-        const NX_BF_ENERGY_SLEEP_TEST = 11;
+        const NX_BF_DISABLE_GRAVITY = 1 << 0;
+        const NX_BF_FROZEN_POS_X = 1 << 1;
+        const NX_BF_FROZEN_POS_Y = 1 << 2;
+        const NX_BF_FROZEN_POS_Z = 1 << 3;
+        const NX_BF_FROZEN_ROT_X = 1 << 4;
+        const NX_BF_FROZEN_ROT_Y = 1 << 5;
+        const NX_BF_FROZEN_ROT_Z = 1 << 6;
+        const NX_BF_KINEMATIC = 1 << 7;
+        const NX_BF_VISUALIZATION = 1 << 8;
+        const NX_BF_POSE_SLEEP_TEST = 1 << 9;
+        const NX_BF_FILTER_SLEEP_VEL = 1 << 10;
+        const NX_BF_ENERGY_SLEEP_TEST = 1 << 11;
     }
 }
 
 bitflags! {
     #[derive(BinRead)]
     pub struct NxShapeFlag: u32 {
-        const NX_SF_TRIGGER_ON_ENTER = 0;
-        const NX_SF_TRIGGER_ON_LEAVE = 1;
-        const NX_SF_TRIGGER_ON_STAY = 2;
-        const NX_SF_VISUALIZATION = 3;
-        const NX_SF_DISABLE_COLLISION = 4;
-        const NX_SF_FEATURE_INDICES = 5;
-        const NX_SF_DISABLE_RAYCASTING = 6;
-        const NX_SF_POINT_CONTACT_FORCE = 7;
-        const NX_SF_FLUID_DRAIN = 8;
-        const NX_SF_FLUID_DISABLE_COLLISION = 10;
-        const NX_SF_FLUID_TWOWAY = 11;
-        const NX_SF_DISABLE_RESPONSE = 12;
-        const NX_SF_DYNAMIC_DYNAMIC_CCD = 13;
-        const NX_SF_DISABLE_SCENE_QUERIES = 14;
-        const NX_SF_CLOTH_DRAIN = 15;
-        const NX_SF_CLOTH_DISABLE_COLLISION = 16;
-        const NX_SF_CLOTH_TWOWAY = 17;
-        const NX_SF_SOFTBODY_DRAIN = 18;
-        const NX_SF_SOFTBODY_DISABLE_COLLISION = 19;
-        const NX_SF_SOFTBODY_TWOWAY = 20;
+        const NX_SF_TRIGGER_ON_ENTER = 1 << 0;
+        const NX_SF_TRIGGER_ON_LEAVE = 1 << 1;
+        const NX_SF_TRIGGER_ON_STAY = 1 << 2;
+        const NX_SF_VISUALIZATION = 1 << 3;
+        const NX_SF_DISABLE_COLLISION = 1 << 4;
+        const NX_SF_FEATURE_INDICES = 1 << 5;
+        const NX_SF_DISABLE_RAYCASTING = 1 << 6;
+        const NX_SF_POINT_CONTACT_FORCE = 1 << 7;
+        const NX_SF_FLUID_DRAIN = 1 << 8;
+        const NX_SF_FLUID_DISABLE_COLLISION = 1 << 10;
+        const NX_SF_FLUID_TWOWAY = 1 << 11;
+        const NX_SF_DISABLE_RESPONSE = 1 << 12;
+        const NX_SF_DYNAMIC_DYNAMIC_CCD = 1 << 13;
+        const NX_SF_DISABLE_SCENE_QUERIES = 1 << 14;
+        const NX_SF_CLOTH_DRAIN = 1 << 15;
+        const NX_SF_CLOTH_DISABLE_COLLISION = 1 << 16;
+        const NX_SF_CLOTH_TWOWAY = 1 << 17;
+        const NX_SF_SOFTBODY_DRAIN = 1 << 18;
+        const NX_SF_SOFTBODY_DISABLE_COLLISION = 1 << 19;
+        const NX_SF_SOFTBODY_TWOWAY = 1 << 20;
     }
 }
 
 bitflags! {
     #[derive(BinRead)]
     pub struct NxMaterialFlag: u32 {
-        const NX_MF_ANISOTROPIC = 0;
-        const NX_MF_DISABLE_FRICTION = 4;
-        const NX_MF_DISABLE_STRONG_FRICTION = 5;
+        const NX_MF_ANISOTROPIC = 1 << 0;
+        const NX_MF_DISABLE_FRICTION = 1 << 4;
+        const NX_MF_DISABLE_STRONG_FRICTION = 1 << 5;
     }
 }
 
 bitflags! {
     #[derive(BinRead)]
     pub struct NxClothFlag: u32 {
-        const NX_CLF_PRESSURE = 0;
-        const NX_CLF_STATIC = 1;
-        const NX_CLF_DISABLE_COLLISION = 2;
-        const NX_CLF_SELFCOLLISION = 3;
-        const NX_CLF_VISUALIZATION = 4;
-        const NX_CLF_GRAVITY = 5;
-        const NX_CLF_BENDING = 6;
-        const NX_CLF_BENDING_ORTHO = 7;
-        const NX_CLF_DAMPING = 8;
-        const NX_CLF_COLLISION_TWOWAY = 9;
-        const NX_CLF_TRIANGLE_COLLISION = 11;
-        const NX_CLF_TEARABLE = 12;
-        const NX_CLF_HARDWARE = 13;
-        const NX_CLF_COMDAMPING = 14;
-        const NX_CLF_VALIDBOUNDS = 15;
-        const NX_CLF_FLUID_COLLISION = 16;
-        const NX_CLF_DISABLE_DYNAMIC_CCD = 17;
-        const NX_CLF_ADHERE = 18;
+        const NX_CLF_PRESSURE = 1 << 0;
+        const NX_CLF_STATIC = 1 << 1;
+        const NX_CLF_DISABLE_COLLISION = 1 << 2;
+        const NX_CLF_SELFCOLLISION = 1 << 3;
+        const NX_CLF_VISUALIZATION = 1 << 4;
+        const NX_CLF_GRAVITY = 1 << 5;
+        const NX_CLF_BENDING = 1 << 6;
+        const NX_CLF_BENDING_ORTHO = 1 << 7;
+        const NX_CLF_DAMPING = 1 << 8;
+        const NX_CLF_COLLISION_TWOWAY = 1 << 9;
+        const NX_CLF_TRIANGLE_COLLISION = 1 << 11;
+        const NX_CLF_TEARABLE = 1 << 12;
+        const NX_CLF_HARDWARE = 1 << 13;
+        const NX_CLF_COMDAMPING = 1 << 14;
+        const NX_CLF_VALIDBOUNDS = 1 << 15;
+        const NX_CLF_FLUID_COLLISION = 1 << 16;
+        const NX_CLF_DISABLE_DYNAMIC_CCD = 1 << 17;
+        const NX_CLF_ADHERE = 1 << 18;
     }
 }
 
@@ -145,13 +145,13 @@ bitflags! {
     /// Determines how the data stream is accessed?
     #[derive(BinRead)]
     pub struct DataStreamAccess: u32 {
-        const CPURead = 0;
-        const CPUWriteStatic = 1;
-        const CPUWriteMutable = 2;
-        const CPUWriteVolatile = 3;
-        const GPURead = 4;
-        const GPUWrite = 5;
-        const CPUWriteStaticInititialized = 6;
+        const CPURead = 1 << 0;
+        const CPUWriteStatic = 1 << 1;
+        const CPUWriteMutable = 1 << 2;
+        const CPUWriteVolatile = 1 << 3;
+        const GPURead = 1 << 4;
+        const GPUWrite = 1 << 5;
+        const CPUWriteStaticInititialized = 1 << 6;
     }
 }
 
@@ -162,15 +162,21 @@ bitflags! {
     /// AUTO_CALC_FULL = (AUTO_NEAR_DIST | AUTO_FAR_DIST | AUTO_DIR_LIGHT_FRUSTUM_WIDTH | AUTO_DIR_LIGHT_FRUSTUM_POSITION) = 0x3C0
     #[derive(BinRead)]
     pub struct NiShadowGeneratorFlags: u16 {
-        const DIRTY_SHADOWMAP = 0;
-        const DIRTY_RENDERVIEWS = 1;
-        const GEN_STATIC = 2;
-        const GEN_ACTIVE = 3;
-        const RENDER_BACKFACES = 4;
-        const STRICTLY_OBSERVE_SIZE_HINT = 5;
-        const AUTO_NEAR_DIST = 6;
-        const AUTO_FAR_DIST = 7;
-        const AUTO_DIR_LIGHT_FRUSTUM_WIDTH = 8;
-        const AUTO_DIR_LIGHT_FRUSTUM_POSITION = 9;
+        const DIRTY_SHADOWMAP = 1 << 0;
+        const DIRTY_RENDERVIEWS = 1 << 1;
+        const GEN_STATIC = 1 << 2;
+        const GEN_ACTIVE = 1 << 3;
+        const RENDER_BACKFACES = 1 << 4;
+        const STRICTLY_OBSERVE_SIZE_HINT = 1 << 5;
+        const AUTO_NEAR_DIST = 1 << 6;
+        const AUTO_FAR_DIST = 1 << 7;
+        const AUTO_DIR_LIGHT_FRUSTUM_WIDTH = 1 << 8;
+        const AUTO_DIR_LIGHT_FRUSTUM_POSITION = 1 << 9;
+
+        const AUTO_CALC_NEARFAR = Self::AUTO_NEAR_DIST.bits() | Self::AUTO_FAR_DIST.bits();
+        const AUTO_CALC_FULL = Self::AUTO_NEAR_DIST.bits()
+            | Self::AUTO_FAR_DIST.bits()
+            | Self::AUTO_DIR_LIGHT_FRUSTUM_WIDTH.bits()
+            | Self::AUTO_DIR_LIGHT_FRUSTUM_POSITION.bits();
     }
 }
@@ -23,10 +23,12 @@ use self::objects::NiObject;
 
 mod parse;
 
+pub mod text;
+
 use binrw::BinResult;
 use binrw::{
-    io::{Read, Seek},
-    BinRead, BinReaderExt,
+    io::{Read, Seek, Write},
+    BinRead, BinReaderExt, BinWrite, BinWriterExt,
 };
 pub use half::f16;
 
@@ -39,7 +41,7 @@ const SUPPORTED_VERSIONS: [FileVersion; 5] = [
 ];
 
 /// Representation of a NIF file in all its glory.
-#[derive(Clone, Debug, PartialEq, BinRead)]
+#[derive(Clone, Debug, PartialEq, BinRead, BinWrite)]
 pub struct Nif {
     /// The NIF [`Header`], directly deserialized from the
     /// input source.
@@ -48,6 +50,7 @@ pub struct Nif {
     /// Every [`NiObject`] block encoded in the file, directly
     /// deserialized from the input source.
     #[br(args(&header), parse_with = parse::blocks)]
+    #[bw(args(&self.header), write_with = parse::blocks_write)]
     pub blocks: Vec<NiObject>,
     /// The terminating NIF [`Footer`], directly deserialized
     /// from the input source.
@@ -60,6 +63,21 @@ impl Nif {
         reader.read_le()
     }
 
+    /// Writes this NIF back out to `writer`, byte-for-byte round-tripping
+    /// an unmodified [`Nif`] obtained from [`Nif::parse`].
+    ///
+    /// `header`, `footer`, and every block in `blocks` are written in
+    /// order, with block ordering and inter-block indices (`footer.roots`,
+    /// and every `children`/`properties`/`extra_data` reference) expected
+    /// to already be consistent with `blocks`; this does not renumber or
+    /// reorder anything on write.
+    ///
+    /// Blocked on `Header`, `Footer`, and `NiObject` gaining `BinWrite`
+    /// support (see `parse::blocks_write`); not yet callable.
+    pub fn write<W: Write + Seek>(&self, writer: &mut W) -> BinResult<()> {
+        writer.write_le(self)
+    }
+
     /// Gets a list of the root [`NiObject`] references for this
     /// data tree.
     pub fn root_objects(&self) -> Vec<&NiObject> {
@@ -27,7 +27,7 @@ impl NiObject {
     /// referenced by this block, if any.
     pub fn children<'b>(&self, blocks: &'b [NiObject]) -> Option<Vec<&'b NiObject>> {
         self.child_refs()
-            .map(|refs| refs.iter().filter_map(|r| r.get(blocks)).collect())
+            .map(|refs| refs.iter().filter_map(|r| r.raw(blocks)).collect())
     }
 
     /// Gets the AVObject part of an object, if it exists
@@ -87,7 +87,7 @@ impl NiObject {
     /// this block, if any.
     pub fn properties<'b>(&self, blocks: &'b [NiObject]) -> Option<Vec<&'b NiObject>> {
         self.property_refs()
-            .map(|refs| refs.iter().filter_map(|r| r.get(blocks)).collect())
+            .map(|refs| refs.iter().filter_map(|r| r.raw(blocks)).collect())
     }
 
     /// Gets a list of extra data references stored in this
@@ -164,7 +164,49 @@ impl NiObject {
     /// block, if any.
     pub fn extra_data<'b>(&self, blocks: &'b [NiObject]) -> Option<Vec<&'b NiObject>> {
         self.extra_data_refs()
-            .map(|refs| refs.iter().filter_map(|r| r.get(blocks)).collect())
+            .map(|refs| refs.iter().filter_map(|r| r.raw(blocks)).collect())
+    }
+}
+
+/// Implements [`FromNiObject`] for a concrete block type that corresponds
+/// to exactly one [`NiObject`] variant of the same name.
+macro_rules! impl_from_ni_object {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl FromNiObject for $ty {
+                fn from_ni_object(obj: &NiObject) -> Option<&Self> {
+                    match obj {
+                        NiObject::$ty(block) => Some(block),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_ni_object!(
+    NiNode,
+    NiMesh,
+    NiTriShape,
+    NiTriStrips,
+    NiCamera,
+    NiAlphaProperty,
+    NiTexturingProperty,
+    NiMultiTextureProperty,
+    NiMaterialProperty,
+    NiVertexColorProperty,
+    NiStencilProperty,
+    NiZBufferProperty,
+    NiSourceTexture,
+    NiSourceCubeMap,
+    NiPixelData,
+    NiDataStream,
+);
+
+impl FromNiObject for NiAVObject {
+    fn from_ni_object(obj: &NiObject) -> Option<&Self> {
+        obj.avobject()
     }
 }
 
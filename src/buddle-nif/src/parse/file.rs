@@ -1,8 +1,9 @@
 use binrw::{
-    io::{Read, Seek},
+    io::{Read, Seek, Write},
     BinResult, Endian, Error,
 };
 
+use super::take_seek::take_seek;
 use crate::{compounds::Header, objects::NiObject};
 
 pub fn blocks<R: Read + Seek>(
@@ -13,14 +14,18 @@ pub fn blocks<R: Read + Seek>(
 
     let mut blocks = Vec::with_capacity(header.num_blocks as usize);
 
-    for idx in &header.block_type_index {
+    for (idx, &size) in header.block_type_index.iter().zip(&header.block_sizes) {
         match header.block_types.get(idx.0 as usize) {
-            Some(block) => blocks.push(NiObject::read_options(
-                reader,
-                endian,
-                &block.data,
-                header.version,
-            )?),
+            Some(block) => {
+                let mut bounded = take_seek(&mut *reader, size as u64)?;
+                blocks.push(NiObject::read_options(
+                    &mut bounded,
+                    endian,
+                    &block.data,
+                    header.version,
+                )?);
+                bounded.assert_fully_consumed()?;
+            }
             None => {
                 return Err(Error::Custom {
                     pos: reader.stream_position()?,
@@ -32,3 +37,31 @@ pub fn blocks<R: Read + Seek>(
 
     Ok(blocks)
 }
+
+// TODO: `NiObject` doesn't derive `BinWrite` yet (and has no generated
+// `write_options`/block-type-table counterpart to the reader's dispatch
+// above), so this can't be wired up to `Nif::write` until the generated
+// compound/object definitions grow write support of their own. The
+// shape below is what `blocks` (read) mirrors once that lands: walk
+// `blocks` in the same order `header.block_type_index` lists them in,
+// re-emitting each one with the block type its header entry expects.
+#[allow(dead_code)]
+pub fn blocks_write<W: Write + Seek>(
+    blocks: &Vec<NiObject>,
+    writer: &mut W,
+    endian: Endian,
+    (header,): (&Header,),
+) -> BinResult<()> {
+    for (block, idx) in blocks.iter().zip(&header.block_type_index) {
+        if header.block_types.get(idx.0 as usize).is_none() {
+            return Err(Error::Custom {
+                pos: writer.stream_position()?,
+                err: Box::new("referenced block does not exist in header"),
+            });
+        }
+
+        block.write_options(writer, endian, header.version)?;
+    }
+
+    Ok(())
+}
@@ -3,6 +3,8 @@ use binrw::{
     BinRead, BinResult, Error, Endian,
 };
 
+use super::take_seek::take_seek;
+
 pub(crate) fn line_string_impl<R: Read + Seek>(
     reader: &mut R,
     _: Endian,
@@ -63,13 +65,28 @@ fn read_string_impl<R: Read + Seek>(reader: &mut R, len: usize) -> BinResult<Str
     // Store current stream position for potential later error handling.
     let pos = reader.stream_position()?;
 
+    // Bound reads to exactly `len` bytes, so a corrupt length prefix can't
+    // run past the end of whatever block or field this string is nested
+    // in and pull in unrelated data.
+    let bounded = take_seek(reader, len as u64)?;
+
     // Read all the data we need.
-    let data = reader
+    let data = bounded
         .bytes()
         .take(len)
         .map(|b| b.map(|b| if b == 1 { b'_' } else { b }))
         .collect::<io::Result<Vec<u8>>>()?;
 
+    if data.len() != len {
+        return Err(Error::Custom {
+            pos,
+            err: Box::new(format!(
+                "sized string prefix claimed {len} bytes but only {} were available",
+                data.len()
+            )),
+        });
+    }
+
     // Perform UTF-8 validation and create a Rust string.
     String::from_utf8(data).map_err(|e| Error::Custom {
         pos,
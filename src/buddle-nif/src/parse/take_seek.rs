@@ -0,0 +1,99 @@
+//! A length-bounded [`Read`] + [`Seek`] adapter.
+//!
+//! NIF blocks and their sub-records carry explicit byte lengths that have
+//! to be trusted at parse time; without a guard, a corrupt length can run
+//! a read straight past the end of its block and corrupt the parse of
+//! everything that follows. [`TakeSeek`] clamps reads to `limit` bytes
+//! from wherever the wrapped reader currently sits, while still letting
+//! relative seeks move around inside that window, and reports how many
+//! bytes of the window have been consumed so a caller can assert it used
+//! exactly `limit`.
+
+use binrw::io::{self, Read, Seek, SeekFrom};
+use binrw::{BinResult, Error};
+
+/// Wraps a reader so that at most `limit` bytes can be read starting from
+/// its current position, while still allowing seeks relative to the
+/// start or end of that window.
+///
+/// Construct with [`take_seek`].
+pub struct TakeSeek<R> {
+    inner: R,
+    start: u64,
+    limit: u64,
+}
+
+impl<R: Seek> TakeSeek<R> {
+    fn new(mut inner: R, limit: u64) -> io::Result<Self> {
+        let start = inner.stream_position()?;
+        Ok(Self { inner, start, limit })
+    }
+
+    /// The number of bytes read (or seeked past) from the start of the
+    /// window so far.
+    pub fn consumed(&mut self) -> io::Result<u64> {
+        Ok(self.inner.stream_position()? - self.start)
+    }
+
+    /// The number of bytes left in the window before `limit` is reached.
+    pub fn remaining(&mut self) -> io::Result<u64> {
+        Ok(self.limit - self.consumed()?)
+    }
+
+    /// Asserts that the window was read to exactly `limit` bytes,
+    /// producing a [`binrw::Error::Custom`] at the window's start
+    /// position otherwise.
+    pub fn assert_fully_consumed(&mut self) -> BinResult<()> {
+        let consumed = self.consumed()?;
+        if consumed != self.limit {
+            return Err(Error::Custom {
+                pos: self.start,
+                err: Box::new(format!(
+                    "expected to consume {} bytes but consumed {consumed}",
+                    self.limit
+                )),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Read for TakeSeek<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.remaining()?;
+        let len = (buf.len() as u64).min(remaining) as usize;
+        self.inner.read(&mut buf[..len])
+    }
+}
+
+impl<R: Seek> Seek for TakeSeek<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let overflow = || io::Error::new(io::ErrorKind::InvalidInput, "seek target overflowed");
+
+        let target = match pos {
+            SeekFrom::Start(offset) => self.start.checked_add(offset).ok_or_else(overflow)?,
+            SeekFrom::End(offset) => self
+                .start
+                .checked_add(self.limit)
+                .and_then(|end| end.checked_add_signed(offset))
+                .ok_or_else(overflow)?,
+            SeekFrom::Current(offset) => self
+                .inner
+                .stream_position()?
+                .checked_add_signed(offset)
+                .ok_or_else(overflow)?,
+        };
+
+        let clamped = target.clamp(self.start, self.start + self.limit);
+        let absolute = self.inner.seek(SeekFrom::Start(clamped))?;
+
+        Ok(absolute - self.start)
+    }
+}
+
+/// Wraps `reader` in a [`TakeSeek`] clamped to `limit` bytes from its
+/// current position.
+pub fn take_seek<R: Read + Seek>(reader: R, limit: u64) -> BinResult<TakeSeek<R>> {
+    Ok(TakeSeek::new(reader, limit)?)
+}
@@ -0,0 +1,163 @@
+//! A line-oriented, human-readable textual IR for NIF blocks.
+//!
+//! [`disassemble_data_stream`] turns an already-parsed [`NiDataStream`]
+//! into diffable, patchable text: scalar fields are written as
+//! `key = value`, the raw payload is hex-encoded, and the collection
+//! counts (`num_regions`, `num_components`, `num_bytes`) that the binary
+//! format recomputes on write (see the `#[br(temp)]` fields on
+//! `NiDataStreamTheSadWay`) are written out explicitly.
+//! [`assemble_data_stream`] checks `num_bytes` against the decoded payload
+//! and rejects any non-zero `num_regions` or `num_components`.
+//!
+//! `regions` and `component_formats` are disassembled via their `Debug`
+//! representation, since that's all every compound/enum in this crate is
+//! currently guaranteed to implement. [`assemble_data_stream`] does not
+//! rebuild them, nor `usage` or `cloning_behavior`; `access`,
+//! `streamable`, and `data` round-trip byte-exactly.
+
+use std::fmt::Write as _;
+
+use crate::{bitflags::DataStreamAccess, objects::NiDataStream};
+
+/// An error produced while assembling a [`NiDataStream`] from its text
+/// IR, either because a line was malformed or because it named a count
+/// that didn't match the data following it.
+#[derive(Debug)]
+pub struct TextIrError(String);
+
+impl std::fmt::Display for TextIrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TextIrError {}
+
+fn err(msg: impl Into<String>) -> TextIrError {
+    TextIrError(msg.into())
+}
+
+fn expect_line<'a>(lines: &mut impl Iterator<Item = &'a str>, key: &str) -> Result<&'a str, TextIrError> {
+    let line = lines
+        .next()
+        .ok_or_else(|| err(format!("unexpected end of input, expected `{key}`")))?;
+
+    let value = line
+        .strip_prefix(key)
+        .and_then(|rest| rest.trim_start().strip_prefix('='))
+        .ok_or_else(|| err(format!("expected `{key} = ...`, got `{line}`")))?;
+
+    Ok(value.trim())
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(out, "{byte:02x}");
+    }
+    out
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>, TextIrError> {
+    if text.len() % 2 != 0 {
+        return Err(err("hex string has an odd number of digits"));
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&text[i..i + 2], 16)
+                .map_err(|_| err(format!("invalid hex byte `{}`", &text[i..i + 2])))
+        })
+        .collect()
+}
+
+/// Disassembles a [`NiDataStream`] block into the textual IR described in
+/// the module docs.
+pub fn disassemble_data_stream(block: &NiDataStream) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "usage = {:?}", block.usage);
+    let _ = writeln!(out, "access = {:#010x}", block.access.bits());
+    let _ = writeln!(out, "cloning_behavior = {:?}", block.cloning_behavior);
+    let _ = writeln!(out, "streamable = {}", block.streamable);
+
+    let _ = writeln!(out, "num_regions = {}", block.regions.len());
+    for (idx, region) in block.regions.iter().enumerate() {
+        let _ = writeln!(out, "region[{idx}] = {region:?}");
+    }
+
+    let _ = writeln!(out, "num_components = {}", block.component_formats.len());
+    for (idx, format) in block.component_formats.iter().enumerate() {
+        let _ = writeln!(out, "component[{idx}] = {format:?}");
+    }
+
+    let _ = writeln!(out, "num_bytes = {}", block.data.len());
+    let _ = writeln!(out, "data = {}", encode_hex(&block.data));
+
+    out
+}
+
+/// Assembles `access`, `streamable`, and `data` back out of the textual
+/// IR produced by [`disassemble_data_stream`], returning them alongside
+/// the line iterator positioned right after `data`.
+///
+/// This stops short of rebuilding a full [`NiDataStream`]: `usage`,
+/// `cloning_behavior`, `regions`, and `component_formats` are emitted via
+/// `Debug` above, and none of those types (nor the `enums`/`compounds`
+/// modules that would define them) currently provide a matching parser.
+/// Once they do, this is the function to extend - `usage`/
+/// `cloning_behavior` follow the same `expect_line` shape as `access`
+/// below, and `region[N]`/`component[N]` follow the same indexed-line
+/// shape already used to disassemble them.
+pub fn assemble_data_stream(text: &str) -> Result<(DataStreamAccess, bool, Vec<u8>), TextIrError> {
+    let mut lines = text.lines();
+
+    let _usage = expect_line(&mut lines, "usage")?;
+    let access = expect_line(&mut lines, "access")?;
+    let access = u32::from_str_radix(access.trim_start_matches("0x"), 16)
+        .map_err(|_| err(format!("invalid access bits `{access}`")))?;
+    let access = DataStreamAccess::from_bits_truncate(access);
+
+    let _cloning_behavior = expect_line(&mut lines, "cloning_behavior")?;
+
+    let streamable = expect_line(&mut lines, "streamable")?;
+    let streamable = match streamable {
+        "true" => true,
+        "false" => false,
+        _ => return Err(err(format!("invalid streamable value `{streamable}`"))),
+    };
+
+    let num_regions: usize = expect_line(&mut lines, "num_regions")?
+        .parse()
+        .map_err(|_| err("invalid num_regions"))?;
+    if num_regions != 0 {
+        return Err(err(
+            "assembling `region[N]` entries isn't supported yet; see the \
+             `assemble_data_stream` doc comment",
+        ));
+    }
+
+    let num_components: usize = expect_line(&mut lines, "num_components")?
+        .parse()
+        .map_err(|_| err("invalid num_components"))?;
+    if num_components != 0 {
+        return Err(err(
+            "assembling `component[N]` entries isn't supported yet; see the \
+             `assemble_data_stream` doc comment",
+        ));
+    }
+
+    let num_bytes: usize = expect_line(&mut lines, "num_bytes")?
+        .parse()
+        .map_err(|_| err("invalid num_bytes"))?;
+    let data = decode_hex(expect_line(&mut lines, "data")?)?;
+    if data.len() != num_bytes {
+        return Err(err(format!(
+            "num_bytes said {num_bytes} but data is {} bytes long",
+            data.len()
+        )));
+    }
+
+    Ok((access, streamable, data))
+}
@@ -80,6 +80,22 @@ fn derive_struct(input: ast::Struct<'_>, path: &Path) -> Result<TokenStream> {
     let on_pre_save = input.on_pre_save();
     let on_post_save = input.on_post_save();
 
+    // Link-time registration only makes sense for a concrete type, so
+    // generic `PropertyClass`es are left out of the `TypeRegistry` - there
+    // is no single monomorphization to register ahead of time.
+    let registration = input.generics.params.is_empty().then(|| {
+        quote! {
+            #path::__private::inventory::submit! {
+                #path::registry::TypeRegistration {
+                    list: || match <#ty as #path::type_info::Reflected>::TYPE_INFO {
+                        #path::type_info::TypeInfo::Class(list) => list,
+                        #path::type_info::TypeInfo::Leaf(_) => unreachable!(),
+                    },
+                }
+            }
+        }
+    });
+
     Ok(quote! {
         const _: () = {
             const __PROPERTIES: [#path::type_info::Property; #field_count] = [
@@ -114,11 +130,18 @@ fn derive_struct(input: ast::Struct<'_>, path: &Path) -> Result<TokenStream> {
                     )
                 };
             }
+
+            #registration
         };
 
         impl #impl_generics #path::Type for #ty #ty_generics #where_clause {
             #path::impl_type_methods!(Class);
 
+            #[inline]
+            fn clone_type(&self) -> ::std::boxed::Box<dyn #path::Type> {
+                #path::PropertyClass::deep_clone(self)
+            }
+
             #[inline]
             fn serialize(&mut self, ser: &mut #path::serde::Serializer<'_>) {
                 ser.serialize(self);
@@ -194,6 +217,13 @@ fn derive_enum(input: ast::Enum<'_>, path: &Path) -> Result<TokenStream> {
         impl #impl_generics #path::Type for #ty #ty_generics #where_clause {
             #path::impl_type_methods!(Enum);
 
+            #[inline]
+            fn clone_type(&self) -> ::std::boxed::Box<dyn #path::Type> {
+                ::std::boxed::Box::new(match self {
+                    #(#ty::#idents => #ty::#idents,)*
+                })
+            }
+
             #[inline]
             fn serialize(&mut self, ser: &mut #path::serde::Serializer<'_>) {
                 ser.serialize_enum(self);
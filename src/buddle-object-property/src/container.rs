@@ -8,6 +8,26 @@ pub trait Container: Type {
     /// Returns a mutable reference to an element at the given index.
     fn get_mut(&mut self, idx: usize) -> Option<&mut dyn Type>;
 
+    /// Returns an immutable reference to an element stored under the
+    /// given string key, for map-like containers.
+    ///
+    /// Defaults to [`None`], since ordered containers have no notion of a
+    /// string key - only map types need to override this.
+    fn get_by_key(&self, key: &str) -> Option<&dyn Type> {
+        let _ = key;
+        None
+    }
+
+    /// Returns a mutable reference to an element stored under the given
+    /// string key, for map-like containers.
+    ///
+    /// Defaults to [`None`], since ordered containers have no notion of a
+    /// string key - only map types need to override this.
+    fn get_by_key_mut(&mut self, key: &str) -> Option<&mut dyn Type> {
+        let _ = key;
+        None
+    }
+
     /// Appends a new element to the back of the container.
     ///
     /// # Panics
@@ -16,6 +36,13 @@ pub trait Container: Type {
     /// fails.
     fn push(&mut self, value: Box<dyn Type>);
 
+    /// Appends a freshly default-constructed element to the back of the
+    /// container and returns a mutable reference to it.
+    ///
+    /// Used by generic deserialization to materialize elements in place
+    /// without the caller knowing the container's concrete element type.
+    fn push_default(&mut self) -> &mut dyn Type;
+
     /// Removes an element from the back.
     fn pop(&mut self) -> Option<Box<dyn Type>>;
 
@@ -26,6 +53,18 @@ pub trait Container: Type {
     /// Returns the number of elements inside the container.
     fn len(&self) -> usize;
 
+    /// Indicates whether the container has a fixed length that can never
+    /// grow or shrink, like `[T; N]`.
+    ///
+    /// Generic callers that fill a container from a self-describing source
+    /// (e.g. [`crate::serde_bridge::deserialize_in_place`]) use this to
+    /// decide whether to fill existing slots through [`Container::get_mut`]
+    /// instead of growing the container through [`Container::push_default`].
+    #[inline]
+    fn is_fixed_len(&self) -> bool {
+        false
+    }
+
     /// Indicates if the container is empty.
     #[inline]
     fn is_empty(&self) -> bool {
@@ -88,7 +88,16 @@ impl<T: PropertyClass> Ptr<T> {
     }
 }
 
-// TODO: Clone, Copy traits?
+impl<T: PropertyClass> Clone for Ptr<T> {
+    fn clone(&self) -> Self {
+        // Invariant is met since the clone is derived from `self.value`,
+        // which by invariant is already derived from `T` or `None`.
+        Self {
+            value: self.value.as_deref().map(|v| v.deep_clone()),
+            _t: PhantomData,
+        }
+    }
+}
 
 impl<T: PropertyClass> Default for Ptr<T> {
     fn default() -> Self {
@@ -175,9 +184,36 @@ impl<T: PropertyClass> SharedPtr<T> {
         self.raw_mut()
             .and_then(|v| (v as &mut dyn Type).downcast_mut())
     }
+
+    /// Creates an independent copy of the pointed-to object graph.
+    ///
+    /// Unlike [`Clone::clone`], which shares the existing allocation through
+    /// reference counting, this recursively duplicates the pointee via
+    /// [`PropertyClass::deep_clone`], giving the caller its own, unaliased
+    /// graph with a fresh reference count of one.
+    pub fn deep_clone(&self) -> Self {
+        // Invariant is met since the clone is derived from `self.raw()`,
+        // which by invariant is already derived from `T`.
+        Self {
+            value: Arc::from(self.raw().deep_clone()),
+            _t: PhantomData,
+        }
+    }
 }
 
-// TODO: Clone, Copy traits?
+impl<T: PropertyClass> Clone for SharedPtr<T> {
+    /// Clones the pointer by sharing the existing allocation, i.e. with the
+    /// same reference-counting semantics as cloning an [`Arc`].
+    ///
+    /// Use [`SharedPtr::deep_clone`] instead if an independent copy of the
+    /// pointee is needed.
+    fn clone(&self) -> Self {
+        Self {
+            value: Arc::clone(&self.value),
+            _t: PhantomData,
+        }
+    }
+}
 
 #[derive(Debug)]
 #[repr(transparent)]
@@ -202,3 +238,29 @@ impl<T: PropertyClass> WeakPtr<T> {
         })
     }
 }
+
+impl<T: PropertyClass> Default for WeakPtr<T> {
+    /// Creates a [`WeakPtr`] with no corresponding [`SharedPtr`] keeping a
+    /// value alive, mirroring [`Weak::new`].
+    fn default() -> Self {
+        Self {
+            value: Weak::new(),
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<T: PropertyClass> Clone for WeakPtr<T> {
+    /// Clones the pointer into a dangling weak reference.
+    ///
+    /// A clone must never resurrect a strong reference through the weak
+    /// edge, or duplicating a cyclic object graph would turn the cycle
+    /// into a leak: the clone has no corresponding [`SharedPtr`] keeping
+    /// the pointee alive, so it must not pretend one exists.
+    fn clone(&self) -> Self {
+        Self {
+            value: Weak::new(),
+            _t: PhantomData,
+        }
+    }
+}
@@ -4,7 +4,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::{anyhow, bail};
+use anyhow::anyhow;
 use buddle_math::*;
 use buddle_utils::{bitint::*, color::Color, hash::StringIdBuilder};
 
@@ -68,18 +68,120 @@ macro_rules! impl_type_methods {
             &mut self,
             value: ::std::boxed::Box<dyn $crate::Type>,
         ) -> ::std::result::Result<(), ::std::boxed::Box<dyn $crate::Type>> {
-            *self = *value.downcast()?;
-            ::std::result::Result::Ok(())
+            let value = match value.downcast() {
+                ::std::result::Result::Ok(value) => {
+                    *self = *value;
+                    return ::std::result::Result::Ok(());
+                }
+                ::std::result::Result::Err(value) => value,
+            };
+
+            // The types don't match exactly; fall back to a lossless numeric
+            // conversion (e.g. assigning an `i16` into an `i32` property).
+            // This is a no-op for non-numeric types, which never override
+            // `Type::try_convert`'s default `None`.
+            let target = <Self as $crate::type_info::Reflected>::TYPE_INFO;
+            match $crate::Type::try_convert(&*value, target) {
+                ::std::option::Option::Some(converted) => {
+                    *self = *converted.downcast().unwrap_or_else(|_| {
+                        ::std::unreachable!("Type::try_convert must return the target type")
+                    });
+                    ::std::result::Result::Ok(())
+                }
+                ::std::option::Option::None => ::std::result::Result::Err(value),
+            }
         }
     };
 }
 
+// Shared implementation of `Type::try_convert` for every numeric leaf type
+// below: the built-in integers (`bool`/`f32`/`f64` never call into this) and
+// the bit-sized `uN`/`iN` wrappers from `buddle_utils::bitint`.
+//
+// `value` holds the source's numeric value sign-extended to `i128`, which is
+// wide enough to exactly represent every one of these types. `bits`/`signed`
+// describe the source's own width and signedness, which is all that's needed
+// to decide whether converting into `target` would be lossless:
+//
+// - Converting into one of the built-in integers only ever widens: it must
+//   keep the same signedness and go to an equal or wider bit width.
+// - Converting into a `uN`/`iN` wrapper is range-checked against the actual
+//   value instead, since those types are defined by their bit width rather
+//   than being ordered relative to the built-in integers.
+fn convert_numeric(
+    value: i128,
+    bits: u32,
+    signed: bool,
+    target: &TypeInfo,
+) -> Option<Box<dyn Type>> {
+    macro_rules! native_target {
+        ($ty:ty, $bits:expr, $signed:expr) => {
+            if target.is::<$ty>() {
+                return (signed == $signed && bits <= $bits)
+                    .then(|| Box::new(value as $ty) as Box<dyn Type>);
+            }
+        };
+    }
+
+    macro_rules! bit_uint_target {
+        ($ty:ty, $raw:ty, $bits:expr) => {
+            if target.is::<$ty>() {
+                let max_exclusive = 1i128 << $bits;
+                return (0 <= value && value < max_exclusive)
+                    .then(|| Box::new(<$ty>::new(value as $raw)) as Box<dyn Type>);
+            }
+        };
+    }
+
+    macro_rules! bit_int_target {
+        ($ty:ty, $raw:ty, $bits:expr) => {
+            if target.is::<$ty>() {
+                let half = 1i128 << ($bits - 1);
+                return (-half <= value && value < half)
+                    .then(|| Box::new(<$ty>::new(value as $raw)) as Box<dyn Type>);
+            }
+        };
+    }
+
+    native_target!(u8, 8, false);
+    native_target!(u16, 16, false);
+    native_target!(u32, 32, false);
+    native_target!(u64, 64, false);
+    native_target!(i8, 8, true);
+    native_target!(i16, 16, true);
+    native_target!(i32, 32, true);
+
+    bit_uint_target!(u1, u8, 1);
+    bit_uint_target!(u2, u8, 2);
+    bit_uint_target!(u3, u8, 3);
+    bit_uint_target!(u4, u8, 4);
+    bit_uint_target!(u5, u8, 5);
+    bit_uint_target!(u6, u8, 6);
+    bit_uint_target!(u7, u8, 7);
+    bit_uint_target!(u24, u32, 24);
+
+    bit_int_target!(i1, i8, 1);
+    bit_int_target!(i2, i8, 2);
+    bit_int_target!(i3, i8, 3);
+    bit_int_target!(i4, i8, 4);
+    bit_int_target!(i5, i8, 5);
+    bit_int_target!(i6, i8, 6);
+    bit_int_target!(i7, i8, 7);
+    bit_int_target!(i24, i32, 24);
+
+    None
+}
+
 macro_rules! impl_primitive {
     ($ty:ident, $name:expr) => {
         impl_leaf_info_for!($ty, $name);
         impl Type for $ty {
             impl_type_methods!(Value);
 
+            fn clone_type(&self) -> Box<dyn Type> {
+                Box::new(*self)
+            }
+
             fn serialize(&mut self, ser: &mut Serializer<'_>) {
                 ser.writer().$ty(*self);
             }
@@ -90,18 +192,44 @@ macro_rules! impl_primitive {
             }
         }
     };
+
+    // Built-in integer types additionally participate in lossless numeric
+    // conversion; `bool`/`f32`/`f64` above don't and keep the default `None`.
+    ($ty:ident, $name:expr, $bits:expr, $signed:expr) => {
+        impl_leaf_info_for!($ty, $name);
+        impl Type for $ty {
+            impl_type_methods!(Value);
+
+            fn clone_type(&self) -> Box<dyn Type> {
+                Box::new(*self)
+            }
+
+            fn serialize(&mut self, ser: &mut Serializer<'_>) {
+                ser.writer().$ty(*self);
+            }
+
+            fn deserialize(&mut self, de: &mut Deserializer<'_>) -> anyhow::Result<()> {
+                *self = de.reader().$ty()?;
+                Ok(())
+            }
+
+            fn try_convert(&self, target: &TypeInfo) -> Option<Box<dyn Type>> {
+                convert_numeric(*self as i128, $bits, $signed, target)
+            }
+        }
+    };
 }
 
 impl_primitive!(bool, "bool");
 
-impl_primitive!(i8, "char");
-impl_primitive!(i16, "short");
-impl_primitive!(i32, "int");
+impl_primitive!(i8, "char", 8, true);
+impl_primitive!(i16, "short", 16, true);
+impl_primitive!(i32, "int", 32, true);
 
-impl_primitive!(u8, "unsigned char");
-impl_primitive!(u16, "unsigned short");
-impl_primitive!(u32, "unsigned int");
-impl_primitive!(u64, "unsigned __int64");
+impl_primitive!(u8, "unsigned char", 8, false);
+impl_primitive!(u16, "unsigned short", 16, false);
+impl_primitive!(u32, "unsigned int", 32, false);
+impl_primitive!(u64, "unsigned __int64", 64, false);
 
 impl_primitive!(f32, "float");
 impl_primitive!(f64, "double");
@@ -110,6 +238,10 @@ impl_leaf_info_for!(RawString, "std::string");
 impl Type for RawString {
     impl_type_methods!(Value);
 
+    fn clone_type(&self) -> Box<dyn Type> {
+        Box::new(self.clone())
+    }
+
     fn serialize(&mut self, ser: &mut Serializer<'_>) {
         ser.write_str(&self.0);
     }
@@ -124,6 +256,10 @@ impl_leaf_info_for!(RawWideString, "std::wstring");
 impl Type for RawWideString {
     impl_type_methods!(Value);
 
+    fn clone_type(&self) -> Box<dyn Type> {
+        Box::new(self.clone())
+    }
+
     fn serialize(&mut self, ser: &mut Serializer<'_>) {
         ser.write_wstr(&self.0);
     }
@@ -149,6 +285,22 @@ macro_rules! impl_container {
         impl<T: Default + Reflected + Type> Type for $ty {
             impl_type_methods!(Container);
 
+            fn clone_type(&self) -> Box<dyn Type> {
+                let mut new = <$ty>::default();
+
+                for value in self {
+                    let cloned = value.clone_type();
+                    <$ty>::$push(
+                        &mut new,
+                        *cloned.downcast().unwrap_or_else(|_| {
+                            unreachable!("Type::clone_type must preserve the concrete type")
+                        }),
+                    );
+                }
+
+                Box::new(new)
+            }
+
             fn serialize(&mut self, ser: &mut Serializer<'_>) {
                 for value in self {
                     value.serialize(ser);
@@ -161,14 +313,17 @@ macro_rules! impl_container {
                 self.clear();
                 self.reserve(len);
 
-                (0..len).try_for_each(|_| {
-                    let mut new = T::default();
-
-                    new.deserialize(de)?;
-                    <$ty>::$push(self, new);
+                // Deserialize every element in its final container slot
+                // rather than on the stack and `push`ing it afterwards:
+                // some `Type::deserialize` implementations (e.g. `WeakPtr`)
+                // stash a pointer into the value being filled for later
+                // fixup, which would otherwise dangle once the element
+                // moved into the container.
+                for _ in 0..len {
+                    Container::push_default(self).deserialize(de)?;
+                }
 
-                    Ok(())
-                })
+                Ok(())
             }
         }
 
@@ -193,6 +348,12 @@ macro_rules! impl_container {
                 )
             }
 
+            fn push_default(&mut self) -> &mut dyn Type {
+                <$ty>::$push(self, T::default());
+                let idx = Container::len(self) - 1;
+                Container::get_mut(self, idx).expect("element was just pushed")
+            }
+
             fn pop(&mut self) -> Option<Box<dyn Type>> {
                 <$ty>::$pop(self).map(|e| Box::new(e) as Box<dyn Type>)
             }
@@ -215,12 +376,96 @@ macro_rules! impl_container {
 impl_container!(Vec<T>, [T], push, pop);
 impl_container!(VecDeque<T>, Self, push_back, pop_back);
 
+// Fixed-length arrays can't be modeled with `impl_container!` above: they have
+// no length prefix on the wire (the length is already known from `N`), and
+// they can't grow or shrink, so `push`/`pop`/`reserve` don't carry their
+// usual `Vec`/`VecDeque` meaning.
+unsafe impl<T: Default + Reflected + Type, const N: usize> Reflected for [T; N] {
+    const TYPE_NAME: &'static str = T::TYPE_NAME;
+
+    const TYPE_INFO: &'static TypeInfo = &TypeInfo::Leaf(ValueInfo {
+        type_name: Self::TYPE_NAME,
+        type_hash: T::TYPE_INFO.type_hash(),
+        type_id: TypeId::of::<Self>(),
+    });
+}
+
+impl<T: Default + Reflected + Type, const N: usize> Type for [T; N] {
+    impl_type_methods!(Container);
+
+    fn clone_type(&self) -> Box<dyn Type> {
+        Box::new(std::array::from_fn::<T, N, _>(|idx| {
+            *self[idx].clone_type().downcast().unwrap_or_else(|_| {
+                unreachable!("Type::clone_type must preserve the concrete type")
+            })
+        }))
+    }
+
+    fn serialize(&mut self, ser: &mut Serializer<'_>) {
+        for value in self {
+            value.serialize(ser);
+        }
+    }
+
+    fn deserialize(&mut self, de: &mut Deserializer<'_>) -> anyhow::Result<()> {
+        // No length prefix to read: `N` is already known statically, and all
+        // `N` slots are filled in place without touching the array's layout.
+        for value in self {
+            value.deserialize(de)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Default + Reflected + Type, const N: usize> Container for [T; N] {
+    fn get(&self, idx: usize) -> Option<&dyn Type> {
+        <[T]>::get(self, idx).map(|e| e as &dyn Type)
+    }
+
+    fn get_mut(&mut self, idx: usize) -> Option<&mut dyn Type> {
+        <[T]>::get_mut(self, idx).map(|e| e as &mut dyn Type)
+    }
+
+    fn push(&mut self, _value: Box<dyn Type>) {
+        panic!("cannot push onto a fixed-size array container");
+    }
+
+    fn push_default(&mut self) -> &mut dyn Type {
+        panic!("cannot push onto a fixed-size array container");
+    }
+
+    fn pop(&mut self) -> Option<Box<dyn Type>> {
+        panic!("cannot pop from a fixed-size array container");
+    }
+
+    fn reserve(&mut self, _capacity: usize) {
+        // No-op: the array's storage is already `N` elements wide.
+    }
+
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn is_fixed_len(&self) -> bool {
+        true
+    }
+
+    fn iter(&self) -> ContainerIter<'_> {
+        ContainerIter::new(self)
+    }
+}
+
 macro_rules! impl_simple {
     (@non_generic $ty:ident, $name:expr, $($idents:ident),* $(,)?) => {
         impl_leaf_info_for!($ty, $name);
         impl Type for $ty {
             impl_type_methods!(Value);
 
+            fn clone_type(&self) -> Box<dyn Type> {
+                Box::new(*self)
+            }
+
             fn serialize(&mut self, ser: &mut Serializer<'_>) {
                 $(
                     self.$idents.serialize(ser);
@@ -255,6 +500,16 @@ macro_rules! impl_simple {
         impl<T: Reflected + Type> Type for $ty {
             impl_type_methods!(Value);
 
+            fn clone_type(&self) -> Box<dyn Type> {
+                Box::new(Self {
+                    $(
+                        $idents: *self.$idents.clone_type().downcast().unwrap_or_else(|_| {
+                            unreachable!("Type::clone_type must preserve the concrete type")
+                        }),
+                    )*
+                })
+            }
+
             fn serialize(&mut self, ser: &mut Serializer<'_>) {
                 $(
                     self.$idents.serialize(ser);
@@ -292,6 +547,10 @@ macro_rules! impl_bit_uint {
         impl Type for $ty {
             impl_type_methods!(Value);
 
+            fn clone_type(&self) -> Box<dyn Type> {
+                Box::new(*self)
+            }
+
             fn serialize(&mut self, ser: &mut Serializer<'_>) {
                 ser.writer().write_bitint(<$raw>::from(*self), $bits);
             }
@@ -302,6 +561,10 @@ macro_rules! impl_bit_uint {
 
                 Ok(())
             }
+
+            fn try_convert(&self, target: &TypeInfo) -> Option<Box<dyn Type>> {
+                convert_numeric(<$raw>::from(*self) as i128, $bits, false, target)
+            }
         }
     };
 }
@@ -312,6 +575,10 @@ macro_rules! impl_bit_int {
         impl Type for $ty {
             impl_type_methods!(Value);
 
+            fn clone_type(&self) -> Box<dyn Type> {
+                Box::new(*self)
+            }
+
             fn serialize(&mut self, ser: &mut Serializer<'_>) {
                 ser.writer().write_bitint(<$raw>::from(*self), $bits);
             }
@@ -322,6 +589,10 @@ macro_rules! impl_bit_int {
 
                 Ok(())
             }
+
+            fn try_convert(&self, target: &TypeInfo) -> Option<Box<dyn Type>> {
+                convert_numeric(<$raw>::from(*self) as i128, $bits, true, target)
+            }
         }
     };
 }
@@ -330,6 +601,10 @@ impl_leaf_info_for!(u1, "bui1");
 impl Type for u1 {
     impl_type_methods!(Value);
 
+    fn clone_type(&self) -> Box<dyn Type> {
+        Box::new(*self)
+    }
+
     fn serialize(&mut self, ser: &mut Serializer<'_>) {
         const ZERO: u1 = u1::new(0);
         ser.writer().bool(*self != ZERO);
@@ -341,6 +616,10 @@ impl Type for u1 {
 
         Ok(())
     }
+
+    fn try_convert(&self, target: &TypeInfo) -> Option<Box<dyn Type>> {
+        convert_numeric(u8::from(*self) as i128, 1, false, target)
+    }
 }
 
 impl_bit_uint!(u2, "bui2", u8, 2);
@@ -355,6 +634,10 @@ impl_leaf_info_for!(i1, "bi1");
 impl Type for i1 {
     impl_type_methods!(Value);
 
+    fn clone_type(&self) -> Box<dyn Type> {
+        Box::new(*self)
+    }
+
     fn serialize(&mut self, ser: &mut Serializer<'_>) {
         const ZERO: i1 = i1::new(0);
         ser.writer().bool(*self != ZERO);
@@ -366,6 +649,10 @@ impl Type for i1 {
 
         Ok(())
     }
+
+    fn try_convert(&self, target: &TypeInfo) -> Option<Box<dyn Type>> {
+        convert_numeric(i8::from(*self) as i128, 1, true, target)
+    }
 }
 
 impl_bit_int!(i2, "bi2", u8, i8, 2);
@@ -430,6 +717,10 @@ impl<T: Reflected + PropertyClass> Type for Ptr<T> {
         }
     }
 
+    fn clone_type(&self) -> Box<dyn Type> {
+        Box::new(self.clone())
+    }
+
     fn serialize(&mut self, ser: &mut Serializer<'_>) {
         ser.try_serialize(self.raw_mut());
     }
@@ -502,13 +793,17 @@ impl<T: Reflected + PropertyClass> Type for SharedPtr<T> {
         }
     }
 
+    fn clone_type(&self) -> Box<dyn Type> {
+        Box::new(self.clone())
+    }
+
     fn serialize(&mut self, ser: &mut Serializer<'_>) {
-        ser.try_serialize(self.raw_mut());
+        ser.serialize_shared(&mut self.value);
     }
 
     fn deserialize(&mut self, de: &mut Deserializer<'_>) -> anyhow::Result<()> {
-        let value = de.deserialize()?;
-        *self = Self::try_new(Arc::from(value))
+        let value = de.deserialize_shared()?;
+        *self = Self::try_new(value)
             .map_err(|v| anyhow!("received incompatible type: {}", v.type_info().type_name()))?;
 
         Ok(())
@@ -555,11 +850,15 @@ impl<T: Reflected + PropertyClass> Type for WeakPtr<T> {
         Ok(())
     }
 
-    fn serialize(&mut self, _: &mut Serializer<'_>) {
-        // Serialization is unsupported, so we do nothing.
+    fn clone_type(&self) -> Box<dyn Type> {
+        Box::new(self.clone())
     }
 
-    fn deserialize(&mut self, _: &mut Deserializer<'_>) -> anyhow::Result<()> {
-        bail!("Deserialization of weak pointers is not supported");
+    fn serialize(&mut self, ser: &mut Serializer<'_>) {
+        ser.serialize_weak(&self.value);
+    }
+
+    fn deserialize(&mut self, de: &mut Deserializer<'_>) -> anyhow::Result<()> {
+        de.deserialize_weak(&mut self.value)
     }
 }
@@ -47,17 +47,26 @@ pub mod path;
 mod property_class;
 pub use self::property_class::*;
 
+pub mod registry;
+pub use self::registry::TypeRegistry;
+
 pub mod serde;
 
+mod serde_bridge;
+pub use self::serde_bridge::{deserialize_in_place, SerializeType};
+
 pub mod type_info;
 
 mod r#type;
 pub use self::r#type::*;
 
+pub mod visitor;
+
 #[doc(hidden)]
 pub mod __private {
     pub use anyhow::Result;
     pub use bitflags::bitflags;
+    pub use inventory;
 
     /// Wrapper around [`std::any::type_name`] for codegen.
     ///
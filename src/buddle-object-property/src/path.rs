@@ -38,6 +38,41 @@ pub trait PathAccess: Type {
                 .ok_or_else(|| anyhow::anyhow!("cannot downcast path element into incorrect type"))
         })
     }
+
+    /// Resolves `path` with [`PathAccess::path_mut`] and assigns `value`
+    /// to the target, failing with a descriptive error instead of
+    /// writing if the target does not hold a `T`.
+    fn set_path<T: Type>(&mut self, path: &str, value: T) -> anyhow::Result<()> {
+        let target = self.path_mut(path)?;
+        let target_name = target.type_info().type_name();
+
+        target.set(Box::new(value)).map_err(|value| {
+            anyhow::anyhow!(
+                "cannot assign a value of type `{}` to path `{path}`, which holds a `{target_name}`",
+                value.type_info().type_name(),
+            )
+        })
+    }
+
+    /// Resolves `path` with [`PathAccess::path`], panicking instead of
+    /// returning a [`Result`] when it cannot be resolved.
+    ///
+    /// Prefer [`PathAccess::path`] unless the path is already known to be
+    /// valid, e.g. when it is hardcoded rather than user-supplied.
+    #[track_caller]
+    fn at(&self, path: &str) -> &dyn Type {
+        self.path(path).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Resolves `path` with [`PathAccess::path_mut`], panicking instead
+    /// of returning a [`Result`] when it cannot be resolved.
+    ///
+    /// Prefer [`PathAccess::path_mut`] unless the path is already known
+    /// to be valid, e.g. when it is hardcoded rather than user-supplied.
+    #[track_caller]
+    fn at_mut(&mut self, path: &str) -> &mut dyn Type {
+        self.path_mut(path).unwrap_or_else(|e| panic!("{e}"))
+    }
 }
 
 impl PathAccess for dyn Type {
@@ -173,8 +208,8 @@ fn access_field<'t>(
     };
 
     let list = cls.property_list();
-    list.property(ident)
-        .map(|view| cls.property(view))
+    list.property_recursive(cls, ident)
+        .map(|(owner, view)| owner.property(view))
         .ok_or_else(|| {
             anyhow::anyhow!("value at depth {depth:?} does not have a field named {ident}")
         })
@@ -185,17 +220,24 @@ fn access_field_mut<'t>(
     ident: &str,
     depth: usize,
 ) -> anyhow::Result<&'t mut dyn Type> {
-    let cls = match value.type_mut() {
+    let mut cls = match value.type_mut() {
         TypeMut::Class(value) => value,
         _ => anyhow::bail!("expected structure at depth {depth:?}"),
     };
 
-    let list = cls.property_list();
-    list.property(ident)
-        .map(|view| cls.property_mut(view))
-        .ok_or_else(|| {
+    // Mirrors `PropertyList::property_recursive`, but walking through
+    // `base_value_mut` instead, since a mutable path can't be handed back
+    // as a reusable `PropertyAccess` pair the way the immutable one is.
+    loop {
+        let list = cls.property_list();
+        if let Some(view) = list.property(ident) {
+            return Ok(cls.property_mut(view));
+        }
+
+        cls = list.base_value_mut(cls).ok_or_else(|| {
             anyhow::anyhow!("value at depth {depth:?} does not have a field named {ident}")
-        })
+        })?;
+    }
 }
 
 fn access_container<'t>(
@@ -208,10 +250,16 @@ fn access_container<'t>(
         _ => anyhow::bail!("expected container to index into at depth {depth:?}"),
     };
 
-    let index = index.parse()?;
-    container
-        .get(index)
-        .ok_or_else(|| anyhow::anyhow!("container at depth {depth:?} has vacant index {index:?}"))
+    // Integer indices address ordered containers; anything else is tried
+    // as a string key into a map-like container.
+    match index.parse::<usize>() {
+        Ok(index) => container.get(index).ok_or_else(|| {
+            anyhow::anyhow!("container at depth {depth:?} has vacant index {index:?}")
+        }),
+        Err(_) => container.get_by_key(index).ok_or_else(|| {
+            anyhow::anyhow!("container at depth {depth:?} has no element keyed {index:?}")
+        }),
+    }
 }
 
 fn access_container_mut<'t>(
@@ -224,10 +272,14 @@ fn access_container_mut<'t>(
         _ => anyhow::bail!("expected container to index into at depth {depth:?}"),
     };
 
-    let index = index.parse()?;
-    container
-        .get_mut(index)
-        .ok_or_else(|| anyhow::anyhow!("container at depth {depth:?} has vacant index {index:?}"))
+    match index.parse::<usize>() {
+        Ok(index) => container.get_mut(index).ok_or_else(|| {
+            anyhow::anyhow!("container at depth {depth:?} has vacant index {index:?}")
+        }),
+        Err(_) => container.get_by_key_mut(index).ok_or_else(|| {
+            anyhow::anyhow!("container at depth {depth:?} has no element keyed {index:?}")
+        }),
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -270,6 +322,25 @@ impl<'p> Lexer<'p> {
             '.' => Token::Dot,
             '[' => Token::LBracket,
             ']' => Token::RBracket,
+            '"' => {
+                // A quoted ident, e.g. the `"with.dots"` in
+                // `container["with.dots"]`: consume up to the matching
+                // quote instead of breaking on '.'/'['/']', so a string
+                // key that happens to contain those characters still
+                // lexes as a single ident.
+                let ident_start = pos;
+                let mut ident_end = pos;
+
+                for ch in self.chars.by_ref() {
+                    pos += 1;
+                    if ch == '"' {
+                        break;
+                    }
+                    ident_end = pos;
+                }
+
+                Token::Ident(&self.path[ident_start..ident_end])
+            }
             _ => {
                 // We don't have much error handling to do, fortunately. So we
                 // assume this is an ident, count chars until the next type of
@@ -1,7 +1,9 @@
 use std::any::TypeId;
 
 use crate::{
-    r#type::Type,
+    container::Container,
+    r#enum::Enum,
+    r#type::{Type, TypeMut, TypeRef},
     type_info::{PropertyAccess, PropertyList, TypeInfo},
 };
 
@@ -88,6 +90,21 @@ pub trait PropertyClass: Type {
     /// Gets the base [`PropertyClass`] for this object, if one exists.
     fn base_mut(&mut self) -> Option<&mut dyn PropertyClass>;
 
+    /// Recursively duplicates the reflected object graph rooted at `self`
+    /// into an independent, freshly allocated instance of the same
+    /// concrete type.
+    ///
+    /// This walks the [`PropertyList`] (including the base class chain, if
+    /// any) and copies every property over: nested [`PropertyClass`]
+    /// properties recurse, [`Container`] properties are cloned
+    /// element-by-element, [`Enum`] properties copy their discriminant, and
+    /// leaf values are duplicated through [`Type::clone_type`].
+    fn deep_clone(&self) -> Box<dyn PropertyClass> {
+        let mut new = self.property_list().make_default();
+        clone_properties(self, new.as_mut());
+        new
+    }
+
     /// Implementation-specific behavior for a class before it is serialized.
     fn on_pre_save(&mut self);
 
@@ -101,6 +118,48 @@ pub trait PropertyClass: Type {
     fn on_post_load(&mut self);
 }
 
+// Recursively copies every property from `src` into `dst`, which must be
+// a default-initialized instance of the same concrete type.
+fn clone_properties(src: &dyn PropertyClass, dst: &mut dyn PropertyClass) {
+    if let (Some(src_base), Some(dst_base)) = (src.base(), dst.base_mut()) {
+        clone_properties(src_base, dst_base);
+    }
+
+    for view in src.property_list().iter_properties() {
+        let src_value = src.property(view);
+        let dst_value = dst.property_mut(view);
+
+        match src_value.type_ref() {
+            TypeRef::Class(src_class) => {
+                if let TypeMut::Class(dst_class) = dst_value.type_mut() {
+                    clone_properties(src_class, dst_class);
+                }
+            }
+
+            TypeRef::Container(src_container) => {
+                if let TypeMut::Container(dst_container) = dst_value.type_mut() {
+                    dst_container.reserve(src_container.len());
+                    for element in src_container.iter() {
+                        dst_container.push(element.clone_type());
+                    }
+                }
+            }
+
+            TypeRef::Enum(src_enum) => {
+                if let TypeMut::Enum(dst_enum) = dst_value.type_mut() {
+                    dst_enum.update_value(src_enum.value());
+                }
+            }
+
+            TypeRef::Value(_) => {
+                dst_value
+                    .set(src_value.clone_type())
+                    .unwrap_or_else(|_| unreachable!("src and dst properties must share a type"));
+            }
+        }
+    }
+}
+
 /// Extension trait to [`PropertyClass`]es which provides shortcuts for
 /// downcasting and accessing bases.
 pub trait PropertyClassExt: PropertyClass {
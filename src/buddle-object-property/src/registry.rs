@@ -1,60 +1,127 @@
+//! A link-time populated registry resolving [`PropertyClass`] identities.
+//!
+//! Every `#[derive(Type)]`-generated [`PropertyClass`] submits a
+//! [`TypeRegistration`] via [`inventory`], so the [`TypeRegistry`] can be
+//! built lazily from whatever is linked into the binary instead of
+//! requiring manual registration calls.
+
 use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::serde::{Deserializer, Serializer, TypeTag};
+use crate::type_info::PropertyList;
+use crate::PropertyClass;
 
+/// A single type's registration record, collected at link time.
+///
+/// This is emitted by the `#[derive(Type)]` macro for every
+/// [`PropertyClass`]; it should not be constructed by hand.
+pub struct TypeRegistration {
+    /// Gets the [`PropertyList`] of the registered type.
+    pub list: fn() -> &'static PropertyList,
+}
 
-use crate::serde::TypeTag;
-use crate::type_info::{PropertyList, Reflected, TypeInfo::Class};
+inventory::collect!(TypeRegistration);
 
-pub struct Registry {
-    registry: HashMap<u32, &'static PropertyList>
+/// A process-wide registry resolving [`PropertyClass`] identities - by
+/// name hash or by name - to their [`PropertyList`].
+///
+/// The registry is populated automatically on first access from every
+/// [`TypeRegistration`] linked into the binary; there is no manual
+/// registration step.
+pub struct TypeRegistry {
+    by_hash: HashMap<u32, &'static PropertyList>,
+    by_name: HashMap<&'static str, &'static PropertyList>,
 }
 
-impl Registry {
-    pub fn register<T: Reflected>(&mut self) {
-        match T::TYPE_INFO {
-            Class(list) => {
-                self.registry.insert(list.type_hash(), list);
+impl TypeRegistry {
+    /// Gets the process-wide [`TypeRegistry`], building it from every
+    /// linked [`TypeRegistration`] on first access.
+    pub fn global() -> &'static Self {
+        static REGISTRY: OnceLock<TypeRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(Self::build)
+    }
+
+    fn build() -> Self {
+        let mut by_hash = HashMap::new();
+        let mut by_name = HashMap::new();
+
+        for registration in inventory::iter::<TypeRegistration> {
+            let list = (registration.list)();
+
+            if let Some(existing) = by_hash.insert(list.type_hash(), list) {
+                panic!(
+                    "type hash collision between '{}' and '{}' (hash {:#010x}); \
+                     ObjectProperty identities must be unique",
+                    existing.type_name(),
+                    list.type_name(),
+                    list.type_hash(),
+                );
             }
-            _ => panic!("Expected Class not leaf")
+
+            by_name.insert(list.type_name(), list);
         }
+
+        Self { by_hash, by_name }
+    }
+
+    /// Resolves a type's name hash to its [`PropertyList`], for binary
+    /// formats that identify objects by hash on the wire.
+    pub fn resolve(&self, hash: u32) -> Option<&'static PropertyList> {
+        self.by_hash.get(&hash).copied()
+    }
+
+    /// Resolves a type's name to its [`PropertyList`], for human-readable
+    /// formats (e.g. JSON) that identify objects by name on the wire.
+    pub fn resolve_name(&self, name: &str) -> Option<&'static PropertyList> {
+        self.by_name.get(name).copied()
+    }
+
+    /// Resolves a type's name hash and creates a default-initialized
+    /// instance of it.
+    ///
+    /// This is the factory lookup that lets `Ptr<T>`/`SharedPtr<T>::deserialize`
+    /// construct the correct concrete [`PropertyClass`] behind a base-class
+    /// pointer purely from the `type_hash` on the wire, via this registry's
+    /// [`TypeTag::read_tag`] impl below and [`Deserializer::try_deserialize`].
+    #[doc(alias = "instantiate")]
+    pub fn make_default(&self, hash: u32) -> Option<Box<dyn PropertyClass>> {
+        self.resolve(hash).map(PropertyList::make_default)
     }
 }
 
-impl TypeTag for Registry {
-    fn read_tag(&self, de: &mut crate::serde::Deserializer<'_>)
-        -> anyhow::Result<Option<Box<dyn crate::PropertyClass>>> {
+impl TypeTag for TypeRegistry {
+    fn read_tag(
+        &self,
+        de: &mut Deserializer<'_>,
+    ) -> anyhow::Result<Option<Box<dyn PropertyClass>>> {
         let type_hash = de.reader().u32()?;
-        
+
         if type_hash == 0 {
             return Ok(None);
         }
 
-        let list = self.registry.get(&type_hash).ok_or_else(|| anyhow::anyhow!("Hash {type_hash} not in registry"))?;
-        Ok(Some(list.make_default()))
+        self.make_default(type_hash)
+            .map(Some)
+            .ok_or_else(|| anyhow::anyhow!("hash {type_hash} not in type registry"))
     }
 
     fn validate_tag(
         &self,
-        de: &mut crate::serde::Deserializer<'_>,
-        obj: &dyn crate::PropertyClass,
+        de: &mut Deserializer<'_>,
+        obj: &dyn PropertyClass,
     ) -> anyhow::Result<()> {
         let type_hash = de.reader().u32()?;
 
         if type_hash == obj.property_list().type_hash() {
             Ok(())
         } else {
-            Err(anyhow::anyhow!("Hashes don't match"))
+            Err(anyhow::anyhow!("hashes don't match"))
         }
     }
 
-    fn write_tag(&self, ser: &mut crate::serde::Serializer<'_>, obj: Option<&dyn crate::PropertyClass>) {
-        let type_hash = match obj {
-            Some(class) => class.property_list().type_hash(),
-            None => 0
-        };
-
+    fn write_tag(&self, ser: &mut Serializer<'_>, obj: Option<&dyn PropertyClass>) {
+        let type_hash = obj.map_or(0, |class| class.property_list().type_hash());
         ser.writer().u32(type_hash);
-
-        //small rust version for vale
-        //ser.writer().u32(obj.map_or(0, |class| class.property_list().type_hash()));
     }
 }
@@ -7,12 +7,52 @@ use crate::type_info::PropertyFlags;
 mod deserializer;
 pub use deserializer::*;
 
+mod interner;
+pub use interner::*;
+
 mod serializer;
 pub use serializer::*;
 
 mod type_tag;
 pub use type_tag::*;
 
+pub mod text;
+
+// The format-agnostic `Marshal`/`Unmarshal`/`Layout` abstraction below is not
+// wired up as part of the crate's public API yet: its `ser::Serializer` and
+// `de::Deserializer` would clash by name with the concrete, officially
+// supported types re-exported above. It stays crate-internal until a later
+// pass reconciles the two.
+mod result;
+
+pub(crate) mod de;
+pub(crate) mod ext;
+pub(crate) mod ser;
+
+pub(crate) mod binary;
+pub(crate) mod json;
+
+/// A zero-sized capability token threaded through every generic
+/// (de)serialization call in [`ser`] and [`de`].
+///
+/// [`ser::Marshal`]/[`ser::Layout`]/[`de::Unmarshal`]/[`de::Layout`]
+/// implementations never construct one themselves - they only ever forward
+/// the one [`ser::Serializer::serialize`]/[`de::Deserializer::deserialize`]
+/// created, which keeps recursive calls (nested classes, containers) from
+/// being driven by anything other than the actual entrypoint.
+#[derive(Clone, Copy)]
+pub(crate) struct Baton(pub(crate) ());
+
+/// Which kind of [`PropertyClass`][crate::PropertyClass] identity is being
+/// (de)serialized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum IdentityType {
+    /// The identity of a [`PropertyClass`][crate::PropertyClass] value, as
+    /// written by [`ser::Serializer::serialize`]/read by
+    /// [`de::Deserializer::deserialize`].
+    Value,
+}
+
 bitflags::bitflags! {
     /// Configuration flags to customize serialization behavior.
     #[repr(transparent)]
@@ -30,6 +70,13 @@ bitflags::bitflags! {
         /// Properties with the `DELTA_ENCODE` bit must always have their
         /// values serialized.
         const FORBID_DELTA_ENCODE = 1 << 4;
+        /// Lengths and scalar integers opting into it are encoded as
+        /// bincode-style varints instead of fixed-width integers.
+        ///
+        /// Mutually exclusive with [`COMPACT_LENGTH_PREFIXES`](Self::COMPACT_LENGTH_PREFIXES);
+        /// if both are set, `COMPACT_LENGTH_PREFIXES` takes precedence for
+        /// length prefixes.
+        const VARINT = 1 << 5;
     }
 }
 
@@ -45,13 +92,34 @@ pub struct Config {
     pub shallow: bool,
     /// A recursion limit for nested data to avoid stack overflows.
     pub recursion_limit: u8,
+    /// Whether deep deserialization tolerates property hashes it doesn't
+    /// recognize.
+    ///
+    /// When `true`, unknown properties are skipped over using their length
+    /// prefix instead of failing the whole object, so data produced by a
+    /// newer client revision can still be read. Defaults to `false`, which
+    /// fails as soon as a hash doesn't match a known property.
+    pub tolerant: bool,
+    /// An optional ceiling on the total number of bytes the deserializer
+    /// may speculatively allocate for length-prefixed data (strings and
+    /// decompressed blobs) while reading a single object. Container length
+    /// prefixes are charged against the same budget, one unit per declared
+    /// element.
+    ///
+    /// Defaults to [`None`], which allows allocations of any size.
+    pub size_limit: Option<usize>,
+    /// An optional ceiling on the number of elements a single container's
+    /// length prefix may declare.
+    ///
+    /// Defaults to [`None`], which allows containers of any length.
+    pub max_collection_len: Option<usize>,
 }
 
 impl Config {
     /// Creates the default serializer configuration.
     ///
     /// No serializer flags, shallow mode, `TRANSMIT | PRIVILEGED_TRANSMIT`
-    /// property mask, recursion limit of `128`.
+    /// property mask, recursion limit of `127`, strict deserialization.
     #[inline(always)]
     pub const fn new() -> Self {
         Self {
@@ -59,6 +127,9 @@ impl Config {
             property_mask: PropertyFlags::TRANSMIT.union(PropertyFlags::PRIVILEGED_TRANSMIT),
             shallow: true,
             recursion_limit: u8::MAX / 2,
+            tolerant: false,
+            size_limit: None,
+            max_collection_len: None,
         }
     }
 }
@@ -0,0 +1,443 @@
+//! A compact binary [`Marshal`]/[`Layout`] pair for the generic serializer,
+//! matching the little-endian, length-prefixed conventions of the existing
+//! wire binary format.
+
+use std::io::{self, Read, Write};
+
+use super::{
+    de,
+    ext::NoopExt,
+    result::{Error, Result},
+    ser::{serialize_value, Layout, Marshal, Serializer},
+    Baton, IdentityType,
+};
+use crate::{
+    registry::TypeRegistry, type_info::PropertyList, Container, Enum, PropertyClass, Type,
+};
+
+/// A [`Marshal`] that writes little-endian primitives and
+/// `u16`-length-prefixed `str`/`wstr` values.
+pub(crate) struct BinaryMarshal<W> {
+    writer: W,
+}
+
+impl<W: io::Write> BinaryMarshal<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    fn write_len(&mut self, len: usize) -> Result<()> {
+        let len = u16::try_from(len).map_err(Error::custom)?;
+        self.writer
+            .write_all(&len.to_le_bytes())
+            .map_err(Error::custom)
+    }
+}
+
+macro_rules! impl_int_marshal {
+    ($($method:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method(&mut self, v: $ty) -> Result<()> {
+                self.writer.write_all(&v.to_le_bytes()).map_err(Error::custom)
+            }
+        )*
+    };
+}
+
+impl<W: io::Write> Marshal for BinaryMarshal<W> {
+    fn human_readable(&self) -> bool {
+        false
+    }
+
+    fn bool(&mut self, v: bool) -> Result<()> {
+        self.writer.write_all(&[v as u8]).map_err(Error::custom)
+    }
+
+    impl_int_marshal! {
+        i8: i8, i16: i16, i32: i32,
+        u8: u8, u16: u16, u32: u32, u64: u64,
+        f32: f32, f64: f64,
+    }
+
+    fn str(&mut self, v: &[u8]) -> Result<()> {
+        self.write_len(v.len())?;
+        self.writer.write_all(v).map_err(Error::custom)
+    }
+
+    fn wstr(&mut self, v: &[u16]) -> Result<()> {
+        self.write_len(v.len())?;
+        for unit in v {
+            self.writer
+                .write_all(&unit.to_le_bytes())
+                .map_err(Error::custom)?;
+        }
+        Ok(())
+    }
+
+    fn begin_seq(&mut self, len: usize) -> Result<()> {
+        let len = u32::try_from(len).map_err(Error::custom)?;
+        self.writer
+            .write_all(&len.to_le_bytes())
+            .map_err(Error::custom)
+    }
+
+    fn end_seq(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn begin_map(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn map_key(&mut self, _key: &str) -> Result<()> {
+        // Binary encodes properties in the fixed order `PropertyList`
+        // reports them, so field names don't need to be on the wire.
+        Ok(())
+    }
+
+    fn end_map(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A [`Layout`] that writes a [`PropertyClass`]'s identity as its type hash,
+/// its properties in declaration order, [`Container`]s as a `u32`-prefixed
+/// element sequence, and [`Enum`] variants as their raw value.
+pub(crate) struct BinaryLayout;
+
+impl BinaryLayout {
+    pub(crate) fn new() -> Self {
+        Self
+    }
+
+    // Recursively writes `v`'s base class chain (outermost first) followed
+    // by `v`'s own properties, all in declaration order on the same wire.
+    fn write_properties(
+        &mut self,
+        m: &mut dyn Marshal,
+        v: &dyn PropertyClass,
+        baton: Baton,
+    ) -> Result<()> {
+        if let Some(base) = v.base() {
+            self.write_properties(m, base, baton)?;
+        }
+
+        for view in v.property_list().iter_properties() {
+            serialize_value(self, m, v.property(view), baton)?;
+        }
+        Ok(())
+    }
+}
+
+impl Layout for BinaryLayout {
+    fn identity(
+        &mut self,
+        m: &mut dyn Marshal,
+        v: Option<&'static PropertyList>,
+        _ty: IdentityType,
+        _baton: Baton,
+    ) -> Result<()> {
+        m.u32(v.map_or(0, PropertyList::type_hash))
+    }
+
+    fn class(&mut self, m: &mut dyn Marshal, v: &dyn PropertyClass, baton: Baton) -> Result<()> {
+        self.write_properties(m, v, baton)
+    }
+
+    fn container(&mut self, m: &mut dyn Marshal, v: &dyn Container, baton: Baton) -> Result<()> {
+        m.begin_seq(v.len())?;
+        for element in v.iter() {
+            serialize_value(self, m, element, baton)?;
+        }
+        Ok(())
+    }
+
+    fn enum_variant(&mut self, m: &mut dyn Marshal, v: &dyn Enum, _baton: Baton) -> Result<()> {
+        m.u32(v.value())
+    }
+}
+
+impl<W: io::Write> Serializer<BinaryMarshal<W>, BinaryLayout, NoopExt> {
+    /// Creates a serializer that writes reflected values in the compact
+    /// binary format to `writer`.
+    pub(crate) fn binary(writer: W) -> Self {
+        Self::new(BinaryMarshal::new(writer), BinaryLayout::new())
+    }
+}
+
+/// An [`de::Unmarshal`] that reads little-endian primitives and
+/// `u16`-length-prefixed `str`/`wstr` values, the inverse of [`BinaryMarshal`].
+pub(crate) struct BinaryUnmarshal<R> {
+    reader: R,
+}
+
+impl<R: io::Read> BinaryUnmarshal<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        let mut bytes = [0; 2];
+        self.reader.read_exact(&mut bytes).map_err(Error::custom)?;
+        Ok(u16::from_le_bytes(bytes) as usize)
+    }
+}
+
+macro_rules! impl_int_unmarshal {
+    ($($method:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method(&mut self) -> Result<$ty> {
+                let mut bytes = [0; std::mem::size_of::<$ty>()];
+                self.reader.read_exact(&mut bytes).map_err(Error::custom)?;
+                Ok(<$ty>::from_le_bytes(bytes))
+            }
+        )*
+    };
+}
+
+impl<R: io::Read> de::Unmarshal for BinaryUnmarshal<R> {
+    fn human_readable(&self) -> bool {
+        false
+    }
+
+    fn bool(&mut self) -> Result<bool> {
+        let mut byte = [0; 1];
+        self.reader.read_exact(&mut byte).map_err(Error::custom)?;
+        Ok(byte[0] != 0)
+    }
+
+    impl_int_unmarshal! {
+        i8: i8, i16: i16, i32: i32,
+        u8: u8, u16: u16, u32: u32, u64: u64,
+        f32: f32, f64: f64,
+    }
+
+    fn str(&mut self) -> Result<Vec<u8>> {
+        let len = self.read_len()?;
+        let mut data = vec![0; len];
+        self.reader.read_exact(&mut data).map_err(Error::custom)?;
+        Ok(data)
+    }
+
+    fn wstr(&mut self) -> Result<Vec<u16>> {
+        let len = self.read_len()?;
+        (0..len)
+            .map(|_| {
+                let mut bytes = [0; 2];
+                self.reader.read_exact(&mut bytes).map_err(Error::custom)?;
+                Ok(u16::from_le_bytes(bytes))
+            })
+            .collect()
+    }
+
+    fn skip(&mut self, len: usize) -> Result<()> {
+        io::copy(&mut self.reader.by_ref().take(len as u64), &mut io::sink())
+            .map_err(Error::custom)?;
+        Ok(())
+    }
+
+    fn begin_seq(&mut self) -> Result<Option<usize>> {
+        Ok(Some(self.u32()? as usize))
+    }
+
+    fn seq_has_next(&mut self) -> Result<bool> {
+        // The compact binary layout prefixes an element count instead of
+        // delimiting the sequence, so `BinaryContainerVisitor` counts down
+        // from what `begin_seq` returned and never polls this.
+        Ok(true)
+    }
+
+    fn end_seq(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn begin_map(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn expect_key(&mut self, _key: &str) -> Result<()> {
+        // Binary encodes properties in the fixed order `PropertyList`
+        // reports them, so field names aren't on the wire to confirm.
+        Ok(())
+    }
+
+    fn end_map(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The inverse [`de::Layout`] of [`BinaryLayout`].
+///
+/// Shares the same type, since both directions agree on the same framing.
+impl BinaryLayout {
+    // Recursively reads `v`'s base class chain (outermost first) followed
+    // by `v`'s own properties, all in declaration order from the same wire.
+    fn read_properties(
+        &mut self,
+        m: &mut dyn de::Unmarshal,
+        v: &mut dyn PropertyClass,
+        baton: Baton,
+    ) -> Result<()> {
+        if let Some(base) = v.base_mut() {
+            self.read_properties(m, base, baton)?;
+        }
+
+        for view in v.property_list().iter_properties() {
+            de::deserialize_value(
+                &mut BinaryDynDeserializer { m, layout: self },
+                v.property_mut(view),
+                baton,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// A minimal `DynDeserializer` wrapping a borrowed `Unmarshal`/`BinaryLayout`
+// pair, so `de::deserialize_value` can recurse into nested classes and
+// containers without threading a whole `Deserializer<M, L, Ext>` through
+// `read_properties`' recursive base-chain walk.
+struct BinaryDynDeserializer<'a> {
+    m: &'a mut dyn de::Unmarshal,
+    layout: &'a mut BinaryLayout,
+}
+
+impl de::DynDeserializer for BinaryDynDeserializer<'_> {
+    fn unmarshal(&mut self) -> &mut dyn de::Unmarshal {
+        self.m
+    }
+
+    fn human_readable(&self) -> bool {
+        self.m.human_readable()
+    }
+
+    fn identity(
+        &mut self,
+        ty: IdentityType,
+        baton: Baton,
+    ) -> Result<Option<&'static PropertyList>> {
+        de::Layout::identity(self.layout, self.m, ty, baton)
+    }
+
+    fn class(&mut self, v: &mut dyn PropertyClass, baton: Baton) -> Result<()> {
+        de::Layout::class(self.layout, self.m, v, baton)
+    }
+
+    fn skip_value(&mut self, baton: Baton) -> Result<()> {
+        de::Layout::skip_value(self.layout, self.m, baton)
+    }
+
+    fn strict(&self) -> bool {
+        de::Layout::strict(self.layout)
+    }
+
+    fn container(
+        &mut self,
+        f: &mut dyn FnMut(&mut dyn de::ContainerVisitor, Baton) -> Result<()>,
+        baton: Baton,
+    ) -> Result<()> {
+        BinaryLayout::container(self, f, baton)
+    }
+
+    fn enum_variant(&mut self, v: &mut dyn Enum, baton: Baton) -> Result<()> {
+        de::Layout::enum_variant(self.layout, self.m, v, baton)
+    }
+}
+
+impl de::Layout for BinaryLayout {
+    fn identity(
+        &mut self,
+        m: &mut dyn de::Unmarshal,
+        _ty: IdentityType,
+        _baton: Baton,
+    ) -> Result<Option<&'static PropertyList>> {
+        let hash = de::Unmarshal::u32(m)?;
+        if hash == 0 {
+            return Ok(None);
+        }
+        TypeRegistry::global()
+            .resolve(hash)
+            .map(Some)
+            .ok_or_else(|| Error::custom(format!("unknown type hash `{hash:#010x}`")))
+    }
+
+    fn class(
+        &mut self,
+        m: &mut dyn de::Unmarshal,
+        v: &mut dyn PropertyClass,
+        baton: Baton,
+    ) -> Result<()> {
+        self.read_properties(m, v, baton)
+    }
+
+    fn skip_value(&mut self, _m: &mut dyn de::Unmarshal, _baton: Baton) -> Result<()> {
+        Err(Error::custom(
+            "the shallow binary layout has no per-property framing to skip an unknown property",
+        ))
+    }
+
+    fn container(
+        deserializer: &mut dyn de::DynDeserializer,
+        f: &mut dyn FnMut(&mut dyn de::ContainerVisitor, Baton) -> Result<()>,
+        baton: Baton,
+    ) -> Result<()> {
+        let len = deserializer.unmarshal().begin_seq()?.unwrap_or(0);
+        let mut visitor = BinaryContainerVisitor {
+            deserializer: &mut *deserializer,
+            remaining: len,
+        };
+        f(&mut visitor, baton)?;
+        deserializer.unmarshal().end_seq()
+    }
+
+    fn enum_variant(
+        &mut self,
+        m: &mut dyn de::Unmarshal,
+        v: &mut dyn Enum,
+        _baton: Baton,
+    ) -> Result<()> {
+        let value = m.u32()?;
+        if v.update_value(value) {
+            Ok(())
+        } else {
+            Err(Error::custom(format!("unknown enum variant value `{value}`")))
+        }
+    }
+}
+
+struct BinaryContainerVisitor<'a> {
+    deserializer: &'a mut dyn de::DynDeserializer,
+    remaining: usize,
+}
+
+impl de::ContainerVisitor for BinaryContainerVisitor<'_> {
+    fn element_count(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+
+    fn next(&mut self, value: &mut dyn Type, baton: Baton) -> Result<bool> {
+        if self.remaining == 0 {
+            return Ok(false);
+        }
+        self.remaining -= 1;
+        de::deserialize_value(self.deserializer, value, baton)?;
+        Ok(true)
+    }
+
+    fn skip_next(&mut self, _baton: Baton) -> Result<bool> {
+        if self.remaining == 0 {
+            return Ok(false);
+        }
+        Err(Error::custom(
+            "skipping a container element without materializing it is not supported",
+        ))
+    }
+}
+
+impl<R: io::Read> de::Deserializer<BinaryUnmarshal<R>, BinaryLayout, NoopExt> {
+    /// Creates a deserializer that reads reflected values from the compact
+    /// binary format produced by [`Serializer::binary`].
+    pub(crate) fn binary(reader: R) -> Self {
+        Self::new(BinaryUnmarshal::new(reader), BinaryLayout::new())
+    }
+}
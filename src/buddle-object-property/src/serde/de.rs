@@ -3,7 +3,11 @@
 use std::marker::PhantomData;
 
 use super::{result::*, Baton, IdentityType};
-use crate::{type_info::PropertyList, Enum, PropertyClass, Type};
+use crate::{
+    cpp::{RawString, RawWideString},
+    type_info::PropertyList,
+    Container, Enum, PropertyClass, Type, TypeMut,
+};
 
 mod sealed {
     pub trait Sealed {}
@@ -54,6 +58,51 @@ pub trait Unmarshal {
 
     /// Unmarshals a wide string value.
     fn wstr(&mut self) -> Result<Vec<u16>>;
+
+    /// Discards `len` bytes without materializing a value.
+    ///
+    /// Used to throw away a property's payload when its hash is not
+    /// recognized by the target [`PropertyClass`], so forward-compatible
+    /// deserialization doesn't have to fail on data written by a newer
+    /// client revision.
+    fn skip(&mut self, len: usize) -> Result<()>;
+
+    /// Begins reading a sequence (array-like) value, the inverse of
+    /// [`Marshal::begin_seq`][super::ser::Marshal::begin_seq].
+    ///
+    /// Returns the element count up front for formats that prefix it on
+    /// the wire, or [`None`] for formats that instead delimit the
+    /// sequence and must be polled with [`Unmarshal::seq_has_next`].
+    fn begin_seq(&mut self) -> Result<Option<usize>>;
+
+    /// Reports whether another element remains in the sequence currently
+    /// being read, for formats that delimit a sequence's end instead of
+    /// prefixing it with an element count.
+    ///
+    /// Formats that already returned a count from [`Unmarshal::begin_seq`]
+    /// are free to never call this themselves.
+    fn seq_has_next(&mut self) -> Result<bool>;
+
+    /// Ends reading a sequence, the inverse of
+    /// [`Marshal::end_seq`][super::ser::Marshal::end_seq].
+    fn end_seq(&mut self) -> Result<()>;
+
+    /// Begins reading a map (struct-like) value, the inverse of
+    /// [`Marshal::begin_map`][super::ser::Marshal::begin_map].
+    fn begin_map(&mut self) -> Result<()>;
+
+    /// Reads and consumes the next map key, the inverse of
+    /// [`Marshal::map_key`][super::ser::Marshal::map_key].
+    ///
+    /// Errors if the key found on the wire doesn't match `key`. Formats
+    /// with no key framing on the wire (like the compact binary layout)
+    /// can ignore `key` entirely, since [`Layout::class`]'s
+    /// declaration-order walk never needs it confirmed.
+    fn expect_key(&mut self, key: &str) -> Result<()>;
+
+    /// Ends reading a map, the inverse of
+    /// [`Marshal::end_map`][super::ser::Marshal::end_map].
+    fn end_map(&mut self) -> Result<()>;
 }
 
 /// Defines the handling of the data format around the
@@ -79,6 +128,12 @@ pub trait Layout {
     /// the object with [`Layout::identity`]. Instead,
     /// the deserialization logic of every [`PropertyClass`]
     /// is responsible for that.
+    ///
+    /// Implementations that encounter a property hash absent from `v`'s
+    /// [`PropertyList`] should consult [`Layout::strict`]: when `false`,
+    /// the value should be discarded with [`Layout::skip_value`] and
+    /// deserialization should continue with the next property; when
+    /// `true`, the unknown hash should be treated as an error.
     fn class(
         &mut self,
         m: &mut dyn Unmarshal,
@@ -86,6 +141,26 @@ pub trait Layout {
         baton: Baton,
     ) -> Result<()>;
 
+    /// Skips over a single serialized property value without
+    /// materializing a concrete [`Type`][crate::Type].
+    ///
+    /// Implementations must still honor the format's own framing for a
+    /// property value (size prefix, padding, and element counts for
+    /// containers) so the cursor lands exactly at the start of the next
+    /// property, the same way [`Layout::class`] would have left it after
+    /// actually deserializing the value.
+    fn skip_value(&mut self, m: &mut dyn Unmarshal, baton: Baton) -> Result<()>;
+
+    /// Whether unknown property hashes encountered by [`Layout::class`]
+    /// are a hard error (`true`, the default) or should be silently
+    /// skipped via [`Layout::skip_value`] (`false`).
+    ///
+    /// Lenient deserialization allows data written by a newer client
+    /// revision - which may carry extra properties - to still be read.
+    fn strict(&self) -> bool {
+        true
+    }
+
     /// Deserializes a [`Container`][crate::Container] object in-place from the
     /// described format.
     ///
@@ -136,6 +211,17 @@ pub trait DynDeserializer: sealed::Sealed {
     /// is responsible for that.
     fn class(&mut self, v: &mut dyn PropertyClass, baton: Baton) -> Result<()>;
 
+    /// Skips over a single serialized property value without
+    /// materializing a concrete [`Type`][crate::Type].
+    ///
+    /// See [`Layout::skip_value`] for the framing guarantees this must
+    /// uphold.
+    fn skip_value(&mut self, baton: Baton) -> Result<()>;
+
+    /// Whether unknown property hashes are a hard error or silently
+    /// skipped. See [`Layout::strict`].
+    fn strict(&self) -> bool;
+
     /// Deserializes a [`Container`][crate::Container] object from the
     /// described format in-place.
     ///
@@ -169,6 +255,16 @@ pub trait ContainerVisitor {
     /// The returned [`bool`] indicates if there are more
     /// elements to read.
     fn next(&mut self, value: &mut dyn Type, baton: Baton) -> Result<bool>;
+
+    /// Advances past the next element without materializing a
+    /// concrete [`Type`], for callers only interested in a handful of
+    /// elements out of a potentially large container.
+    ///
+    /// Must honor the same per-element framing [`ContainerVisitor::next`]
+    /// would have, so the cursor lands exactly where `next` would have
+    /// left it. The returned [`bool`] indicates if there are more
+    /// elements to read, exactly as for [`ContainerVisitor::next`].
+    fn skip_next(&mut self, baton: Baton) -> Result<bool>;
 }
 
 /// An extension trait for adding custom pre and post
@@ -252,6 +348,56 @@ impl<M: Unmarshal, L: Layout, Ext: DeserializerExt> Deserializer<M, L, Ext> {
 
         Ext::post(self)
     }
+
+    /// Deserializes only the `index`-th element of a [`Container`][crate::Container]
+    /// in-place, skipping over every other element instead of
+    /// materializing the whole container.
+    ///
+    /// Returns `Ok(false)` without touching `value` if the container
+    /// holds fewer than `index + 1` elements - this includes containers
+    /// whose [`ContainerVisitor::element_count`] is unknown, in which
+    /// case running out of elements while seeking is discovered lazily
+    /// from a `skip_next` or `next` call returning `false`.
+    ///
+    /// Either way, every element of the container is consumed before
+    /// returning, so the stream is left exactly where it would be after
+    /// a full [`Layout::container`] call.
+    pub fn nth_element(
+        &mut self,
+        index: usize,
+        value: &mut dyn Type,
+        baton: Baton,
+    ) -> Result<bool> {
+        let mut found = false;
+
+        L::container(
+            self,
+            &mut |visitor, baton| {
+                let mut skipped = 0;
+                while skipped < index {
+                    if !visitor.skip_next(baton)? {
+                        return Ok(());
+                    }
+                    skipped += 1;
+                }
+
+                if visitor.next(value, baton)? {
+                    found = true;
+                } else {
+                    return Ok(());
+                }
+
+                // Drain the rest of the container so the cursor ends up
+                // exactly where a full deserialization would have left it.
+                while visitor.skip_next(baton)? {}
+
+                Ok(())
+            },
+            baton,
+        )?;
+
+        Ok(found)
+    }
 }
 
 impl<M, L, Ext> sealed::Sealed for Deserializer<M, L, Ext> {}
@@ -277,6 +423,14 @@ impl<M: Unmarshal, L: Layout, Ext: DeserializerExt> DynDeserializer for Deserial
         self.layout.class(&mut self.unmarshal, v, baton)
     }
 
+    fn skip_value(&mut self, baton: Baton) -> Result<()> {
+        self.layout.skip_value(&mut self.unmarshal, baton)
+    }
+
+    fn strict(&self) -> bool {
+        self.layout.strict()
+    }
+
     fn container(
         &mut self,
         f: &mut dyn FnMut(&mut dyn ContainerVisitor, Baton) -> Result<()>,
@@ -289,3 +443,96 @@ impl<M: Unmarshal, L: Layout, Ext: DeserializerExt> DynDeserializer for Deserial
         self.layout.enum_variant(&mut self.unmarshal, v, baton)
     }
 }
+
+/// Deserializes a single reflected value through `deserializer`, dispatching
+/// on its [`TypeMut`] category.
+///
+/// Shared by every [`Layout`] implementation's [`Layout::class`]/
+/// [`Layout::container`] so each only has to describe its own framing, not
+/// how to walk a [`PropertyClass`]'s or [`Container`]'s elements.
+pub(crate) fn deserialize_value(
+    deserializer: &mut dyn DynDeserializer,
+    v: &mut dyn Type,
+    baton: Baton,
+) -> Result<()> {
+    match v.type_mut() {
+        TypeMut::Class(class) => {
+            // The embedded class is already the right concrete type, so the
+            // identity is only read to keep the cursor in sync with what
+            // `ser::serialize_value` wrote for it; it isn't used to swap in
+            // a different implementation.
+            deserializer.identity(IdentityType::Value, baton)?;
+            deserializer.class(class, baton)
+        }
+        TypeMut::Container(container) => deserialize_container(deserializer, container, baton),
+        TypeMut::Enum(e) => deserializer.enum_variant(e, baton),
+        TypeMut::Value(value) => deserialize_leaf(deserializer.unmarshal(), value),
+    }
+}
+
+// Refills `container` from `deserializer`, discarding its previous contents.
+//
+// Elements are materialized via `Container::push_default`, since the
+// concrete element type isn't known at this point - a trailing element that
+// turns out not to exist is popped back off once the visitor reports it has
+// run out of values.
+fn deserialize_container(
+    deserializer: &mut dyn DynDeserializer,
+    container: &mut dyn Container,
+    baton: Baton,
+) -> Result<()> {
+    while container.pop().is_some() {}
+
+    deserializer.container(
+        &mut |visitor, baton| loop {
+            let element = container.push_default();
+            if !visitor.next(element, baton)? {
+                container.pop();
+                return Ok(());
+            }
+        },
+        baton,
+    )
+}
+
+/// Unmarshals a leaf [`Type`] that is neither a [`PropertyClass`], a
+/// [`Container`], nor an [`Enum`].
+///
+/// Only the primitive kinds [`Unmarshal`] itself knows how to decode are
+/// supported; anything else (e.g. `Ptr`/`SharedPtr` polymorphic pointers)
+/// has no generic representation yet and is reported as an error instead.
+fn deserialize_leaf(m: &mut dyn Unmarshal, v: &mut dyn Type) -> Result<()> {
+    macro_rules! try_leaf {
+        ($ty:ty, $method:ident) => {
+            if let Some(slot) = v.downcast_mut::<$ty>() {
+                *slot = m.$method()?;
+                return Ok(());
+            }
+        };
+    }
+
+    try_leaf!(bool, bool);
+    try_leaf!(i8, i8);
+    try_leaf!(i16, i16);
+    try_leaf!(i32, i32);
+    try_leaf!(u8, u8);
+    try_leaf!(u16, u16);
+    try_leaf!(u32, u32);
+    try_leaf!(u64, u64);
+    try_leaf!(f32, f32);
+    try_leaf!(f64, f64);
+
+    if let Some(slot) = v.downcast_mut::<RawString>() {
+        slot.0 = m.str()?;
+        return Ok(());
+    }
+    if let Some(slot) = v.downcast_mut::<RawWideString>() {
+        slot.0 = m.wstr()?;
+        return Ok(());
+    }
+
+    Err(Error::custom(format!(
+        "no generic Unmarshal/Layout support for leaf type `{}`",
+        v.type_info().type_name()
+    )))
+}
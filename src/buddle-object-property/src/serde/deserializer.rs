@@ -1,4 +1,8 @@
-use std::io::{self, Write};
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    sync::{Arc, Weak},
+};
 
 use anyhow::{anyhow, bail};
 use buddle_bit_buf::BitReader;
@@ -7,7 +11,7 @@ use flate2::write::ZlibDecoder;
 
 use crate::{property_class::PropertyClass, r#enum::Enum, type_info::PropertyFlags};
 
-use super::{Config, SerializerFlags, TypeTag};
+use super::{Config, SerializerFlags, StringInterner, TypeTag};
 
 #[inline]
 fn zlib_decompress<'a>(data: &[u8], buf: &'a mut Vec<u8>) -> io::Result<&'a [u8]> {
@@ -23,6 +27,8 @@ macro_rules! impl_read_len {
             fn $fn(&mut self) -> anyhow::Result<usize> {
                 if self.config.flags.contains(SerializerFlags::COMPACT_LENGTH_PREFIXES) {
                     self.read_compact_length_prefix()
+                } else if self.config.flags.contains(SerializerFlags::VARINT) {
+                    self.read_varint().map(|v| v as usize)
                 } else {
                     self.reader.$read_fn().map(|v| v as usize)
                 }
@@ -38,6 +44,40 @@ pub struct Deserializer<'de> {
     reader: BitReader<'de>,
     config: Config,
     tag: &'de dyn TypeTag,
+
+    /// Bytes still available for speculative allocation, per
+    /// [`Config::size_limit`]. Decremented as data is consumed and never
+    /// replenished; [`None`] when no limit was configured.
+    remaining_budget: Option<usize>,
+
+    /// An optional cache for deduplicating repeated strings, set up via
+    /// [`Deserializer::with_interner`].
+    interner: Option<&'de mut StringInterner>,
+
+    /// Nesting depth of [`Deserializer::with_recursion_limit`], used to
+    /// detect when the outermost call for the current object graph
+    /// returns, so [`Deserializer::resolve_weak_fixups`] runs exactly once
+    /// per loaded graph, after every strong shared pointer inside it.
+    graph_depth: u32,
+
+    /// Object reference table for the shared-pointer graph mode: strong
+    /// objects are recorded here in the order their ids are first
+    /// assigned, so repeated ids resolve to the same allocation instead of
+    /// deserializing duplicate data.
+    shared_refs: Vec<Arc<dyn PropertyClass>>,
+
+    /// `WeakPtr<T>` slots deferred until `shared_refs` is complete, paired
+    /// with the id they should resolve against.
+    ///
+    /// # Safety invariant
+    ///
+    /// Every pointer here must stay valid and unmoved until
+    /// [`Deserializer::resolve_weak_fixups`] runs. This holds because
+    /// containers always `reserve` their exact final length before
+    /// pushing elements (see `impl_container!`), and every
+    /// [`Type::deserialize`][crate::Type::deserialize] implementation
+    /// fills its value in place rather than relocating it.
+    weak_fixups: Vec<(u32, *mut Weak<dyn PropertyClass>)>,
 }
 
 impl<'de> Deserializer<'de> {
@@ -45,11 +85,37 @@ impl<'de> Deserializer<'de> {
     pub fn new(config: Config, tag: &'de dyn TypeTag) -> Self {
         Self {
             reader: BitReader::default(),
+            remaining_budget: config.size_limit,
             config,
             tag,
+            interner: None,
+            graph_depth: 0,
+            shared_refs: Vec::new(),
+            weak_fixups: Vec::new(),
         }
     }
 
+    /// Reuses `interner` to deduplicate repeated strings read via
+    /// [`Deserializer::read_interned_bytes`]/[`Deserializer::read_interned_str`],
+    /// across possibly many [`Deserializer::load`] calls.
+    pub fn with_interner(mut self, interner: &'de mut StringInterner) -> Self {
+        self.interner = Some(interner);
+        self
+    }
+
+    /// Charges `bytes` against the remaining allocation budget, failing
+    /// instead of letting a hostile length prefix trigger an unbounded
+    /// speculative allocation.
+    fn charge_allocation(&mut self, bytes: usize) -> anyhow::Result<()> {
+        if let Some(remaining) = &mut self.remaining_budget {
+            *remaining = remaining
+                .checked_sub(bytes)
+                .ok_or_else(|| anyhow!("exceeded configured allocation size limit"))?;
+        }
+
+        Ok(())
+    }
+
     /// Provides access to the underlying [`BitReader`].
     #[inline]
     pub fn reader(&mut self) -> &mut BitReader<'de> {
@@ -70,16 +136,105 @@ impl<'de> Deserializer<'de> {
             bail!("deserializer recursion limit exhausted");
         }
 
+        self.graph_depth += 1;
         let res = f(self);
+        self.graph_depth -= 1;
+
+        // Once the outermost call for this object graph returns, every
+        // strong shared pointer inside it has been materialized into
+        // `shared_refs`, so deferred `WeakPtr`s can finally be resolved.
+        if self.graph_depth == 0 {
+            if res.is_ok() {
+                self.resolve_weak_fixups();
+            } else {
+                // The graph was abandoned partway through; some of these
+                // pointers may now dangle inside dropped objects, so don't
+                // touch them.
+                self.weak_fixups.clear();
+            }
+        }
 
         self.config.recursion_limit += 1;
 
         res
     }
 
-    fn decompress(mut data: &[u8], scratch: &'de mut Vec<u8>) -> anyhow::Result<&'de [u8]> {
+    /// Deserializes a `SharedPtr<T>`'s pointee through the shared-pointer
+    /// graph: reads whether this is the first occurrence of its id, and
+    /// either deserializes a fresh object or clones the existing `Arc` for
+    /// a repeated id.
+    ///
+    /// This method may be used to implement [`Type::deserialize`] for
+    /// `SharedPtr<T>`.
+    ///
+    /// [`Type::deserialize`]: crate::Type::deserialize
+    pub fn deserialize_shared(&mut self) -> anyhow::Result<Arc<dyn PropertyClass>> {
+        let is_new = self.reader.read_bit()?;
+        let id = self.reader.u32()?;
+
+        if is_new {
+            if id as usize != self.shared_refs.len() {
+                bail!("shared pointer graph assigned id {id} out of order");
+            }
+
+            let obj: Arc<dyn PropertyClass> = Arc::from(self.deserialize()?);
+            self.shared_refs.push(Arc::clone(&obj));
+            Ok(obj)
+        } else {
+            self.shared_refs
+                .get(id as usize)
+                .cloned()
+                .ok_or_else(|| anyhow!("shared pointer referenced unknown id {id}"))
+        }
+    }
+
+    /// Deserializes a `WeakPtr<T>`, reading an optional shared-pointer
+    /// graph id and deferring its resolution until every strong reference
+    /// in the same object graph has been materialized.
+    ///
+    /// This method may be used to implement [`Type::deserialize`] for
+    /// `WeakPtr<T>`.
+    ///
+    /// [`Type::deserialize`]: crate::Type::deserialize
+    pub fn deserialize_weak(&mut self, value: &mut Weak<dyn PropertyClass>) -> anyhow::Result<()> {
+        if self.reader.read_bit()? {
+            let id = self.reader.u32()?;
+
+            // SAFETY: see `Deserializer::weak_fixups`'s safety invariant.
+            self.weak_fixups.push((id, value as *mut _));
+        } else {
+            *value = Weak::new();
+        }
+
+        Ok(())
+    }
+
+    // Resolves every deferred `WeakPtr` slot against `shared_refs`: ids
+    // that never correspond to a strong object become empty weaks rather
+    // than errors, since a dangling weak reference is an expected outcome,
+    // not a malformed one.
+    fn resolve_weak_fixups(&mut self) {
+        for (id, ptr) in self.weak_fixups.drain(..) {
+            let weak = match self.shared_refs.get(id as usize) {
+                Some(obj) => Arc::downgrade(obj),
+                None => Weak::new(),
+            };
+
+            // SAFETY: see `Deserializer::weak_fixups`'s safety invariant.
+            unsafe {
+                *ptr = weak;
+            }
+        }
+    }
+
+    fn decompress(
+        &mut self,
+        mut data: &[u8],
+        scratch: &'de mut Vec<u8>,
+    ) -> anyhow::Result<&'de [u8]> {
         // Read the expected decompressed size of the blob.
         let size = data.read_u32::<LE>()? as usize;
+        self.charge_allocation(size)?;
 
         // Clear the scratch buffer and reserve the memory in advance.
         scratch.clear();
@@ -106,7 +261,11 @@ impl<'de> Deserializer<'de> {
         data: &[u8],
         scratch: &'de mut Vec<u8>,
     ) -> anyhow::Result<()> {
-        let mut decompressed = Self::decompress(data, scratch)?;
+        self.remaining_budget = self.config.size_limit;
+        self.shared_refs.clear();
+        self.weak_fixups.clear();
+
+        let mut decompressed = self.decompress(data, scratch)?;
 
         // If configuration flags are stateful, load them.
         if self.config.flags.contains(SerializerFlags::STATEFUL_FLAGS) {
@@ -127,6 +286,10 @@ impl<'de> Deserializer<'de> {
     /// This will also do the necessary deserializer configuration upfront, so
     /// the object is ready to deserialize data after calling this method.
     pub fn load(&mut self, mut data: &'de [u8], scratch: &'de mut Vec<u8>) -> anyhow::Result<()> {
+        self.remaining_budget = self.config.size_limit;
+        self.shared_refs.clear();
+        self.weak_fixups.clear();
+
         // If configuration flags are stateful, load them.
         if self.config.flags.contains(SerializerFlags::STATEFUL_FLAGS) {
             let raw = data.read_u32::<LE>()? as u8;
@@ -135,7 +298,7 @@ impl<'de> Deserializer<'de> {
 
         // Determine whether the data is compressed or not.
         if self.config.flags.contains(SerializerFlags::COMPRESS) && data.read_u8()? != 0 {
-            self.reader = Self::decompress(data, scratch).map(BitReader::new)?;
+            self.reader = self.decompress(data, scratch).map(BitReader::new)?;
         } else {
             self.reader = BitReader::new(data);
         }
@@ -203,6 +366,45 @@ impl<'de> Deserializer<'de> {
         }
     }
 
+    /// Reads a bincode-style variable-length encoded integer: a tag byte
+    /// below `251` is the value itself, and `251`..=`254` select a
+    /// little-endian `u16`/`u32`/`u64`/`u128` payload wide enough to hold
+    /// it.
+    fn read_varint(&mut self) -> anyhow::Result<u128> {
+        Ok(match self.reader.u8()? {
+            tag @ 0..=250 => tag as u128,
+            251 => self.reader.u16()? as u128,
+            252 => self.reader.u32()? as u128,
+            253 => self.reader.u64()? as u128,
+            254 => self.reader.u128()?,
+            255 => bail!("received reserved varint tag"),
+        })
+    }
+
+    /// Reads a [`u32`] using [`SerializerFlags::VARINT`] encoding.
+    ///
+    /// This method may be used to implement [`Type::deserialize`] for
+    /// integer leaves that opt into the varint representation.
+    ///
+    /// [`Type::deserialize`]: crate::Type::deserialize
+    pub fn read_varint_u32(&mut self) -> anyhow::Result<u32> {
+        self.read_varint()?
+            .try_into()
+            .map_err(|_| anyhow!("received varint value that does not fit into a u32"))
+    }
+
+    /// Reads a [`u64`] using [`SerializerFlags::VARINT`] encoding.
+    ///
+    /// This method may be used to implement [`Type::deserialize`] for
+    /// integer leaves that opt into the varint representation.
+    ///
+    /// [`Type::deserialize`]: crate::Type::deserialize
+    pub fn read_varint_u64(&mut self) -> anyhow::Result<u64> {
+        self.read_varint()?
+            .try_into()
+            .map_err(|_| anyhow!("received varint value that does not fit into a u64"))
+    }
+
     impl_read_len! {
         // Used for strings, where the length is written as `u16`.
         read_str_len = u16(),
@@ -213,8 +415,10 @@ impl<'de> Deserializer<'de> {
 
     /// Reads raw bytes, including the length prefix.
     pub fn read_bytes(&mut self) -> anyhow::Result<&'de [u8]> {
-        self.read_str_len()
-            .and_then(|len| self.reader.read_bytes(len))
+        let len = self.read_str_len()?;
+        self.charge_allocation(len)?;
+
+        self.reader.read_bytes(len)
     }
 
     /// Reads the raw data of a string, including its length prefix.
@@ -222,20 +426,44 @@ impl<'de> Deserializer<'de> {
         self.read_bytes().map(|b| b.to_vec())
     }
 
+    /// Reads raw bytes, including the length prefix, deduplicating the
+    /// result against the [`StringInterner`] configured via
+    /// [`Deserializer::with_interner`], if any.
+    ///
+    /// Unlike [`Deserializer::read_bytes`], the returned slice is not tied
+    /// to the deserializer's input lifetime but to the interner itself, so
+    /// this is meant for transient uses -- e.g. comparing against a known
+    /// identifier -- rather than building up an owned value from it.
+    pub fn read_interned_bytes(&mut self) -> anyhow::Result<&[u8]> {
+        let bytes = self.read_bytes()?;
+
+        Ok(match &mut self.interner {
+            Some(interner) => interner.intern(bytes),
+            None => bytes,
+        })
+    }
+
+    /// Like [`Deserializer::read_interned_bytes`], but validates and
+    /// returns the data as UTF-8.
+    pub fn read_interned_str(&mut self) -> anyhow::Result<&str> {
+        std::str::from_utf8(self.read_interned_bytes()?).map_err(Into::into)
+    }
+
     /// Reads the raw data of a wide string, including its length prefix.
     pub fn read_wstr(&mut self) -> anyhow::Result<Vec<u16>> {
-        self.read_str_len().and_then(|len| {
-            let buf = self.reader.read_bytes(len * 2)?;
+        let len = self.read_str_len()?;
+        self.charge_allocation(len * 2)?;
 
-            let mut data = Vec::with_capacity(len);
-            buf.chunks_exact(std::mem::size_of::<u16>()).for_each(|c| {
-                // SAFETY: `.chunks_exact()` guarantees slices of correct length.
-                let c: [u8; 2] = unsafe { c.try_into().unwrap_unchecked() };
-                data.push(u16::from_le_bytes(c));
-            });
+        let buf = self.reader.read_bytes(len * 2)?;
 
-            Ok(data)
-        })
+        let mut data = Vec::with_capacity(len);
+        buf.chunks_exact(std::mem::size_of::<u16>()).for_each(|c| {
+            // SAFETY: `.chunks_exact()` guarantees slices of correct length.
+            let c: [u8; 2] = unsafe { c.try_into().unwrap_unchecked() };
+            data.push(u16::from_le_bytes(c));
+        });
+
+        Ok(data)
     }
 
     fn deserialize_properties_shallow(&mut self, v: &mut dyn PropertyClass) -> anyhow::Result<()> {
@@ -259,32 +487,87 @@ impl<'de> Deserializer<'de> {
         Ok(())
     }
 
+    /// Maps every property hash reachable from `v`'s (emulated) inheritance
+    /// chain to the number of `base_mut()` hops needed to reach the class
+    /// that actually owns it.
+    ///
+    /// Built once per object, this lets the deep deserialization loop below
+    /// route each property in the stream straight to its owning class,
+    /// without rescanning every level's [`PropertyList`](crate::type_info::PropertyList)
+    /// in turn for every single property.
+    fn property_depths(v: &dyn PropertyClass) -> HashMap<u32, usize> {
+        let mut depths = HashMap::new();
+
+        let mut depth = 0;
+        let mut class = Some(v);
+        while let Some(v) = class {
+            for property in v.property_list().iter_properties() {
+                depths.entry(property.hash()).or_insert(depth);
+            }
+
+            class = v.base();
+            depth += 1;
+        }
+
+        depths
+    }
+
     fn deserialize_properties_deep(
         &mut self,
         v: &mut dyn PropertyClass,
         mut size: usize,
     ) -> anyhow::Result<usize> {
-        // If this object has a base type, we will deserialize its properties
-        // without a header as if they were part of this object.
-        if let Some(base) = v.base_mut() {
-            size = self.deserialize_properties_deep(base, size)?;
-        }
+        // Every level of the (emulated) inheritance chain is serialized
+        // into one flat, self-describing stream: each entry carries its
+        // own length and hash, so properties don't have to appear in
+        // declaration order.
+        let depths = Self::property_depths(v);
 
-        // Consume data until the object size drops to 0.
-        for property in v.property_list().iter_properties() {
+        while size > 0 {
             // Back up the current buffer length and read the next property's size.
             // This will also count padding bits towards byte boundary.
             let property_start = self.reader.len();
             let property_size = self.reader.u32()? as usize;
 
-            // Read the property's hash and find the associated entry.
+            // Read the property's hash and dispatch it to whichever level
+            // of the inheritance chain declares it.
             let property_hash = self.reader.u32()?;
-            if property.hash() != property_hash {
-                bail!("received unknown property hash {property_hash}");
-            }
+            match depths.get(&property_hash) {
+                Some(&depth) => {
+                    let mut target = &mut *v;
+                    for _ in 0..depth {
+                        // `depth` was derived from walking the exact same
+                        // base chain, so this should never run past its
+                        // end; if it ever does, fail loudly instead of
+                        // reading through a dangling reference on this
+                        // untrusted-data path.
+                        target = target
+                            .base_mut()
+                            .expect("depth exceeded the base chain it was derived from");
+                    }
+
+                    // `property_hash` was just found in `target`'s own
+                    // property list while building `depths`; see above for
+                    // why this is `expect`ed rather than assumed.
+                    let property = target
+                        .property_list()
+                        .property_for(property_hash)
+                        .expect("hash was just found in this class's own property list");
+                    target.property_mut(property).deserialize(self)?;
+                }
 
-            // Deserialize the property's value.
-            v.property_mut(property).deserialize(self)?;
+                // Unknown property: skip its value using the length prefix
+                // when the caller opted into tolerant deserialization,
+                // otherwise treat data we can't account for as an error.
+                None if self.config.tolerant => {
+                    let consumed = property_start - self.reader.len();
+                    let remaining = property_size
+                        .checked_sub(consumed)
+                        .ok_or_else(|| anyhow!("received property with invalid length"))?;
+                    self.reader.read_bits(remaining)?;
+                }
+                None => bail!("encountered unknown property during strict deserialization"),
+            }
 
             // Validate the property's size.
             if property_start - self.reader.len() != property_size {
@@ -344,9 +627,17 @@ impl<'de> Deserializer<'de> {
     ///
     /// [`Container`]: crate::container::Container
     /// [`Type::deserialize`]: crate::Type::deserialize
-    #[inline]
     pub fn deserialize_container_len(&mut self) -> anyhow::Result<usize> {
-        self.read_seq_len()
+        let len = self.read_seq_len()?;
+
+        if let Some(max) = self.config.max_collection_len {
+            if len > max {
+                bail!("received container length exceeding the configured limit");
+            }
+        }
+        self.charge_allocation(len)?;
+
+        Ok(len)
     }
 
     /// Deserializes an [`Enum`] variant in-place.
@@ -361,7 +652,7 @@ impl<'de> Deserializer<'de> {
             .flags
             .contains(SerializerFlags::HUMAN_READABLE_ENUMS)
         {
-            let variant = std::str::from_utf8(self.read_bytes()?)?;
+            let variant = self.read_interned_str()?;
             v.update_variant(variant)
         } else {
             let variant = self.reader.u32()?;
@@ -0,0 +1,22 @@
+//! A no-op [`SerializerExt`] for callers that don't need custom pre/post
+//! serialization hooks.
+
+use super::{
+    result::Result,
+    ser::{Serializer, SerializerExt},
+};
+
+/// A [`SerializerExt`] that runs no custom logic around serialization.
+pub(crate) struct NoopExt;
+
+impl SerializerExt for NoopExt {
+    type Res = ();
+
+    fn pre<M, L>(_serializer: &mut Serializer<M, L, Self>) -> Result<()> {
+        Ok(())
+    }
+
+    fn post<M, L>(_serializer: Serializer<M, L, Self>) -> Result<Self::Res> {
+        Ok(())
+    }
+}
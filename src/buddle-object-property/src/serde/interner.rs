@@ -0,0 +1,55 @@
+use std::{collections::HashMap, hash::BuildHasher};
+
+use buddle_utils::ahash::RandomState;
+
+/// A cache for deduplicating repeated strings encountered during
+/// deserialization.
+///
+/// Every unique string seen is copied into its own stable heap allocation
+/// -- rather than being appended into one contiguous buffer -- so that
+/// references already handed out remain valid as further, unrelated
+/// strings are interned later. A table then maps each string's content
+/// hash to the index of its entry, so repeats (type names, enum variants,
+/// localization keys, ...) are looked up instead of being copied again.
+///
+/// Meant to be created once by the caller and threaded through many
+/// [`Deserializer::load`](super::Deserializer::load) calls -- e.g. once
+/// per record in an archive -- via
+/// [`Deserializer::with_interner`](super::Deserializer::with_interner),
+/// the same way the `scratch` buffer is reused for decompression.
+#[derive(Default)]
+pub struct StringInterner {
+    entries: Vec<Box<[u8]>>,
+    index: HashMap<u64, usize, RandomState>,
+    hasher: RandomState,
+}
+
+impl StringInterner {
+    /// Creates a new, empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns `bytes`, returning a reference to its (possibly already
+    /// cached) stable copy.
+    ///
+    /// If an identical string was already seen, its existing entry is
+    /// reused and `bytes` is not copied again.
+    pub(crate) fn intern(&mut self, bytes: &[u8]) -> &[u8] {
+        let hash = self.hasher.hash_one(bytes);
+
+        if let Some(&idx) = self.index.get(&hash) {
+            // Treat the hash as an index into `entries`, but still
+            // compare the bytes themselves to guard against collisions.
+            if &*self.entries[idx] == bytes {
+                return &self.entries[idx];
+            }
+        }
+
+        let idx = self.entries.len();
+        self.entries.push(bytes.into());
+        self.index.insert(hash, idx);
+
+        &self.entries[idx]
+    }
+}
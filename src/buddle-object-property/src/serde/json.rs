@@ -0,0 +1,673 @@
+//! A JSON [`Marshal`]/[`Layout`] pair for the generic serializer.
+
+use std::io::{self, Read, Write};
+
+use super::{
+    de,
+    ext::NoopExt,
+    result::{Error, Result},
+    ser::{serialize_value, Layout, Marshal, Serializer},
+    Baton, IdentityType,
+};
+use crate::{
+    registry::TypeRegistry, type_info::PropertyList, Container, Enum, PropertyClass, Type,
+};
+
+/// A [`Marshal`] that emits primitives as JSON tokens.
+pub(crate) struct JsonMarshal<W> {
+    writer: W,
+    // Whether the innermost open array/object already wrote an element,
+    // so commas are placed correctly between siblings. Empty when at the
+    // document root.
+    frames: Vec<bool>,
+    // Set right after `map_key` writes a field name, so the value that
+    // follows it doesn't also try to comma-separate itself from the key.
+    pending_key: bool,
+}
+
+impl<W: io::Write> JsonMarshal<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self {
+            writer,
+            frames: Vec::new(),
+            pending_key: false,
+        }
+    }
+
+    // Writes a comma if this value isn't the first in its enclosing
+    // array/object/document.
+    fn begin_value(&mut self) -> Result<()> {
+        if self.pending_key {
+            self.pending_key = false;
+            return Ok(());
+        }
+
+        if let Some(written) = self.frames.last_mut() {
+            if *written {
+                self.writer.write_all(b",").map_err(Error::custom)?;
+            } else {
+                *written = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_escaped(&mut self, s: &str) -> Result<()> {
+        self.writer.write_all(b"\"").map_err(Error::custom)?;
+        for c in s.chars() {
+            match c {
+                '"' => self.writer.write_all(b"\\\""),
+                '\\' => self.writer.write_all(b"\\\\"),
+                '\n' => self.writer.write_all(b"\\n"),
+                '\r' => self.writer.write_all(b"\\r"),
+                '\t' => self.writer.write_all(b"\\t"),
+                c if c.is_control() => {
+                    write!(self.writer, "\\u{:04x}", c as u32)
+                }
+                c => write!(self.writer, "{c}"),
+            }
+            .map_err(Error::custom)?;
+        }
+        self.writer.write_all(b"\"").map_err(Error::custom)
+    }
+}
+
+impl<W: io::Write> Marshal for JsonMarshal<W> {
+    fn human_readable(&self) -> bool {
+        true
+    }
+
+    fn bool(&mut self, v: bool) -> Result<()> {
+        self.begin_value()?;
+        write!(self.writer, "{v}").map_err(Error::custom)
+    }
+
+    fn i8(&mut self, v: i8) -> Result<()> {
+        self.begin_value()?;
+        write!(self.writer, "{v}").map_err(Error::custom)
+    }
+
+    fn i16(&mut self, v: i16) -> Result<()> {
+        self.begin_value()?;
+        write!(self.writer, "{v}").map_err(Error::custom)
+    }
+
+    fn i32(&mut self, v: i32) -> Result<()> {
+        self.begin_value()?;
+        write!(self.writer, "{v}").map_err(Error::custom)
+    }
+
+    fn u8(&mut self, v: u8) -> Result<()> {
+        self.begin_value()?;
+        write!(self.writer, "{v}").map_err(Error::custom)
+    }
+
+    fn u16(&mut self, v: u16) -> Result<()> {
+        self.begin_value()?;
+        write!(self.writer, "{v}").map_err(Error::custom)
+    }
+
+    fn u32(&mut self, v: u32) -> Result<()> {
+        self.begin_value()?;
+        write!(self.writer, "{v}").map_err(Error::custom)
+    }
+
+    fn u64(&mut self, v: u64) -> Result<()> {
+        self.begin_value()?;
+        write!(self.writer, "{v}").map_err(Error::custom)
+    }
+
+    fn f32(&mut self, v: f32) -> Result<()> {
+        if !v.is_finite() {
+            return Err(Error::custom("cannot represent a non-finite f32 in JSON"));
+        }
+        self.begin_value()?;
+        write!(self.writer, "{v}").map_err(Error::custom)
+    }
+
+    fn f64(&mut self, v: f64) -> Result<()> {
+        if !v.is_finite() {
+            return Err(Error::custom("cannot represent a non-finite f64 in JSON"));
+        }
+        self.begin_value()?;
+        write!(self.writer, "{v}").map_err(Error::custom)
+    }
+
+    fn str(&mut self, v: &[u8]) -> Result<()> {
+        self.begin_value()?;
+        self.write_escaped(&String::from_utf8_lossy(v))
+    }
+
+    fn wstr(&mut self, v: &[u16]) -> Result<()> {
+        self.begin_value()?;
+        self.write_escaped(&String::from_utf16_lossy(v))
+    }
+
+    fn begin_seq(&mut self, _len: usize) -> Result<()> {
+        self.begin_value()?;
+        self.writer.write_all(b"[").map_err(Error::custom)?;
+        self.frames.push(false);
+        Ok(())
+    }
+
+    fn end_seq(&mut self) -> Result<()> {
+        self.frames.pop();
+        self.writer.write_all(b"]").map_err(Error::custom)
+    }
+
+    fn begin_map(&mut self) -> Result<()> {
+        self.begin_value()?;
+        self.writer.write_all(b"{").map_err(Error::custom)?;
+        self.frames.push(false);
+        Ok(())
+    }
+
+    fn map_key(&mut self, key: &str) -> Result<()> {
+        self.begin_value()?;
+        self.write_escaped(key)?;
+        self.writer.write_all(b":").map_err(Error::custom)?;
+        self.pending_key = true;
+        Ok(())
+    }
+
+    fn end_map(&mut self) -> Result<()> {
+        self.frames.pop();
+        self.writer.write_all(b"}").map_err(Error::custom)
+    }
+}
+
+/// A [`Layout`] that represents a [`PropertyClass`]'s identity and
+/// properties as a JSON object, [`Container`]s as JSON arrays, and
+/// [`Enum`] variants as their human-readable name string.
+pub(crate) struct JsonLayout;
+
+impl JsonLayout {
+    pub(crate) fn new() -> Self {
+        Self
+    }
+
+    // Recursively writes `v`'s base class chain (outermost first) followed
+    // by `v`'s own properties, all as fields of the same JSON object.
+    fn write_properties(
+        &mut self,
+        m: &mut dyn Marshal,
+        v: &dyn PropertyClass,
+        baton: Baton,
+    ) -> Result<()> {
+        if let Some(base) = v.base() {
+            self.write_properties(m, base, baton)?;
+        }
+
+        for view in v.property_list().iter_properties() {
+            m.map_key(view.name())?;
+            serialize_value(self, m, v.property(view), baton)?;
+        }
+        Ok(())
+    }
+}
+
+impl Layout for JsonLayout {
+    fn identity(
+        &mut self,
+        m: &mut dyn Marshal,
+        v: Option<&'static PropertyList>,
+        _ty: IdentityType,
+        _baton: Baton,
+    ) -> Result<()> {
+        m.begin_map()?;
+        m.map_key("$type")?;
+        match v {
+            Some(list) => m.str(list.type_name().as_bytes()),
+            None => m.str(b""),
+        }
+    }
+
+    fn class(&mut self, m: &mut dyn Marshal, v: &dyn PropertyClass, baton: Baton) -> Result<()> {
+        self.write_properties(m, v, baton)?;
+        m.end_map()
+    }
+
+    fn container(&mut self, m: &mut dyn Marshal, v: &dyn Container, baton: Baton) -> Result<()> {
+        m.begin_seq(v.len())?;
+        for element in v.iter() {
+            serialize_value(self, m, element, baton)?;
+        }
+        m.end_seq()
+    }
+
+    fn enum_variant(&mut self, m: &mut dyn Marshal, v: &dyn Enum, _baton: Baton) -> Result<()> {
+        m.str(v.variant().as_bytes())
+    }
+}
+
+impl<W: io::Write> Serializer<JsonMarshal<W>, JsonLayout, NoopExt> {
+    /// Creates a serializer that writes reflected values as JSON to `writer`.
+    pub(crate) fn json(writer: W) -> Self {
+        Self::new(JsonMarshal::new(writer), JsonLayout::new())
+    }
+}
+
+/// An [`de::Unmarshal`] that reads JSON tokens from an eagerly buffered
+/// byte slice, the inverse of [`JsonMarshal`].
+pub(crate) struct JsonUnmarshal {
+    data: Vec<u8>,
+    pos: usize,
+    // Mirrors `JsonMarshal::frames`: whether the innermost open array/object
+    // already read an element, so a leading comma is expected before the
+    // next one.
+    frames: Vec<bool>,
+    // Mirrors `JsonMarshal::pending_key`: set right after `expect_key`
+    // consumes a field name, so the value that follows it doesn't also try
+    // to comma-separate itself from the key.
+    pending_key: bool,
+}
+
+impl JsonUnmarshal {
+    pub(crate) fn new(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            pos: 0,
+            frames: Vec::new(),
+            pending_key: false,
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn expect_byte(&mut self, expected: u8) -> Result<()> {
+        self.skip_ws();
+        match self.bump() {
+            Some(b) if b == expected => Ok(()),
+            Some(b) => Err(Error::custom(format!(
+                "expected `{}`, found `{}`",
+                expected as char, b as char
+            ))),
+            None => Err(Error::custom(format!(
+                "expected `{}`, found end of input",
+                expected as char
+            ))),
+        }
+    }
+
+    // Reads a comma if this value isn't the first in its enclosing
+    // array/object/document, mirroring `JsonMarshal::begin_value` in
+    // reverse.
+    fn begin_value(&mut self) -> Result<()> {
+        self.skip_ws();
+
+        if self.pending_key {
+            self.pending_key = false;
+            return Ok(());
+        }
+
+        if let Some(written) = self.frames.last_mut() {
+            if *written {
+                self.expect_byte(b',')?;
+            } else {
+                *written = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_bool_token(&mut self) -> Result<bool> {
+        self.skip_ws();
+        if self.data[self.pos..].starts_with(b"true") {
+            self.pos += 4;
+            Ok(true)
+        } else if self.data[self.pos..].starts_with(b"false") {
+            self.pos += 5;
+            Ok(false)
+        } else {
+            Err(Error::custom("expected `true` or `false`"))
+        }
+    }
+
+    fn parse_number_token(&mut self) -> Result<String> {
+        self.skip_ws();
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        while matches!(self.peek(), Some(b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-')) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(Error::custom("expected a number"));
+        }
+        std::str::from_utf8(&self.data[start..self.pos])
+            .map(str::to_owned)
+            .map_err(Error::custom)
+    }
+
+    fn parse_string_token(&mut self) -> Result<String> {
+        self.expect_byte(b'"')?;
+
+        let mut out = Vec::new();
+        loop {
+            let b = self.bump().ok_or_else(|| Error::custom("unterminated string"))?;
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let escape = self
+                        .bump()
+                        .ok_or_else(|| Error::custom("unterminated escape sequence"))?;
+                    match escape {
+                        b'"' => out.push(b'"'),
+                        b'\\' => out.push(b'\\'),
+                        b'/' => out.push(b'/'),
+                        b'n' => out.push(b'\n'),
+                        b'r' => out.push(b'\r'),
+                        b't' => out.push(b'\t'),
+                        b'u' => {
+                            let mut hex = [0u8; 4];
+                            for slot in &mut hex {
+                                *slot = self
+                                    .bump()
+                                    .ok_or_else(|| Error::custom("unterminated `\\u` escape"))?;
+                            }
+                            let hex = std::str::from_utf8(&hex).map_err(Error::custom)?;
+                            let code = u32::from_str_radix(hex, 16).map_err(Error::custom)?;
+                            let c = char::from_u32(code)
+                                .ok_or_else(|| Error::custom("invalid `\\u` escape"))?;
+                            let mut buf = [0; 4];
+                            out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                        }
+                        other => {
+                            return Err(Error::custom(format!(
+                                "invalid escape sequence `\\{}`",
+                                other as char
+                            )))
+                        }
+                    }
+                }
+                other => out.push(other),
+            }
+        }
+
+        String::from_utf8(out).map_err(Error::custom)
+    }
+}
+
+macro_rules! impl_int_unmarshal {
+    ($($method:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method(&mut self) -> Result<$ty> {
+                self.begin_value()?;
+                self.parse_number_token()?.parse().map_err(Error::custom)
+            }
+        )*
+    };
+}
+
+impl de::Unmarshal for JsonUnmarshal {
+    fn human_readable(&self) -> bool {
+        true
+    }
+
+    fn bool(&mut self) -> Result<bool> {
+        self.begin_value()?;
+        self.parse_bool_token()
+    }
+
+    impl_int_unmarshal! {
+        i8: i8, i16: i16, i32: i32,
+        u8: u8, u16: u16, u32: u32, u64: u64,
+        f32: f32, f64: f64,
+    }
+
+    fn str(&mut self) -> Result<Vec<u8>> {
+        self.begin_value()?;
+        Ok(self.parse_string_token()?.into_bytes())
+    }
+
+    fn wstr(&mut self) -> Result<Vec<u16>> {
+        self.begin_value()?;
+        Ok(self.parse_string_token()?.encode_utf16().collect())
+    }
+
+    fn skip(&mut self, len: usize) -> Result<()> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| Error::custom("attempted to skip past the end of the buffer"))?;
+        self.pos = end;
+        Ok(())
+    }
+
+    fn begin_seq(&mut self) -> Result<Option<usize>> {
+        self.begin_value()?;
+        self.expect_byte(b'[')?;
+        self.frames.push(false);
+        Ok(None)
+    }
+
+    fn seq_has_next(&mut self) -> Result<bool> {
+        self.skip_ws();
+        Ok(self.peek() != Some(b']'))
+    }
+
+    fn end_seq(&mut self) -> Result<()> {
+        self.frames.pop();
+        self.expect_byte(b']')
+    }
+
+    fn begin_map(&mut self) -> Result<()> {
+        self.begin_value()?;
+        self.expect_byte(b'{')?;
+        self.frames.push(false);
+        Ok(())
+    }
+
+    fn expect_key(&mut self, key: &str) -> Result<()> {
+        self.begin_value()?;
+        let found = self.parse_string_token()?;
+        if found != key {
+            return Err(Error::custom(format!(
+                "expected property `{key}`, found `{found}`"
+            )));
+        }
+        self.expect_byte(b':')?;
+        self.pending_key = true;
+        Ok(())
+    }
+
+    fn end_map(&mut self) -> Result<()> {
+        self.frames.pop();
+        self.expect_byte(b'}')
+    }
+}
+
+impl JsonLayout {
+    // Recursively reads `v`'s base class chain (outermost first) followed
+    // by `v`'s own properties, all as fields of the same JSON object.
+    fn read_properties(
+        &mut self,
+        m: &mut dyn de::Unmarshal,
+        v: &mut dyn PropertyClass,
+        baton: Baton,
+    ) -> Result<()> {
+        if let Some(base) = v.base_mut() {
+            self.read_properties(m, base, baton)?;
+        }
+
+        for view in v.property_list().iter_properties() {
+            m.expect_key(view.name())?;
+            de::deserialize_value(
+                &mut JsonDynDeserializer { m, layout: self },
+                v.property_mut(view),
+                baton,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// A minimal `DynDeserializer` wrapping a borrowed `Unmarshal`/`JsonLayout`
+// pair, so `de::deserialize_value` can recurse into nested classes and
+// containers without threading a whole `Deserializer<M, L, Ext>` through
+// `read_properties`'s recursive base-chain walk.
+struct JsonDynDeserializer<'a> {
+    m: &'a mut dyn de::Unmarshal,
+    layout: &'a mut JsonLayout,
+}
+
+impl de::DynDeserializer for JsonDynDeserializer<'_> {
+    fn unmarshal(&mut self) -> &mut dyn de::Unmarshal {
+        self.m
+    }
+
+    fn human_readable(&self) -> bool {
+        self.m.human_readable()
+    }
+
+    fn identity(
+        &mut self,
+        ty: IdentityType,
+        baton: Baton,
+    ) -> Result<Option<&'static PropertyList>> {
+        de::Layout::identity(self.layout, self.m, ty, baton)
+    }
+
+    fn class(&mut self, v: &mut dyn PropertyClass, baton: Baton) -> Result<()> {
+        de::Layout::class(self.layout, self.m, v, baton)
+    }
+
+    fn skip_value(&mut self, baton: Baton) -> Result<()> {
+        de::Layout::skip_value(self.layout, self.m, baton)
+    }
+
+    fn strict(&self) -> bool {
+        de::Layout::strict(self.layout)
+    }
+
+    fn container(
+        &mut self,
+        f: &mut dyn FnMut(&mut dyn de::ContainerVisitor, Baton) -> Result<()>,
+        baton: Baton,
+    ) -> Result<()> {
+        JsonLayout::container(self, f, baton)
+    }
+
+    fn enum_variant(&mut self, v: &mut dyn Enum, baton: Baton) -> Result<()> {
+        de::Layout::enum_variant(self.layout, self.m, v, baton)
+    }
+}
+
+impl de::Layout for JsonLayout {
+    fn identity(
+        &mut self,
+        m: &mut dyn de::Unmarshal,
+        _ty: IdentityType,
+        _baton: Baton,
+    ) -> Result<Option<&'static PropertyList>> {
+        m.begin_map()?;
+        m.expect_key("$type")?;
+        let name = String::from_utf8(m.str()?).map_err(Error::custom)?;
+
+        if name.is_empty() {
+            return Ok(None);
+        }
+
+        TypeRegistry::global()
+            .resolve_name(&name)
+            .map(Some)
+            .ok_or_else(|| Error::custom(format!("unknown type name `{name}`")))
+    }
+
+    fn class(
+        &mut self,
+        m: &mut dyn de::Unmarshal,
+        v: &mut dyn PropertyClass,
+        baton: Baton,
+    ) -> Result<()> {
+        self.read_properties(m, v, baton)?;
+        m.end_map()
+    }
+
+    fn skip_value(&mut self, _m: &mut dyn de::Unmarshal, _baton: Baton) -> Result<()> {
+        Err(Error::custom(
+            "skipping an unrecognized property is not supported by the JSON layout yet",
+        ))
+    }
+
+    fn container(
+        deserializer: &mut dyn de::DynDeserializer,
+        f: &mut dyn FnMut(&mut dyn de::ContainerVisitor, Baton) -> Result<()>,
+        baton: Baton,
+    ) -> Result<()> {
+        deserializer.unmarshal().begin_seq()?;
+        let mut visitor = JsonContainerVisitor { deserializer };
+        f(&mut visitor, baton)
+    }
+
+    fn enum_variant(
+        &mut self,
+        m: &mut dyn de::Unmarshal,
+        v: &mut dyn Enum,
+        _baton: Baton,
+    ) -> Result<()> {
+        let name = String::from_utf8(m.str()?).map_err(Error::custom)?;
+        if v.update_variant(&name) {
+            Ok(())
+        } else {
+            Err(Error::custom(format!("unknown enum variant `{name}`")))
+        }
+    }
+}
+
+struct JsonContainerVisitor<'a> {
+    deserializer: &'a mut dyn de::DynDeserializer,
+}
+
+impl de::ContainerVisitor for JsonContainerVisitor<'_> {
+    fn element_count(&self) -> Option<usize> {
+        None
+    }
+
+    fn next(&mut self, value: &mut dyn Type, baton: Baton) -> Result<bool> {
+        if !self.deserializer.unmarshal().seq_has_next()? {
+            self.deserializer.unmarshal().end_seq()?;
+            return Ok(false);
+        }
+        de::deserialize_value(self.deserializer, value, baton)?;
+        Ok(true)
+    }
+
+    fn skip_next(&mut self, _baton: Baton) -> Result<bool> {
+        if !self.deserializer.unmarshal().seq_has_next()? {
+            self.deserializer.unmarshal().end_seq()?;
+            return Ok(false);
+        }
+        Err(Error::custom(
+            "skipping a container element without materializing it is not supported",
+        ))
+    }
+}
+
+impl de::Deserializer<JsonUnmarshal, JsonLayout, NoopExt> {
+    /// Creates a deserializer that reads reflected values from the JSON
+    /// text produced by [`Serializer::json`].
+    pub(crate) fn json(mut reader: impl io::Read) -> Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).map_err(Error::custom)?;
+        Ok(Self::new(JsonUnmarshal::new(data), JsonLayout::new()))
+    }
+}
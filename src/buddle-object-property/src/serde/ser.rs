@@ -3,7 +3,11 @@
 use std::marker::PhantomData;
 
 use super::{result::*, Baton, IdentityType};
-use crate::{type_info::PropertyList, Container, Enum, PropertyClass};
+use crate::{
+    cpp::{RawString, RawWideString},
+    type_info::{DynReflected, PropertyList},
+    Container, Enum, PropertyClass, Type, TypeRef,
+};
 
 mod sealed {
     pub trait Sealed {}
@@ -54,6 +58,23 @@ pub trait Marshal {
 
     /// Marshals a wide string value.
     fn wstr(&mut self, v: &[u16]) -> Result<()>;
+
+    /// Begins a sequence of `len` values, e.g. a [`Container`]'s elements.
+    fn begin_seq(&mut self, len: usize) -> Result<()>;
+
+    /// Ends a sequence started by [`Marshal::begin_seq`].
+    fn end_seq(&mut self) -> Result<()>;
+
+    /// Begins a compound value made up of named fields, e.g. a
+    /// [`PropertyClass`]'s identity and properties.
+    fn begin_map(&mut self) -> Result<()>;
+
+    /// Marshals the name of the next field in a compound value opened by
+    /// [`Marshal::begin_map`].
+    fn map_key(&mut self, key: &str) -> Result<()>;
+
+    /// Ends a compound value started by [`Marshal::begin_map`].
+    fn end_map(&mut self) -> Result<()>;
 }
 
 /// Defines the handling of the data format around the
@@ -227,3 +248,65 @@ impl<M: Marshal, L: Layout, Ext: SerializerExt> DynSerializer for Serializer<M,
         self.layout.enum_variant(&mut self.marshal, v, baton)
     }
 }
+
+/// Serializes a single reflected value through `layout`, dispatching on its
+/// [`TypeRef`] category.
+///
+/// Shared by every [`Layout`] implementation's [`Layout::class`]/
+/// [`Layout::container`] so each only has to describe its own framing, not
+/// how to walk a [`PropertyClass`]'s or [`Container`]'s elements.
+pub(crate) fn serialize_value<L: Layout>(
+    layout: &mut L,
+    m: &mut dyn Marshal,
+    v: &dyn Type,
+    baton: Baton,
+) -> Result<()> {
+    match v.type_ref() {
+        TypeRef::Class(class) => {
+            layout.identity(m, Some(class.property_list()), IdentityType::Value, baton)?;
+            layout.class(m, class, baton)
+        }
+        TypeRef::Container(container) => layout.container(m, container, baton),
+        TypeRef::Enum(e) => layout.enum_variant(m, e, baton),
+        TypeRef::Value(value) => serialize_leaf(m, value),
+    }
+}
+
+/// Marshals a leaf [`Type`] that is neither a [`PropertyClass`], a
+/// [`Container`], nor an [`Enum`].
+///
+/// Only the primitive kinds [`Marshal`] itself knows how to encode are
+/// supported; anything else (e.g. `Ptr`/`SharedPtr` polymorphic pointers)
+/// has no generic representation yet and is reported as an error instead.
+fn serialize_leaf(m: &mut dyn Marshal, v: &dyn Type) -> Result<()> {
+    macro_rules! try_leaf {
+        ($ty:ty, $method:ident) => {
+            if let Some(v) = v.downcast_ref::<$ty>() {
+                return m.$method(*v);
+            }
+        };
+    }
+
+    try_leaf!(bool, bool);
+    try_leaf!(i8, i8);
+    try_leaf!(i16, i16);
+    try_leaf!(i32, i32);
+    try_leaf!(u8, u8);
+    try_leaf!(u16, u16);
+    try_leaf!(u32, u32);
+    try_leaf!(u64, u64);
+    try_leaf!(f32, f32);
+    try_leaf!(f64, f64);
+
+    if let Some(v) = v.downcast_ref::<RawString>() {
+        return m.str(&v.0);
+    }
+    if let Some(v) = v.downcast_ref::<RawWideString>() {
+        return m.wstr(&v.0);
+    }
+
+    Err(Error::custom(format!(
+        "no generic Marshal/Layout support for leaf type `{}`",
+        v.type_info().type_name()
+    )))
+}
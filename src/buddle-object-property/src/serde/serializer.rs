@@ -1,4 +1,8 @@
-use std::io::{self, Read};
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+    sync::{Arc, Weak},
+};
 
 use buddle_bit_buf::BitWriter;
 use byteorder::{WriteBytesExt, LE};
@@ -20,6 +24,8 @@ macro_rules! impl_write_len {
             fn $fn(&mut self, len: usize) {
                 if self.config.flags.contains(SerializerFlags::COMPACT_LENGTH_PREFIXES) {
                     self.write_compact_length_prefix(len);
+                } else if self.config.flags.contains(SerializerFlags::VARINT) {
+                    self.write_varint(len as u128);
                 } else {
                     self.writer.$write_fn(len as _);
                 }
@@ -35,6 +41,11 @@ pub struct Serializer<'a> {
     writer: BitWriter,
     config: Config,
     tag: &'a dyn TypeTag,
+
+    /// Identity map from a shared pointee's `Arc` allocation address to the
+    /// small integer id assigned to it, so repeated `SharedPtr`/`WeakPtr`
+    /// occurrences of the same allocation serialize the object only once.
+    shared_refs: HashMap<*const (), u32>,
 }
 
 impl<'a> Serializer<'a> {
@@ -44,6 +55,7 @@ impl<'a> Serializer<'a> {
             writer: BitWriter::new(),
             config,
             tag,
+            shared_refs: HashMap::new(),
         };
 
         // As an optimization, we can write the flags directly if we
@@ -124,6 +136,13 @@ impl<'a> Serializer<'a> {
             None => return,
         };
 
+        self.serialize_object(obj);
+    }
+
+    // Serializes `obj`'s properties (and the pre-/post-save hooks around
+    // them), without writing a type tag; `try_serialize` writes the tag
+    // before calling this.
+    fn serialize_object(&mut self, obj: &mut dyn PropertyClass) {
         obj.on_pre_save();
 
         if self.config.shallow {
@@ -142,6 +161,66 @@ impl<'a> Serializer<'a> {
         obj.on_post_save();
     }
 
+    /// Serializes a `SharedPtr<T>`'s pointee through the shared-pointer
+    /// graph: the first time a given allocation is encountered, its id is
+    /// written followed by the full object; every later occurrence of the
+    /// same allocation writes only the id, avoiding duplicate data for
+    /// aliased `Arc`s.
+    ///
+    /// This method may be used to implement
+    /// [`Type::serialize`][crate::Type::serialize] for `SharedPtr<T>`.
+    pub fn serialize_shared(&mut self, value: &mut Arc<dyn PropertyClass>) {
+        let ptr = Arc::as_ptr(value) as *const ();
+
+        if let Some(&id) = self.shared_refs.get(&ptr) {
+            self.writer.write_bit(false);
+            self.writer.u32(id);
+            return;
+        }
+
+        let id = self.shared_refs.len() as u32;
+        self.shared_refs.insert(ptr, id);
+
+        self.writer.write_bit(true);
+        self.writer.u32(id);
+
+        match Arc::get_mut(value) {
+            Some(obj) => self.try_serialize(Some(obj)),
+
+            // Another `SharedPtr`/`WeakPtr` is aliasing this allocation, so
+            // we can't get exclusive access to run the usual `&mut`
+            // `on_pre_save`/`on_post_save` hooks on it directly. Serialize
+            // an independent deep copy instead; the real, still-shared
+            // object is left untouched.
+            None => {
+                let mut clone = value.deep_clone();
+                self.try_serialize(Some(&mut *clone));
+            }
+        }
+    }
+
+    /// Serializes a `WeakPtr<T>` through the shared-pointer graph: writes
+    /// the id already assigned to its pointee by some `SharedPtr<T>`
+    /// elsewhere in the graph, or a null marker if the pointee is dead or
+    /// was never assigned one.
+    ///
+    /// This method may be used to implement
+    /// [`Type::serialize`][crate::Type::serialize] for `WeakPtr<T>`.
+    pub fn serialize_weak(&mut self, value: &Weak<dyn PropertyClass>) {
+        let id = value.upgrade().and_then(|arc| {
+            let ptr = Arc::as_ptr(&arc) as *const ();
+            self.shared_refs.get(&ptr).copied()
+        });
+
+        match id {
+            Some(id) => {
+                self.writer.write_bit(true);
+                self.writer.u32(id);
+            }
+            None => self.writer.write_bit(false),
+        }
+    }
+
     /// Provides access to the underlying [`BitWriter`].
     #[inline]
     pub fn writer(&mut self) -> &mut BitWriter {
@@ -158,6 +237,46 @@ impl<'a> Serializer<'a> {
         }
     }
 
+    /// Writes `value` using a bincode-style variable-length encoding: a
+    /// tag byte below `251` is the value itself, and `251`..=`254` select
+    /// a little-endian `u16`/`u32`/`u64`/`u128` payload wide enough to
+    /// hold it.
+    fn write_varint(&mut self, value: u128) {
+        if value < 251 {
+            self.writer.u8(value as u8);
+        } else if let Ok(v) = u16::try_from(value) {
+            self.writer.u8(251);
+            self.writer.u16(v);
+        } else if let Ok(v) = u32::try_from(value) {
+            self.writer.u8(252);
+            self.writer.u32(v);
+        } else if let Ok(v) = u64::try_from(value) {
+            self.writer.u8(253);
+            self.writer.u64(v);
+        } else {
+            self.writer.u8(254);
+            self.writer.u128(value);
+        }
+    }
+
+    /// Serializes a [`u32`] using [`SerializerFlags::VARINT`] encoding.
+    ///
+    /// This method may be used to implement
+    /// [`Type::serialize`][crate::Type::serialize] for integer leaves that
+    /// opt into the varint representation.
+    pub fn write_varint_u32(&mut self, value: u32) {
+        self.write_varint(value as u128);
+    }
+
+    /// Serializes a [`u64`] using [`SerializerFlags::VARINT`] encoding.
+    ///
+    /// This method may be used to implement
+    /// [`Type::serialize`][crate::Type::serialize] for integer leaves that
+    /// opt into the varint representation.
+    pub fn write_varint_u64(&mut self, value: u64) {
+        self.write_varint(value as u128);
+    }
+
     impl_write_len! {
         // Used for strings, where the length is written as `u16`.
         write_str_len = u16(),
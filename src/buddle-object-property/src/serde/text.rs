@@ -0,0 +1,236 @@
+//! A flat, human-editable text export/import for `PropertyClass` trees.
+//!
+//! Unlike the binary and JSON formats, this one does not nest: every leaf
+//! value is written as a single `path = value` line, where `path` follows
+//! the same syntax [`PathAccess`] accepts (`foo.bar[2].baz`), in a stable
+//! depth-first order of base classes, properties, and container elements.
+//! This makes the output suitable for hand-editing, diffing, and checking
+//! into version control.
+//!
+//! Only the scalar/string leaf types this module knows how to parse back
+//! (the primitive numeric types, [`bool`], [`RawString`], and [`Enum`]
+//! variants) can be edited through [`import`]; other leaf types are still
+//! exported (via their `Debug` representation) so the file stays a
+//! complete snapshot, but an edit to one of those lines is rejected rather
+//! than silently dropped.
+
+use std::{fmt::Write as _, fs, io, path::Path};
+
+use crate::{cpp::RawString, path::PathAccess, Container, Enum, PropertyClass, Type, TypeMut, TypeRef};
+
+/// Renders `obj`'s reflected property tree as `path = value` lines, one
+/// leaf per line, in a stable depth-first order.
+pub fn export(obj: &dyn PropertyClass) -> String {
+    let mut out = String::new();
+    write_class(&mut out, "", obj);
+    out
+}
+
+/// Idempotently writes [`export`]'s output for `obj` to `path`.
+///
+/// If `path` already holds byte-identical content, the file is left
+/// untouched, so re-exporting an unchanged tree produces no spurious
+/// version-control diff.
+pub fn export_to_file(obj: &dyn PropertyClass, path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    let rendered = export(obj);
+
+    if let Ok(existing) = fs::read(path) {
+        if existing == rendered.as_bytes() {
+            return Ok(());
+        }
+    }
+
+    fs::write(path, rendered)
+}
+
+/// Applies the `path = value` lines produced by [`export`] onto `obj`,
+/// only mutating properties whose current textual value differs from the
+/// line's.
+pub fn import(obj: &mut dyn PropertyClass, text: &str) -> anyhow::Result<()> {
+    for (lineno, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (path, value) = line
+            .split_once(" = ")
+            .ok_or_else(|| anyhow::anyhow!("line {}: expected `path = value`", lineno + 1))?;
+
+        apply_one(obj, path, value).map_err(|e| anyhow::anyhow!("line {}: {e}", lineno + 1))?;
+    }
+
+    Ok(())
+}
+
+/// Reads `path` and applies it to `obj` with [`import`].
+pub fn import_from_file(obj: &mut dyn PropertyClass, path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let text = fs::read_to_string(path)?;
+    import(obj, &text)
+}
+
+// Recursively writes `obj`'s base class chain (outermost first) followed
+// by `obj`'s own properties, with `path` as the already-built prefix for
+// `obj` itself (empty at the root).
+fn write_class(out: &mut String, path: &str, obj: &dyn PropertyClass) {
+    if let Some(base) = obj.base() {
+        write_class(out, path, base);
+    }
+
+    for view in obj.property_list().iter_properties() {
+        let child = if path.is_empty() {
+            view.name().to_owned()
+        } else {
+            format!("{path}.{}", view.name())
+        };
+        write_value(out, &child, obj.property(view));
+    }
+}
+
+fn write_value(out: &mut String, path: &str, value: &dyn Type) {
+    match value.type_ref() {
+        TypeRef::Class(class) => write_class(out, path, class),
+        TypeRef::Container(container) => {
+            for (idx, element) in container.iter().enumerate() {
+                write_value(out, &format!("{path}[{idx}]"), element);
+            }
+        }
+        TypeRef::Enum(e) => {
+            let _ = writeln!(out, "{path} = {}", e.variant());
+        }
+        TypeRef::Value(v) => {
+            let _ = writeln!(out, "{path} = {}", render_leaf(v));
+        }
+    }
+}
+
+// Applies a single `path = value` line to `obj`. A no-op if `value`
+// already matches the property's current rendering.
+fn apply_one(obj: &mut dyn PropertyClass, path: &str, value: &str) -> anyhow::Result<()> {
+    let current = (&*obj as &dyn Type).path(path)?;
+    if matches!(current.type_ref(), TypeRef::Value(v) if render_leaf(v) == value) {
+        return Ok(());
+    }
+    if matches!(current.type_ref(), TypeRef::Enum(e) if e.variant() == value) {
+        return Ok(());
+    }
+
+    match (obj as &mut dyn Type).path_mut(path)?.type_mut() {
+        TypeMut::Enum(e) => {
+            if !e.update_variant(value) {
+                anyhow::bail!("`{path}` has no enum variant named `{value}`");
+            }
+            Ok(())
+        }
+        TypeMut::Value(v) => set_leaf(v, value),
+        TypeMut::Class(_) | TypeMut::Container(_) => {
+            anyhow::bail!("`{path}` does not refer to a leaf value")
+        }
+    }
+}
+
+// Renders a leaf `Value` the same way for both `export` and the
+// no-op comparison in `apply_one`.
+fn render_leaf(value: &dyn Type) -> String {
+    macro_rules! try_render {
+        ($ty:ty) => {
+            if let Some(v) = value.downcast_ref::<$ty>() {
+                return v.to_string();
+            }
+        };
+    }
+
+    try_render!(bool);
+    try_render!(i8);
+    try_render!(i16);
+    try_render!(i32);
+    try_render!(u8);
+    try_render!(u16);
+    try_render!(u32);
+    try_render!(u64);
+    try_render!(f32);
+    try_render!(f64);
+
+    if let Some(v) = value.downcast_ref::<RawString>() {
+        return quote(&String::from_utf8_lossy(v));
+    }
+
+    // No text codec for this leaf type: fall back to `Debug` so the
+    // export stays a complete snapshot. `set_leaf` rejects mutating
+    // these, so an unedited line still round-trips byte-for-byte.
+    format!("{value:?}")
+}
+
+fn set_leaf(value: &mut dyn Type, text: &str) -> anyhow::Result<()> {
+    macro_rules! try_parse {
+        ($ty:ty) => {
+            if let Some(slot) = value.downcast_mut::<$ty>() {
+                *slot = text
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("invalid {} value `{text}`: {e}", stringify!($ty)))?;
+                return Ok(());
+            }
+        };
+    }
+
+    try_parse!(bool);
+    try_parse!(i8);
+    try_parse!(i16);
+    try_parse!(i32);
+    try_parse!(u8);
+    try_parse!(u16);
+    try_parse!(u32);
+    try_parse!(u64);
+    try_parse!(f32);
+    try_parse!(f64);
+
+    if let Some(slot) = value.downcast_mut::<RawString>() {
+        *slot = RawString::from(unquote(text)?);
+        return Ok(());
+    }
+
+    anyhow::bail!(
+        "property of type `{}` has no text codec to parse an edit from",
+        value.type_info().type_name()
+    )
+}
+
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn unquote(s: &str) -> anyhow::Result<String> {
+    let inner = s
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(|| anyhow::anyhow!("expected a quoted string, found `{s}`"))?;
+
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('"') => out.push('"'),
+                Some('\\') => out.push('\\'),
+                Some('n') => out.push('\n'),
+                Some(other) => anyhow::bail!("invalid escape sequence `\\{other}`"),
+                None => anyhow::bail!("unterminated escape sequence"),
+            },
+            c => out.push(c),
+        }
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,325 @@
+//! A bridge from the *ObjectProperty* reflection model onto the data model
+//! of the `serde` crate, independent of [`crate::serde`]'s own bit-packed
+//! format.
+//!
+//! [`SerializeType`] implements [`Serialize`] by dispatching on
+//! [`Type::type_ref`]: classes become maps of property name to value,
+//! containers become sequences, enums become their variant name, and leaf
+//! values become whatever primitive they actually hold. This lets
+//! reflected objects round-trip through any serde-backed format (JSON,
+//! YAML, RON, ...) instead of just this crate's own wire format.
+//!
+//! A blanket `impl Serialize for &dyn Type` isn't possible here - it would
+//! conflict with serde's own `impl<T: ?Sized + Serialize> Serialize for &T`
+//! - so [`SerializeType`] wraps the reference instead, the same workaround
+//! `erased_serde` uses for trait objects in general.
+//!
+//! [`deserialize_in_place`] is the inverse: rather than constructing a new
+//! value the way [`Deserialize`] normally would, it fills an existing
+//! `&mut dyn Type` from any [`Deserializer`], routing map entries to
+//! properties by name through [`TypeInfo`][crate::type_info::TypeInfo] -
+//! the same in-place shape every [`Type::deserialize`] in this crate
+//! already has to work with.
+
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::cpp::{RawString, RawWideString};
+use crate::{Container, Enum, PropertyClass, Type, TypeMut, TypeRef};
+
+/// Adapts a reflected `&dyn Type` to [`Serialize`].
+pub struct SerializeType<'a>(pub &'a dyn Type);
+
+impl Serialize for SerializeType<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0.type_ref() {
+            TypeRef::Class(class) => serialize_class(class, serializer),
+            TypeRef::Container(container) => serialize_container(container, serializer),
+            TypeRef::Enum(e) => e.variant().serialize(serializer),
+            TypeRef::Value(value) => serialize_leaf(value, serializer),
+        }
+    }
+}
+
+fn serialize_class<S: Serializer>(
+    class: &dyn PropertyClass,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(None)?;
+    serialize_properties(class, &mut map)?;
+    map.end()
+}
+
+// Recursively emits `class`'s base class chain (outermost first) followed
+// by `class`'s own properties, all as entries of the same map - mirroring
+// `PropertyClass::deep_clone`'s base-to-derived walk.
+fn serialize_properties<M: SerializeMap>(
+    class: &dyn PropertyClass,
+    map: &mut M,
+) -> Result<(), M::Error> {
+    if let Some(base) = class.base() {
+        serialize_properties(base, map)?;
+    }
+
+    for view in class.property_list().iter_properties() {
+        map.serialize_entry(view.name(), &SerializeType(class.property(view)))?;
+    }
+
+    Ok(())
+}
+
+fn serialize_container<S: Serializer>(
+    container: &dyn Container,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(container.len()))?;
+    for element in container.iter() {
+        seq.serialize_element(&SerializeType(element))?;
+    }
+    seq.end()
+}
+
+// Marshals a leaf `Type` that is neither a `PropertyClass`, a `Container`,
+// nor an `Enum`. Only the primitive kinds serde itself knows how to encode
+// are supported; `Ptr`/`SharedPtr` polymorphic pointers have no generic
+// representation yet and are reported as an error instead, mirroring
+// `crate::serde::ser::serialize_leaf`.
+fn serialize_leaf<S: Serializer>(value: &dyn Type, serializer: S) -> Result<S::Ok, S::Error> {
+    macro_rules! try_leaf {
+        ($ty:ty) => {
+            if let Some(v) = value.downcast_ref::<$ty>() {
+                return v.serialize(serializer);
+            }
+        };
+    }
+
+    try_leaf!(bool);
+    try_leaf!(i8);
+    try_leaf!(i16);
+    try_leaf!(i32);
+    try_leaf!(u8);
+    try_leaf!(u16);
+    try_leaf!(u32);
+    try_leaf!(u64);
+    try_leaf!(f32);
+    try_leaf!(f64);
+
+    if let Some(v) = value.downcast_ref::<RawString>() {
+        return String::from_utf8_lossy(&v.0).serialize(serializer);
+    }
+    if let Some(v) = value.downcast_ref::<RawWideString>() {
+        return String::from_utf16_lossy(&v.0).serialize(serializer);
+    }
+
+    Err(serde::ser::Error::custom(format_args!(
+        "no generic serde support for leaf type `{}`",
+        value.type_info().type_name()
+    )))
+}
+
+/// Fills `value` in place from `deserializer`, dispatching on
+/// [`Type::type_mut`] the way [`SerializeType`] reads it back out via
+/// [`Type::type_ref`].
+pub fn deserialize_in_place<'de, D: Deserializer<'de>>(
+    value: &mut dyn Type,
+    deserializer: D,
+) -> Result<(), D::Error> {
+    match value.type_mut() {
+        TypeMut::Class(class) => deserialize_class(class, deserializer),
+        TypeMut::Container(container) => deserialize_container(container, deserializer),
+        TypeMut::Enum(e) => deserialize_enum(e, deserializer),
+        TypeMut::Value(v) => deserialize_leaf(v, deserializer),
+    }
+}
+
+// A `DeserializeSeed` that drives `deserialize_in_place` for a single
+// already-located `&mut dyn Type`, shared by container elements and class
+// properties.
+struct TypeSeed<'v> {
+    value: &'v mut dyn Type,
+}
+
+impl<'de, 'v> DeserializeSeed<'de> for TypeSeed<'v> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserialize_in_place(self.value, deserializer)
+    }
+}
+
+fn deserialize_class<'de, D: Deserializer<'de>>(
+    class: &mut dyn PropertyClass,
+    deserializer: D,
+) -> Result<(), D::Error> {
+    struct ClassVisitor<'c> {
+        class: &'c mut dyn PropertyClass,
+    }
+
+    impl<'de, 'c> Visitor<'de> for ClassVisitor<'c> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a map of reflected property values")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let class = self.class;
+
+            while let Some(key) = map.next_key::<String>()? {
+                match property_mut_recursive(&mut *class, &key) {
+                    Some(value) => map.next_value_seed(TypeSeed { value })?,
+
+                    // Unknown property; discard its value so data with
+                    // extra fields from a newer revision still loads.
+                    None => drop(map.next_value::<de::IgnoredAny>()?),
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    deserializer.deserialize_map(ClassVisitor { class })
+}
+
+// Finds the property named `name` anywhere in `class`'s base class chain,
+// mutably, the way `serialize_properties` reads the same chain immutably.
+fn property_mut_recursive<'c>(
+    class: &'c mut dyn PropertyClass,
+    name: &str,
+) -> Option<&'c mut dyn Type> {
+    if let Some(view) = class.property_list().property(name) {
+        return Some(class.property_mut(view));
+    }
+
+    class.base_mut().and_then(|base| property_mut_recursive(base, name))
+}
+
+fn deserialize_container<'de, D: Deserializer<'de>>(
+    container: &mut dyn Container,
+    deserializer: D,
+) -> Result<(), D::Error> {
+    struct ContainerVisitor<'c> {
+        container: &'c mut dyn Container,
+    }
+
+    impl<'de, 'c> Visitor<'de> for ContainerVisitor<'c> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a sequence of reflected values")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let container = self.container;
+
+            // Fixed-length containers (e.g. `[T; N]`) can't grow through
+            // `push_default`, so fill their existing slots in place instead
+            // and reject a source sequence of the wrong length.
+            if container.is_fixed_len() {
+                let len = container.len();
+
+                for idx in 0..len {
+                    let value = container
+                        .get_mut(idx)
+                        .expect("idx is within the fixed-length container's bounds");
+
+                    if seq.next_element_seed(TypeSeed { value })?.is_none() {
+                        return Err(de::Error::custom(format_args!(
+                            "expected {len} elements for a fixed-length container, got {idx}"
+                        )));
+                    }
+                }
+
+                if seq.next_element::<de::IgnoredAny>()?.is_some() {
+                    return Err(de::Error::custom(format_args!(
+                        "expected exactly {len} elements for a fixed-length container"
+                    )));
+                }
+
+                return Ok(());
+            }
+
+            if let Some(hint) = seq.size_hint() {
+                container.reserve(hint);
+            }
+
+            loop {
+                let value = container.push_default();
+                if seq.next_element_seed(TypeSeed { value })?.is_none() {
+                    // The sequence ended; drop the slot pushed for it.
+                    container.pop();
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    deserializer.deserialize_seq(ContainerVisitor { container })
+}
+
+fn deserialize_enum<'de, D: Deserializer<'de>>(
+    e: &mut dyn Enum,
+    deserializer: D,
+) -> Result<(), D::Error> {
+    let variant = String::deserialize(deserializer)?;
+
+    if !e.update_variant(&variant) {
+        return Err(de::Error::custom(format_args!(
+            "unknown enum variant `{variant}`"
+        )));
+    }
+
+    Ok(())
+}
+
+fn deserialize_leaf<'de, D: Deserializer<'de>>(
+    value: &mut dyn Type,
+    deserializer: D,
+) -> Result<(), D::Error> {
+    macro_rules! try_leaf {
+        ($ty:ty) => {
+            if value.is::<$ty>() {
+                let parsed = <$ty as Deserialize<'de>>::deserialize(deserializer)?;
+                value
+                    .set(Box::new(parsed))
+                    .unwrap_or_else(|_| unreachable!("value was just checked to be this type"));
+                return Ok(());
+            }
+        };
+    }
+
+    try_leaf!(bool);
+    try_leaf!(i8);
+    try_leaf!(i16);
+    try_leaf!(i32);
+    try_leaf!(u8);
+    try_leaf!(u16);
+    try_leaf!(u32);
+    try_leaf!(u64);
+    try_leaf!(f32);
+    try_leaf!(f64);
+
+    if value.is::<RawString>() {
+        let parsed = String::deserialize(deserializer)?;
+        value
+            .set(Box::new(RawString::from(parsed)))
+            .unwrap_or_else(|_| unreachable!("value was just checked to be this type"));
+        return Ok(());
+    }
+    if value.is::<RawWideString>() {
+        let parsed = String::deserialize(deserializer)?;
+        value
+            .set(Box::new(RawWideString::from(parsed)))
+            .unwrap_or_else(|_| unreachable!("value was just checked to be this type"));
+        return Ok(());
+    }
+
+    Err(de::Error::custom(format_args!(
+        "no generic serde support for leaf type `{}`",
+        value.type_info().type_name()
+    )))
+}
@@ -8,7 +8,7 @@ use crate::{
     property_class::PropertyClass,
     r#enum::Enum,
     serde::{Deserializer, Serializer},
-    type_info::DynReflected,
+    type_info::{DynReflected, TypeInfo},
 };
 
 /// An immutable reference to a value categorized by varying data types.
@@ -95,6 +95,32 @@ pub trait Type: Any + Sync + Send + Debug + DynReflected + 'static {
     /// passed back in the [`Err`] variant of the [`Result`].
     fn set(&mut self, value: Box<dyn Type>) -> Result<(), Box<dyn Type>>;
 
+    /// Attempts to losslessly convert `self` into the numeric leaf type
+    /// described by `target`.
+    ///
+    /// This only ever succeeds between registered numeric leaf types (the
+    /// built-in integers and the bit-sized `uN`/`iN` wrappers), and only for
+    /// conversions that cannot lose information: widening between integers
+    /// of the same signedness, and range-checked conversions to or from a
+    /// bit-sized wrapper. Every other implementor keeps the default `None`.
+    ///
+    /// [`Type::set`] falls back to this when a direct downcast fails, so
+    /// assigning e.g. an `i16` property value into an `i32` property works
+    /// without the caller having to convert it by hand.
+    fn try_convert(&self, target: &TypeInfo) -> Option<Box<dyn Type>> {
+        let _ = target;
+        None
+    }
+
+    /// Creates an owned, independent copy of `self`'s value.
+    ///
+    /// `Type` cannot simply require [`Clone`] since that is not object-safe,
+    /// so every implementation provides this explicitly instead. Prefer
+    /// [`PropertyClass::deep_clone`] as the entry point for duplicating a
+    /// whole reflected object graph; this method is its per-property
+    /// building block.
+    fn clone_type(&self) -> Box<dyn Type>;
+
     /// Serializes `self` to the given [`Serializer`].
     ///
     /// Serialization is infallible so this method does not return anything.
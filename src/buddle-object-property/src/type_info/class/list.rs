@@ -121,6 +121,47 @@ impl PropertyList {
             .map(|p| p.make_access(self.type_id()))
     }
 
+    /// Like [`PropertyList::property`], but also searches the base class
+    /// chain when `name` is not declared directly on this type.
+    ///
+    /// A [`PropertyAccess`] is only valid when read through the exact
+    /// object whose [`PropertyList`] produced it, so a hit in an ancestor
+    /// is returned together with a reference to that ancestor object -
+    /// obtained by repeatedly rebasing `obj` through
+    /// [`PropertyList::base_value`] - rather than the original `obj`.
+    pub fn property_recursive<'a>(
+        &'static self,
+        obj: &'a dyn PropertyClass,
+        name: &str,
+    ) -> Option<(&'a dyn PropertyClass, PropertyAccess<'static>)> {
+        if let Some(view) = self.property(name) {
+            return Some((obj, view));
+        }
+
+        let base_list = self.base_list()?;
+        let base = self.base_value(obj)?;
+        base_list.property_recursive(base, name)
+    }
+
+    /// Like [`PropertyList::property_for`], but also searches the base
+    /// class chain when `hash` is not declared directly on this type.
+    ///
+    /// See [`PropertyList::property_recursive`] for why the match is
+    /// returned together with the ancestor object it belongs to.
+    pub fn property_for_recursive<'a>(
+        &'static self,
+        obj: &'a dyn PropertyClass,
+        hash: u32,
+    ) -> Option<(&'a dyn PropertyClass, PropertyAccess<'static>)> {
+        if let Some(view) = self.property_for(hash) {
+            return Some((obj, view));
+        }
+
+        let base_list = self.base_list()?;
+        let base = self.base_value(obj)?;
+        base_list.property_for_recursive(base, hash)
+    }
+
     /// Attempts to find a property at a specified index.
     ///
     /// NOTE: This does not scan [`PropertyList`]s of base types for the
@@ -0,0 +1,165 @@
+//! Generic traversal of reflected `Type`/`PropertyClass` trees.
+//!
+//! [`PathAccess`][crate::path::PathAccess] resolves a single path into a
+//! value; [`walk`] and [`walk_mut`] instead drive a [`TypeVisitor`] (or
+//! [`TypeVisitorMut`]) over an entire tree, in the same base-to-derived,
+//! depth-first order [`PropertyClass::deep_clone`][crate::PropertyClass::deep_clone]
+//! already relies on internally. This gives serialization, structural
+//! diffing, validation, and redaction one shared traversal instead of each
+//! hand-rolling its own recursion. [`TypeFold`] builds on [`walk_mut`] to
+//! additionally replace leaf values in place, mirroring rustc's
+//! `TypeFoldable`.
+
+use crate::{property_class::PropertyClass, r#type::Type, TypeMut, TypeRef};
+
+/// Observes an immutable reflected tree as [`walk`] drives it.
+///
+/// All hooks default to doing nothing, so implementations only need to
+/// override the ones relevant to their use case.
+pub trait TypeVisitor {
+    /// Called once for every [`PropertyClass`] reached, before its base
+    /// class and properties are visited.
+    fn visit_class(&mut self, class: &dyn PropertyClass) {
+        let _ = class;
+    }
+
+    /// Called for every property of a [`PropertyClass`], before recursing
+    /// into its value.
+    fn visit_property(&mut self, name: &str, hash: u32, value: &dyn Type) {
+        let (_, _, _) = (name, hash, value);
+    }
+
+    /// Called for every element of a container, before recursing into it.
+    fn visit_container_element(&mut self, index: usize, value: &dyn Type) {
+        let (_, _) = (index, value);
+    }
+
+    /// Called for every leaf value (an [`Enum`][crate::Enum] or a plain
+    /// [`Value`][TypeRef::Value]) that terminates the traversal.
+    fn visit_leaf(&mut self, value: &dyn Type) {
+        let _ = value;
+    }
+}
+
+/// Walks `value`, dispatching to `v`'s hooks in base-to-derived,
+/// depth-first order.
+///
+/// [`PropertyClass`] values descend into their base class first (via
+/// [`base_value`][crate::type_info::PropertyList::base_value], so the
+/// [`DynMetadata`][std::ptr::DynMetadata] fat pointer stays correct) before
+/// their own properties; container values descend into their elements in
+/// order; everything else is a leaf.
+pub fn walk(value: &dyn Type, v: &mut impl TypeVisitor) {
+    match value.type_ref() {
+        TypeRef::Class(class) => {
+            v.visit_class(class);
+
+            let list = class.property_list();
+            if let Some(base) = list.base_value(class) {
+                walk(base as &dyn Type, v);
+            }
+
+            for view in list.iter_properties() {
+                let value = class.property(view);
+                v.visit_property(view.name(), view.hash(), value);
+                walk(value, v);
+            }
+        }
+
+        TypeRef::Container(container) => {
+            for (index, element) in container.iter().enumerate() {
+                v.visit_container_element(index, element);
+                walk(element, v);
+            }
+        }
+
+        TypeRef::Enum(_) | TypeRef::Value(_) => v.visit_leaf(value),
+    }
+}
+
+/// Observes a mutable reflected tree as [`walk_mut`] drives it.
+///
+/// All hooks default to doing nothing, so implementations only need to
+/// override the ones relevant to their use case.
+pub trait TypeVisitorMut {
+    /// Called once for every [`PropertyClass`] reached, before its base
+    /// class and properties are visited.
+    fn visit_class(&mut self, class: &mut dyn PropertyClass) {
+        let _ = class;
+    }
+
+    /// Called for every property of a [`PropertyClass`], before recursing
+    /// into its value.
+    fn visit_property(&mut self, name: &str, hash: u32, value: &mut dyn Type) {
+        let (_, _, _) = (name, hash, value);
+    }
+
+    /// Called for every element of a container, before recursing into it.
+    fn visit_container_element(&mut self, index: usize, value: &mut dyn Type) {
+        let (_, _) = (index, value);
+    }
+
+    /// Called for every leaf value (an [`Enum`][crate::Enum] or a plain
+    /// [`Value`][TypeMut::Value]) that terminates the traversal.
+    fn visit_leaf(&mut self, value: &mut dyn Type) {
+        let _ = value;
+    }
+}
+
+/// The mutating counterpart to [`walk`], using
+/// [`base_value_mut`][crate::type_info::PropertyList::base_value_mut] and
+/// [`PropertyClass::property_mut`] to reach the same values in place.
+pub fn walk_mut(value: &mut dyn Type, v: &mut impl TypeVisitorMut) {
+    match value.type_mut() {
+        TypeMut::Class(class) => {
+            v.visit_class(class);
+
+            let list = class.property_list();
+            if let Some(base) = list.base_value_mut(class) {
+                walk_mut(base as &mut dyn Type, v);
+            }
+
+            for view in list.iter_properties() {
+                let value = class.property_mut(view);
+                v.visit_property(view.name(), view.hash(), value);
+                walk_mut(value, v);
+            }
+        }
+
+        TypeMut::Container(container) => {
+            for index in 0..container.len() {
+                // `get_mut` returns an `Option`; every `index` here is below
+                // the `len()` read above, so the element is always found.
+                if let Some(element) = container.get_mut(index) {
+                    v.visit_container_element(index, element);
+                    walk_mut(element, v);
+                }
+            }
+        }
+
+        TypeMut::Enum(_) | TypeMut::Value(_) => v.visit_leaf(value),
+    }
+}
+
+/// Replaces leaf values of a reflected tree in place, mirroring rustc's
+/// `TypeFoldable`.
+pub trait TypeFold {
+    /// Called for every leaf value (an [`Enum`][crate::Enum] or a plain
+    /// [`Value`][TypeMut::Value]) reached by [`fold`]; may mutate `value`
+    /// to replace it.
+    fn fold_leaf(&mut self, value: &mut dyn Type);
+}
+
+/// Walks `value` like [`walk_mut`], calling [`TypeFold::fold_leaf`] on
+/// every leaf so `f` can replace it in place.
+pub fn fold(value: &mut dyn Type, f: &mut impl TypeFold) {
+    struct Adapter<'f, F>(&'f mut F);
+
+    impl<F: TypeFold> TypeVisitorMut for Adapter<'_, F> {
+        fn visit_leaf(&mut self, value: &mut dyn Type) {
+            self.0.fold_leaf(value);
+        }
+    }
+
+    walk_mut(value, &mut Adapter(f));
+}
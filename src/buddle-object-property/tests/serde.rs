@@ -0,0 +1,123 @@
+use buddle_object_property::{
+    serde::{Config, Deserializer, Serializer, SerializerFlags, TypeTag},
+    PropertyClass, Type,
+};
+
+#[derive(Debug, Default, Type)]
+struct Inner {
+    #[property(flags(TRANSMIT))]
+    x: u32,
+}
+
+#[derive(Debug, Default, Type)]
+struct Outer {
+    #[property(flags(TRANSMIT))]
+    a: u32,
+    #[property(flags(TRANSMIT))]
+    b: i32,
+    #[property(flags(TRANSMIT))]
+    inner: Inner,
+}
+
+/// A [`TypeTag`] that only ever tags [`Outer`] objects, mirroring how
+/// `TypeRegistry` maps a type hash to a constructor but without the lookup.
+struct SingleTag;
+
+impl TypeTag for SingleTag {
+    fn read_tag(
+        &self,
+        de: &mut Deserializer<'_>,
+    ) -> anyhow::Result<Option<Box<dyn PropertyClass>>> {
+        let hash = de.reader().u32()?;
+        if hash == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(Box::<Outer>::default()))
+    }
+
+    fn validate_tag(
+        &self,
+        de: &mut Deserializer<'_>,
+        obj: &dyn PropertyClass,
+    ) -> anyhow::Result<()> {
+        let hash = de.reader().u32()?;
+        if hash == obj.property_list().type_hash() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("type hash mismatch"))
+        }
+    }
+
+    fn write_tag(&self, ser: &mut Serializer<'_>, obj: Option<&dyn PropertyClass>) {
+        ser.writer()
+            .u32(obj.map_or(0, |class| class.property_list().type_hash()));
+    }
+}
+
+/// Serializes `obj`, deserializes the result, re-serializes it and asserts
+/// that both passes produced byte-identical output.
+fn assert_roundtrip(config: Config, obj: &mut Outer) {
+    let tag = SingleTag;
+
+    let mut ser = Serializer::new(config, &tag);
+    ser.serialize(obj);
+    let first = ser.finish().unwrap();
+
+    let mut scratch = Vec::new();
+    let mut de = Deserializer::new(config, &tag);
+    de.load(&first, &mut scratch).unwrap();
+    let mut roundtripped = de.deserialize().unwrap();
+
+    let mut ser = Serializer::new(config, &tag);
+    ser.serialize(&mut *roundtripped);
+    let second = ser.finish().unwrap();
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn shallow_roundtrip() {
+    assert_roundtrip(
+        Config {
+            shallow: true,
+            ..Config::new()
+        },
+        &mut Outer {
+            a: 7,
+            b: -5,
+            inner: Inner { x: 42 },
+        },
+    );
+}
+
+#[test]
+fn deep_roundtrip() {
+    assert_roundtrip(
+        Config {
+            shallow: false,
+            ..Config::new()
+        },
+        &mut Outer {
+            a: 7,
+            b: -5,
+            inner: Inner { x: 42 },
+        },
+    );
+}
+
+#[test]
+fn compressed_roundtrip() {
+    assert_roundtrip(
+        Config {
+            shallow: false,
+            flags: SerializerFlags::COMPRESS,
+            ..Config::new()
+        },
+        &mut Outer {
+            a: 7,
+            b: -5,
+            inner: Inner { x: 42 },
+        },
+    );
+}
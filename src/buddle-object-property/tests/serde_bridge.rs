@@ -0,0 +1,30 @@
+use buddle_object_property::{deserialize_in_place, SerializeType, Type};
+
+#[derive(Debug, Default, Type)]
+struct Fixed {
+    #[property]
+    data: [u32; 3],
+}
+
+#[test]
+fn fixed_length_array_roundtrip() {
+    let original = Fixed { data: [1, 2, 3] };
+
+    let json = serde_json::to_value(SerializeType(&original)).unwrap();
+
+    let mut roundtripped = Fixed::default();
+    deserialize_in_place(&mut roundtripped, json).unwrap();
+
+    assert_eq!(roundtripped.data, original.data);
+}
+
+#[test]
+fn fixed_length_array_rejects_wrong_length() {
+    let mut target = Fixed::default();
+
+    let too_short = serde_json::json!({ "data": [1, 2] });
+    assert!(deserialize_in_place(&mut target, too_short).is_err());
+
+    let too_long = serde_json::json!({ "data": [1, 2, 3, 4] });
+    assert!(deserialize_in_place(&mut target, too_long).is_err());
+}
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use buddle_object_property::{
+    cpp::{SharedPtr, WeakPtr},
+    registry::TypeRegistry,
+    serde::{Config, Deserializer, Serializer},
+    PropertyClass, Type,
+};
+
+#[derive(Debug, Default, Type)]
+struct Node {
+    #[property(flags(TRANSMIT | PRIVILEGED_TRANSMIT))]
+    id: u32,
+}
+
+#[derive(Debug, Type)]
+struct Item {
+    #[property(flags(TRANSMIT | PRIVILEGED_TRANSMIT))]
+    node: SharedPtr<Node>,
+    #[property(flags(TRANSMIT | PRIVILEGED_TRANSMIT))]
+    back: WeakPtr<Node>,
+}
+
+impl Default for Item {
+    fn default() -> Self {
+        Self {
+            node: SharedPtr::try_new(Arc::new(Node::default())).unwrap(),
+            back: WeakPtr::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Type)]
+struct Root {
+    #[property(flags(TRANSMIT | PRIVILEGED_TRANSMIT))]
+    items: Vec<Item>,
+}
+
+// Deserializing `Root.items` exercises the generic `Vec<T>` container path
+// (`impl_container!`), with a `WeakPtr` nested inside each element - the
+// scenario where a pointer captured mid-deserialization would dangle if an
+// element were built on the stack and moved into the `Vec` afterwards.
+#[test]
+fn weak_ptr_inside_container_resolves_after_roundtrip() {
+    let node0: Arc<dyn PropertyClass> = Arc::new(Node { id: 1 });
+    let shared0 = SharedPtr::<Node>::try_new(Arc::clone(&node0)).unwrap();
+    let weak0 = shared0.downgrade();
+
+    let node1: Arc<dyn PropertyClass> = Arc::new(Node { id: 2 });
+    let shared1 = SharedPtr::<Node>::try_new(node1).unwrap();
+
+    let mut root = Root {
+        items: vec![
+            Item {
+                node: shared0,
+                back: WeakPtr::default(),
+            },
+            Item {
+                node: shared1,
+                // References the first item's node, which was already
+                // assigned a shared-pointer graph id by the time this one
+                // is serialized.
+                back: weak0,
+            },
+        ],
+    };
+
+    let tag = TypeRegistry::global();
+
+    let mut ser = Serializer::new(Config::new(), tag);
+    ser.serialize(&mut root);
+    let data = ser.finish().unwrap();
+
+    let mut scratch = Vec::new();
+    let mut de = Deserializer::new(Config::new(), tag);
+    de.load(&data, &mut scratch).unwrap();
+    let roundtripped = de.deserialize().unwrap();
+
+    let roundtripped: &Root = roundtripped.as_any().downcast_ref().unwrap();
+
+    assert!(roundtripped.items[0].back.upgrade().is_none());
+
+    let resolved = roundtripped.items[1]
+        .back
+        .upgrade()
+        .expect("weak pointer inside the container should resolve to the shared node");
+    assert_eq!(resolved.get().id, 1);
+}
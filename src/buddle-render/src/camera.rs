@@ -62,7 +62,7 @@ impl Camera {
             BufferUsages::UNIFORM | BufferUsages::COPY_DST,
         );
         let camera_bind_group = ctx.create_bind_group(
-            &ctx.create_bind_group_layout(vec![BindGroupLayoutEntry::Buffer]),
+            &ctx.create_bind_group_layout(vec![BindGroupLayoutEntry::Buffer { dynamic: false, min_binding_size: None }]),
             vec![camera_buffer.as_entire_binding()],
         );
         Rasterizer::new(self, camera_buffer, camera_bind_group)
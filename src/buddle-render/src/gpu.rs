@@ -8,4 +8,34 @@ mod descriptors;
 pub use descriptors::*;
 
 mod render_buffer;
-pub use render_buffer::*;
\ No newline at end of file
+pub use render_buffer::*;
+
+mod texture;
+pub use texture::*;
+
+mod atlas;
+pub use atlas::*;
+
+mod pipeline_cache;
+pub use pipeline_cache::PipelineCache;
+
+mod shader;
+pub use shader::*;
+
+mod preprocessor;
+pub use preprocessor::preprocess;
+
+mod shadow;
+pub use shadow::*;
+
+mod profiler;
+pub use profiler::FrameTimings;
+
+mod cull;
+pub use cull::FRUSTUM_CULL;
+
+mod uniform_storage;
+pub use uniform_storage::*;
+
+mod decal;
+pub use decal::*;
\ No newline at end of file
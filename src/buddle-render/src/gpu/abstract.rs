@@ -7,6 +7,10 @@ use crate::{Context, Texture, Vertex};
 pub struct Surface {
     pub(crate) surface: wgpu::Surface,
     pub(crate) config: wgpu::SurfaceConfiguration,
+    /// The present modes `surface` actually reported support for, so
+    /// [`PresentMode::into_wgpu`](crate::PresentMode::into_wgpu) can fall
+    /// back to `Fifo` without needing the adapter again.
+    pub(crate) available_present_modes: Vec<wgpu::PresentMode>,
 }
 
 impl Surface {
@@ -20,9 +24,53 @@ pub struct Shader {
     pub(crate) pipeline: wgpu::RenderPipeline,
 }
 
-pub struct RenderTexture {
+/// A compute-only counterpart to [`Shader`], used for GPU work that never
+/// rasterizes anything, e.g. [`crate::gpu::FrustumCuller`].
+pub struct ComputeShader {
+    pub(crate) module: wgpu::ShaderModule,
+    pub(crate) pipeline: wgpu::ComputePipeline,
+}
+
+/// An offscreen render target a scene can be rendered into instead of the
+/// swapchain, e.g. for reflections, thumbnails, or feeding a later
+/// post-processing pass. Carries its own depth and opaque/accum/reveal OIT
+/// buffers, sized and (for the opaque pass) formatted to match how
+/// [`Context`] sets up the equivalent textures for the swapchain, so
+/// [`crate::RenderBuffer::render_to`] can run the identical two-pass plus
+/// composite pipeline against it.
+///
+/// `texture` is the resolved, single-sampled color output; its underlying
+/// `wgpu::Texture` carries `TEXTURE_BINDING`, so once a scene has been
+/// rendered into it, it can be bound as an input texture to a later
+/// material or pass. Its format is whatever was requested of
+/// [`Context::create_render_target`], independent of the opaque/accum/
+/// reveal intermediates, since the final composite pass is a plain
+/// textured quad that can write to any color format.
+pub struct RenderTarget {
     pub texture: Texture,
-    pub depth: Texture,
+    pub(crate) format: wgpu::TextureFormat,
+    pub(crate) depth: Texture,
+    pub(crate) oit_opaque: Texture,
+    pub(crate) oit_accum: Texture,
+    pub(crate) oit_reveal: Texture,
+    /// Multisampled attachments the opaque/OIT passes render into
+    /// directly, resolved down into the single-sampled textures above
+    /// once each pass ends. `None` for a target created with [`MSAA::Off`](crate::MSAA::Off).
+    pub(crate) msaa: Option<MsaaAttachments>,
+}
+
+/// The multisampled counterparts of a [`RenderTarget`]'s depth and
+/// opaque/accum/reveal buffers, rendered into directly so the pipelines
+/// bound against them can resolve anti-aliased edges down on pass end.
+pub(crate) struct MsaaAttachments {
+    pub(crate) depth: wgpu::TextureView,
+    pub(crate) oit_opaque: wgpu::TextureView,
+    pub(crate) oit_accum: wgpu::TextureView,
+    pub(crate) oit_reveal: wgpu::TextureView,
+    /// The sample count the attachments above were created with, kept
+    /// alongside them since [`wgpu::RenderBundle`]s must be built against
+    /// the exact sample count of the pass they're replayed into.
+    pub(crate) sample_count: u32,
 }
 
 pub struct Mesh {
@@ -33,6 +81,47 @@ pub struct Mesh {
     pub(crate) index_buffer: wgpu::Buffer,
     pub(crate) model_buffer: wgpu::Buffer,
     pub(crate) model_bind_group: wgpu::BindGroup,
+    /// A local-space bounding sphere (`[center.x, center.y, center.z,
+    /// radius]`) enclosing every vertex, used by [`crate::gpu::FrustumCuller`]
+    /// to test this mesh's draw-call instances against the camera frustum
+    /// without walking the full vertex list every frame.
+    pub(crate) bounding_sphere: [f32; 4],
+    /// Set by [`Context::create_instanced_mesh`](crate::Context::create_instanced_mesh),
+    /// an `Instance`-stepped vertex buffer of [`InstanceData`](crate::InstanceData)
+    /// bound alongside `vertex_buffer` when drawn via
+    /// [`RenderBuffer::add_instanced_draw_call`](crate::RenderBuffer::add_instanced_draw_call).
+    /// `None` for meshes built with [`Context::create_mesh`], which carry
+    /// their single transform in `model_bind_group` instead.
+    pub(crate) instance_buffer: Option<wgpu::Buffer>,
+    /// The number of instances `instance_buffer` currently holds.
+    pub(crate) instance_count: u32,
+}
+
+/// Computes a simple (not necessarily minimal) bounding sphere around
+/// `vertices`: centered on their axis-aligned bounding box, sized to
+/// reach the farthest vertex from that center. Good enough for frustum
+/// culling, where a slightly oversized sphere only costs the occasional
+/// false-positive draw, never a wrongly culled one.
+pub(crate) fn bounding_sphere(vertices: &[Vertex]) -> [f32; 4] {
+    if vertices.is_empty() {
+        return [0.0, 0.0, 0.0, 0.0];
+    }
+
+    let mut min = Vec3::splat(f32::MAX);
+    let mut max = Vec3::splat(f32::MIN);
+    for vertex in vertices {
+        let position = Vec3::from(vertex.position);
+        min = min.min(position);
+        max = max.max(position);
+    }
+
+    let center = (min + max) * 0.5;
+    let radius = vertices
+        .iter()
+        .map(|vertex| Vec3::from(vertex.position).distance(center))
+        .fold(0.0_f32, f32::max);
+
+    [center.x, center.y, center.z, radius]
 }
 
 impl Mesh {
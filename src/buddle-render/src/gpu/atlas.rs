@@ -0,0 +1,217 @@
+//! Packing multiple small textures into a single atlas
+//!
+//! Binding one texture per draw call fragments bind groups and inflates
+//! draw-call overhead for scenes with many small UI/material textures.
+//! [`AtlasBuilder`] packs RGBA8 images into a single [`Texture`] using a
+//! shelf/skyline strategy, handing back the UV sub-rectangle each input
+//! image ended up at.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use buddle_math::{Vec2, UVec2};
+
+use crate::{Context, Texture};
+
+/// A horizontal strip of the atlas at a fixed height, with an x-cursor
+/// tracking how much of its width has been claimed so far.
+struct Shelf {
+    y: u32,
+    height: u32,
+    cursor_x: u32,
+}
+
+/// A UV sub-rectangle into a packed [`Texture`], usable to remap mesh UVs
+/// into atlas space: `uv_in_atlas = offset + uv_in_source * scale`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AtlasRect {
+    pub offset: Vec2,
+    pub scale: Vec2,
+}
+
+/// Texels of border padding reserved around each packed image, filled by
+/// repeating its edge pixels, so bilinear sampling near a tile's edge
+/// can't bleed into whatever landed next to it in the atlas.
+const GUTTER: u32 = 2;
+
+/// Builds a single packed [`Texture`] out of many smaller RGBA8 images.
+///
+/// Rectangles are placed on the first open shelf whose height fits them
+/// with the least wasted vertical space, opening a new shelf when none
+/// fits. When a rectangle doesn't fit the atlas at all, its size is
+/// doubled (in whichever dimension keeps it as square as possible) and
+/// packing restarts. Each rectangle reserves an extra [`GUTTER`]-texel
+/// border so its [`AtlasRect`] never samples a neighboring image.
+pub struct AtlasBuilder<K> {
+    size: UVec2,
+    shelves: Vec<Shelf>,
+    entries: Vec<(K, UVec2, Vec<u8>)>,
+}
+
+impl<K: Eq + Hash + Clone> AtlasBuilder<K> {
+    /// Creates a new builder with an initial atlas size. Growth happens
+    /// automatically as needed once [`AtlasBuilder::build`] is called.
+    pub fn new(initial_size: UVec2) -> Self {
+        Self {
+            size: initial_size,
+            shelves: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Queues an RGBA8 image of `size` keyed by `key` for packing.
+    ///
+    /// `rgba8` must contain exactly `size.x * size.y * 4` bytes.
+    pub fn add(&mut self, key: K, size: UVec2, rgba8: Vec<u8>) -> &mut Self {
+        debug_assert_eq!(rgba8.len(), (size.x * size.y * 4) as usize);
+        self.entries.push((key, size, rgba8));
+        self
+    }
+
+    /// Packs every queued image and uploads the result as a single
+    /// [`Texture`], returning it alongside each key's [`AtlasRect`].
+    pub fn build(mut self, ctx: &Context) -> (Texture, HashMap<K, AtlasRect>) {
+        // Pack the tallest rectangles first: this tends to leave less
+        // awkward leftover space on each shelf than insertion order would.
+        self.entries.sort_by(|a, b| b.1.y.cmp(&a.1.y));
+
+        loop {
+            self.shelves.clear();
+            let mut placements = Vec::with_capacity(self.entries.len());
+            let mut overflowed = false;
+
+            for (key, size, _) in &self.entries {
+                let padded = UVec2::new(size.x + 2 * GUTTER, size.y + 2 * GUTTER);
+                match self.place(padded) {
+                    // `place` hands back the padded box's origin; the image
+                    // itself starts `GUTTER` texels in from there.
+                    Some((x, y)) => placements.push((key.clone(), x + GUTTER, y + GUTTER)),
+                    None => {
+                        overflowed = true;
+                        break;
+                    }
+                }
+            }
+
+            if !overflowed {
+                let mut pixels = vec![0u8; (self.size.x * self.size.y * 4) as usize];
+                let mut rects = HashMap::with_capacity(self.entries.len());
+
+                for ((key, x, y), (_, size, rgba8)) in placements.into_iter().zip(&self.entries) {
+                    blit_with_gutter(&mut pixels, self.size, *size, x, y, rgba8);
+
+                    rects.insert(
+                        key,
+                        AtlasRect {
+                            offset: Vec2::new(x as f32 / self.size.x as f32, y as f32 / self.size.y as f32),
+                            scale: Vec2::new(
+                                size.x as f32 / self.size.x as f32,
+                                size.y as f32 / self.size.y as f32,
+                            ),
+                        },
+                    );
+                }
+
+                return (ctx.create_texture(&pixels, self.size), rects);
+            }
+
+            // Grow the atlas and retry, keeping it as square as possible.
+            if self.size.x <= self.size.y {
+                self.size.x *= 2;
+            } else {
+                self.size.y *= 2;
+            }
+        }
+    }
+
+    /// Finds a spot for a rectangle of `size`, opening or growing shelves
+    /// as needed. Returns `None` if it doesn't fit the current atlas size
+    /// at all.
+    fn place(&mut self, size: UVec2) -> Option<(u32, u32)> {
+        let mut best: Option<usize> = None;
+
+        for (i, shelf) in self.shelves.iter().enumerate() {
+            if shelf.height >= size.y && self.size.x - shelf.cursor_x >= size.x {
+                let wasted = shelf.height - size.y;
+                let better = match best {
+                    Some(b) => wasted < self.shelves[b].height - size.y,
+                    None => true,
+                };
+                if better {
+                    best = Some(i);
+                }
+            }
+        }
+
+        if let Some(i) = best {
+            let shelf = &mut self.shelves[i];
+            let x = shelf.cursor_x;
+            shelf.cursor_x += size.x;
+            return Some((x, shelf.y));
+        }
+
+        // No existing shelf fits; open a new one at the bottom of the
+        // stack, if there's room.
+        let y = self.shelves.iter().map(|s| s.y + s.height).max().unwrap_or(0);
+        if y + size.y > self.size.y || size.x > self.size.x {
+            return None;
+        }
+
+        self.shelves.push(Shelf {
+            y,
+            height: size.y,
+            cursor_x: size.x,
+        });
+        Some((0, y))
+    }
+}
+
+fn blit(dst: &mut [u8], dst_size: UVec2, src_size: UVec2, x: u32, y: u32, src: &[u8]) {
+    for row in 0..src_size.y {
+        let src_start = (row * src_size.x * 4) as usize;
+        let src_row = &src[src_start..src_start + (src_size.x * 4) as usize];
+
+        let dst_start = (((y + row) * dst_size.x + x) * 4) as usize;
+        dst[dst_start..dst_start + src_row.len()].copy_from_slice(src_row);
+    }
+}
+
+/// Like [`blit`], but also repeats `src`'s edge texels into the
+/// [`GUTTER`]-texel border reserved around it at `(x, y)`.
+fn blit_with_gutter(dst: &mut [u8], dst_size: UVec2, src_size: UVec2, x: u32, y: u32, src: &[u8]) {
+    blit(dst, dst_size, src_size, x, y, src);
+
+    let texel = |ix: u32, iy: u32| -> [u8; 4] {
+        let start = ((iy * src_size.x + ix) * 4) as usize;
+        [src[start], src[start + 1], src[start + 2], src[start + 3]]
+    };
+    let put = |dst: &mut [u8], dx: u32, dy: u32, color: [u8; 4]| {
+        let start = ((dy * dst_size.x + dx) * 4) as usize;
+        dst[start..start + 4].copy_from_slice(&color);
+    };
+
+    for g in 1..=GUTTER {
+        for col in 0..src_size.x {
+            put(dst, x + col, y - g, texel(col, 0));
+            put(dst, x + col, y + src_size.y - 1 + g, texel(col, src_size.y - 1));
+        }
+        for row in 0..src_size.y {
+            put(dst, x - g, y + row, texel(0, row));
+            put(dst, x + src_size.x - 1 + g, y + row, texel(src_size.x - 1, row));
+        }
+    }
+
+    for gx in 1..=GUTTER {
+        for gy in 1..=GUTTER {
+            put(dst, x - gx, y - gy, texel(0, 0));
+            put(dst, x + src_size.x - 1 + gx, y - gy, texel(src_size.x - 1, 0));
+            put(dst, x - gx, y + src_size.y - 1 + gy, texel(0, src_size.y - 1));
+            put(
+                dst,
+                x + src_size.x - 1 + gx,
+                y + src_size.y - 1 + gy,
+                texel(src_size.x - 1, src_size.y - 1),
+            );
+        }
+    }
+}
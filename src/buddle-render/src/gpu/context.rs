@@ -11,6 +11,9 @@ use buddle_math::{Mat4, UVec2};
 
 use crate::camera::ModelMatrices;
 use crate::gpu::*;
+use crate::gpu::pipeline_cache;
+use crate::gpu::profiler::GpuProfiler;
+use crate::gpu::cull::FrustumCuller;
 
 pub struct Context {
     pub(crate) device: wgpu::Device,
@@ -20,11 +23,58 @@ pub struct Context {
     pub(crate) oit_opaque: Texture,
     pub(crate) oit_accum: Texture,
     pub(crate) oit_reveal: Texture,
-    shader_cache: RefCell<HashMap<(&'static str, SimplifiedPipelineConfig), Rc<Shader>>>,
+    /// The sample count the swapchain's own depth/OIT attachments are kept
+    /// in sync with; see [`Self::sample_count`].
+    sample_count: u32,
+    /// Multisampled counterparts of `depth_buffer`/`oit_*` that the
+    /// swapchain path renders directly into and resolves down, mirroring
+    /// [`RenderTarget`]'s own `msaa` field. `None` below [`MSAA::On`]'s
+    /// minimum of 2 samples, in which case the pass renders straight into
+    /// the single-sampled textures above.
+    pub(crate) msaa: Option<MsaaAttachments>,
+    shader_cache: RefCell<HashMap<(&'static str, Vec<&'static str>, SimplifiedPipelineConfig), Rc<Shader>>>,
+    pipeline_cache: Option<PipelineCache>,
+    /// Forces every [`Context::create_shader`] call to recompile instead of
+    /// reusing the on-disk pipeline cache, so debug builds can iterate on
+    /// shaders without stale artifacts.
+    pub bypass_cache: bool,
+    pub(crate) static_bundle_cache: RefCell<Option<StaticBundleCache>>,
+    /// `None` when the adapter doesn't support [`wgpu::Features::TIMESTAMP_QUERY`].
+    pub(crate) profiler: Option<GpuProfiler>,
+    /// Lazily built on first use, since it needs a [`Context`] to compile
+    /// its compute pipeline against.
+    pub(crate) frustum_culler: RefCell<Option<FrustumCuller>>,
+}
+
+/// Cached [`wgpu::RenderBundle`]s for a [`RenderBuffer`](crate::RenderBuffer)'s
+/// static draw calls, pre-encoded once and replayed via `execute_bundles`
+/// at near-zero CPU cost every frame until the static set (or the pass
+/// formats/sample count it was built against) changes.
+pub(crate) struct StaticBundleCache {
+    pub(crate) key: u64,
+    pub(crate) opaque: wgpu::RenderBundle,
+    pub(crate) oit: wgpu::RenderBundle,
 }
 
 impl Context {
+    /// Creates a new [`Context`], blocking the current thread until the GPU
+    /// adapter and device are ready.
+    ///
+    /// Only available off `wasm32`, where there is a thread to block: the
+    /// browser never hands back control synchronously, so a `wasm32` build
+    /// must drive [`Self::new_async`] from its own async entry point
+    /// instead (see `buddle`'s web target).
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn new<W: HasRawWindowHandle + HasRawDisplayHandle>(window: &W, size: UVec2) -> Self {
+        pollster::block_on(Self::new_async(window, size))
+    }
+
+    /// Async variant of [`Self::new`], required on `wasm32` and usable
+    /// anywhere else an executor is already driving the caller.
+    pub async fn new_async<W: HasRawWindowHandle + HasRawDisplayHandle>(
+        window: &W,
+        size: UVec2,
+    ) -> Self {
         // The instance is a handle to our GPU
         // Backends::all => Vulkan + Metal + DX12 + Browser WebGPU
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
@@ -33,22 +83,35 @@ impl Context {
         });
 
         let surface = unsafe { instance.create_surface(window) }.unwrap();
-        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            compatible_surface: Some(&surface),
-            force_fallback_adapter: false,
-        }))
-        .unwrap();
-
-        let (device, queue) = pollster::block_on(adapter.request_device(
-            &wgpu::DeviceDescriptor {
-                features: wgpu::Features::empty(),
-                limits: wgpu::Limits::default(),
-                label: None,
-            },
-            None,
-        ))
-        .unwrap();
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: Some(&surface),
+                force_fallback_adapter: false,
+            })
+            .await
+            .unwrap();
+
+        // Only request pipeline caching when the adapter actually supports
+        // it; otherwise `create_pipeline` builds every pipeline without a
+        // cache.
+        let features = wgpu::Features::PIPELINE_CACHE & adapter.features();
+        // Only request timestamp queries when the adapter supports them;
+        // `Context::profiler` is `None` otherwise and every profiled pass
+        // simply skips recording timestamp writes.
+        let timestamp_features = wgpu::Features::TIMESTAMP_QUERY & adapter.features();
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    features: features | timestamp_features,
+                    limits: wgpu::Limits::default(),
+                    label: None,
+                },
+                None,
+            )
+            .await
+            .unwrap();
 
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps
@@ -64,8 +127,7 @@ impl Context {
             format: surface_format,
             width: size.x,
             height: size.y,
-            // todo: control vsync properly
-            present_mode: surface_caps.present_modes[0],
+            present_mode: PresentMode::AutoVsync.into_wgpu(&surface_caps.present_modes),
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
         };
@@ -76,16 +138,62 @@ impl Context {
         let oit_accum = Self::create_oit_accum_texture(&device, size);
         let oit_reveal = Self::create_oit_reveal_texture(&device, size);
 
+        let sample_count = 4;
+        let msaa = Self::create_msaa_attachments(&device, size, sample_count, surface_format);
+
+        let profiler = device
+            .features()
+            .contains(wgpu::Features::TIMESTAMP_QUERY)
+            .then(|| GpuProfiler::new(&device, &queue));
+
         Context {
             device,
             queue,
-            surface: Surface { surface, config },
+            surface: Surface {
+                surface,
+                config,
+                available_present_modes: surface_caps.present_modes,
+            },
             depth_buffer,
             oit_opaque,
             oit_accum,
             oit_reveal,
+            sample_count,
+            msaa,
             shader_cache: RefCell::new(HashMap::new()),
+            pipeline_cache: PipelineCache::open(),
+            bypass_cache: false,
+            static_bundle_cache: RefCell::new(None),
+            profiler,
+            frustum_culler: RefCell::new(None),
+        }
+    }
+
+    /// The number of instances a single [`Self::dispatch_compute`]
+    /// workgroup handles; must match `@workgroup_size` in every compute
+    /// shader dispatched that way.
+    pub(crate) const COMPUTE_WORKGROUP_SIZE: u32 = 64;
+
+    /// Returns the lazily-built [`FrustumCuller`], compiling its compute
+    /// pipeline the first time this is called.
+    pub(crate) fn frustum_culler(&self) -> std::cell::Ref<FrustumCuller> {
+        if self.frustum_culler.borrow().is_none() {
+            *self.frustum_culler.borrow_mut() = Some(FrustumCuller::new(self));
         }
+
+        std::cell::Ref::map(self.frustum_culler.borrow(), |culler| {
+            culler.as_ref().unwrap()
+        })
+    }
+
+    /// How long last frame's opaque, OIT, and composite passes took on the
+    /// GPU. Every field is `None` if the adapter doesn't support
+    /// [`wgpu::Features::TIMESTAMP_QUERY`].
+    pub fn frame_timings(&self) -> FrameTimings {
+        self.profiler
+            .as_ref()
+            .map(|profiler| profiler.timings())
+            .unwrap_or_default()
     }
 
     /// Resizes the internal surface
@@ -99,6 +207,12 @@ impl Context {
             self.oit_opaque = Self::create_oit_opaque_texture(&self.device, &self.surface.config);
             self.oit_accum = Self::create_oit_accum_texture(&self.device, new_size);
             self.oit_reveal = Self::create_oit_reveal_texture(&self.device, new_size);
+            self.msaa = Self::create_msaa_attachments(
+                &self.device,
+                new_size,
+                self.sample_count,
+                self.surface.config.format,
+            );
 
             self.reconfigure();
         }
@@ -108,6 +222,47 @@ impl Context {
         self.surface.configure(&self.device);
     }
 
+    /// The swapchain's current present mode, falling back to
+    /// [`wgpu::PresentMode::Fifo`] wherever it was chosen for by
+    /// [`PresentMode::into_wgpu`] at creation or the last
+    /// [`Self::set_present_mode`] call.
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.surface.config.present_mode
+    }
+
+    /// Changes the swapchain's present mode, falling back to
+    /// [`PresentMode::Fifo`] if `mode` isn't supported on this surface, and
+    /// reconfiguring immediately so the change is visible next frame.
+    /// `resize` keeps reusing this mode afterwards, since it only ever
+    /// touches `width`/`height` on the same [`wgpu::SurfaceConfiguration`].
+    pub fn set_present_mode(&mut self, mode: PresentMode) {
+        self.surface.config.present_mode = mode.into_wgpu(&self.surface.available_present_modes);
+        self.reconfigure();
+    }
+
+    /// The sample count the swapchain's depth/OIT attachments are built
+    /// with; materials drawn via [`RenderBuffer::submit`](crate::RenderBuffer::submit)
+    /// must themselves be built with a matching `MSAA::On` (or `MSAA::Off`
+    /// if this is `1`) for their pipeline's sample count to agree with the
+    /// pass they're drawn into.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Changes the swapchain's multisample count, immediately rebuilding
+    /// its depth/OIT attachments at the new count and the current size.
+    /// Existing materials keep whatever sample count their pipeline was
+    /// already built with, so callers should rebuild those too.
+    pub fn set_sample_count(&mut self, sample_count: u32) {
+        self.sample_count = sample_count;
+        self.msaa = Self::create_msaa_attachments(
+            &self.device,
+            UVec2::new(self.surface.config.width, self.surface.config.height),
+            sample_count,
+            self.surface.config.format,
+        );
+    }
+
     pub fn create_buffer<T>(&self, data: &[T], usage: wgpu::BufferUsages) -> wgpu::Buffer
     where
         T: bytemuck::Pod,
@@ -130,6 +285,30 @@ impl Context {
             .write_buffer(&buffer, 0, bytemuck::cast_slice(data));
     }
 
+    /// Like [`Self::update_buffer`], but writes `data` at `offset` instead
+    /// of the start of `buffer`, e.g. one slot of a [`UniformStorage`].
+    pub(crate) fn update_buffer_at<T>(&self, buffer: &wgpu::Buffer, offset: wgpu::BufferAddress, data: &[T])
+    where
+        T: bytemuck::Pod,
+        T: bytemuck::Zeroable,
+    {
+        self.queue
+            .write_buffer(&buffer, offset, bytemuck::cast_slice(data));
+    }
+
+    /// Builds the bind group layout every `@group(1)` model slot is bound
+    /// against: a single read-only storage buffer of [`ModelMatrices`],
+    /// indexed in the vertex shader by `@builtin(instance_index)`. A
+    /// single-draw call just binds a one-element buffer (see
+    /// [`Self::create_mesh`]); [`RenderBuffer`](crate::RenderBuffer) binds
+    /// a larger one built on the fly when it batches consecutive draws
+    /// sharing the same mesh and material into one instanced call.
+    pub(crate) fn model_bind_group_layout(&self) -> wgpu::BindGroupLayout {
+        self.create_bind_group_layout(vec![BindGroupLayoutEntry::StorageBuffer {
+            read_only: true,
+        }])
+    }
+
     /// Creates a new [Mesh]
     ///
     /// Creates two [Buffer]s internally
@@ -142,10 +321,10 @@ impl Context {
                 Mat4::IDENTITY,
                 Mat4::IDENTITY,
             )],
-            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
         );
         let model_bind_group = self.create_bind_group(
-            &self.create_bind_group_layout(vec![BindGroupLayoutEntry::Buffer]),
+            &self.model_bind_group_layout(),
             vec![model_buffer.as_entire_binding()],
         );
 
@@ -155,57 +334,311 @@ impl Context {
             index_buffer,
             model_buffer,
             model_bind_group,
+            bounding_sphere: bounding_sphere(vertices),
+            instance_buffer: None,
+            instance_count: 0,
         }
     }
 
+    /// Like [`Self::create_mesh`], but additionally allocates a second,
+    /// `Instance`-stepped vertex buffer of [`InstanceData`], so `instances`
+    /// worth of transforms ride along with the draw instead of living in
+    /// [`Mesh::model_bind_group`]'s single-instance storage buffer. A mesh created
+    /// this way is drawn with one `draw_indexed` covering every instance,
+    /// instead of one draw call per instance.
+    ///
+    /// The pipeline it's drawn with must have been built from a
+    /// [`SimplifiedPipelineConfig`] with `instanced: true`, and the draw
+    /// call issued through [`RenderBuffer::add_instanced_draw_call`](crate::RenderBuffer::add_instanced_draw_call)
+    /// rather than [`RenderBuffer::add_draw_call`], which expects the
+    /// regular per-mesh model storage buffer instead.
+    pub fn create_instanced_mesh(
+        &self,
+        vertices: &[Vertex],
+        indices: &[u16],
+        instances: &[InstanceData],
+    ) -> Mesh {
+        let mut mesh = self.create_mesh(vertices, indices);
+        mesh.instance_buffer = Some(self.create_buffer(
+            instances,
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        ));
+        mesh.instance_count = instances.len() as u32;
+        mesh
+    }
+
+    /// Rewrites `mesh`'s instance buffer (see [`Self::create_instanced_mesh`])
+    /// with `instances`, reallocating it first if the count has grown past
+    /// the buffer's current capacity.
+    pub fn update_instances(&self, mesh: &mut Mesh, instances: &[InstanceData]) {
+        let needed =
+            (instances.len() * std::mem::size_of::<InstanceData>()) as wgpu::BufferAddress;
+
+        if let Some(buffer) = mesh.instance_buffer.as_ref().filter(|b| b.size() >= needed) {
+            self.update_buffer(buffer, instances);
+        } else {
+            mesh.instance_buffer = Some(self.create_buffer(
+                instances,
+                wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            ));
+        }
+
+        mesh.instance_count = instances.len() as u32;
+    }
+
+    /// Creates a [`ComputeShader`] from WGSL `code`, analogous to
+    /// [`Self::create_shader`] but for a compute pipeline: no vertex
+    /// layout, fragment targets, or MSAA/wireframe config to pick, just
+    /// bind group layouts and an entry point named `cs_main`.
+    pub fn create_compute_shader(
+        &self,
+        code: &'static str,
+        defines: &[&'static str],
+        bind_group_layouts: Vec<&wgpu::BindGroupLayout>,
+    ) -> ComputeShader {
+        let expanded = preprocess(
+            code,
+            &shared_includes(),
+            &defines.iter().copied().collect(),
+        )
+        .expect("shader preprocessing failed");
+
+        let module = self
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Compute Shader"),
+                source: wgpu::ShaderSource::Wgsl(expanded.into()),
+            });
+
+        let layout = self
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Compute Pipeline Layout"),
+                bind_group_layouts: bind_group_layouts.as_slice(),
+                push_constant_ranges: &[],
+            });
+
+        let pipeline = self
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("Compute Pipeline"),
+                layout: Some(&layout),
+                module: &module,
+                entry_point: "cs_main",
+            });
+
+        ComputeShader { module, pipeline }
+    }
+
+    /// Dispatches `shader` against `bind_groups` (bound in order starting
+    /// at `@group(0)`), rounding `instance_count` up to whole workgroups
+    /// of [`Self::COMPUTE_WORKGROUP_SIZE`].
+    pub(crate) fn dispatch_compute(
+        &self,
+        shader: &ComputeShader,
+        bind_groups: &[&wgpu::BindGroup],
+        instance_count: u32,
+    ) {
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Compute Command Encoder"),
+            });
+
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Compute Pass"),
+                timestamp_writes: None,
+            });
+
+            pass.set_pipeline(&shader.pipeline);
+            for (index, bind_group) in bind_groups.iter().enumerate() {
+                pass.set_bind_group(index as u32, bind_group, &[]);
+            }
+
+            let workgroups =
+                (instance_count + Self::COMPUTE_WORKGROUP_SIZE - 1) / Self::COMPUTE_WORKGROUP_SIZE;
+            pass.dispatch_workgroups(workgroups.max(1), 1, 1);
+        }
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+    }
+
     /// Creates a new [Shader]
+    ///
+    /// `code` is preprocessed with [`preprocess`] before compilation,
+    /// expanding any `#include`s against [`shared_includes`] and resolving
+    /// `#ifdef`/`#else`/`#endif` blocks against `defines`, so a single
+    /// source file can serve every pipeline permutation that needs it.
+    ///
+    /// `layout_descs` must describe the entries of each layout in
+    /// `bind_group_layouts`, in the same order, so the on-disk pipeline
+    /// cache can be keyed off their content rather than the compiled
+    /// [`wgpu::BindGroupLayout`] handles, which carry no introspectable
+    /// descriptor of their own.
     pub fn create_shader(
         &self,
         code: &'static str,
+        defines: &[&'static str],
         bind_group_layouts: Vec<&wgpu::BindGroupLayout>,
+        layout_descs: &[&[BindGroupLayoutEntry]],
         config: SimplifiedPipelineConfig,
     ) -> Rc<Shader> {
-        if let Some(shader) = self.shader_cache.borrow().get(&(code, config.clone())) {
+        let cache_key = (code, defines.to_vec(), config.clone());
+        if let Some(shader) = self.shader_cache.borrow().get(&cache_key) {
             return shader.clone();
         }
 
+        let expanded = preprocess(
+            code,
+            &shared_includes(),
+            &defines.iter().copied().collect(),
+        )
+        .expect("shader preprocessing failed");
+
+        let key = pipeline_cache::cache_key(&expanded, layout_descs, &config);
+        let cached = if self.bypass_cache {
+            None
+        } else {
+            self.pipeline_cache.as_ref().and_then(|cache| cache.get(key))
+        };
+
         let module = self
             .device
             .create_shader_module(wgpu::ShaderModuleDescriptor {
                 label: Some("Shader"),
-                source: wgpu::ShaderSource::Wgsl(code.into()),
+                source: wgpu::ShaderSource::Wgsl(expanded.into()),
             });
 
-        let pipeline = self.create_pipeline(&module, bind_group_layouts, config.clone());
+        let pipeline =
+            self.create_pipeline(&module, bind_group_layouts, config.clone(), key, cached);
 
         let shader = Rc::new(Shader { module, pipeline });
         self.shader_cache
             .borrow_mut()
-            .insert((code, config), shader.clone());
+            .insert(cache_key, shader.clone());
         shader
     }
 
-    pub fn create_render_texture(&self, size: UVec2) -> RenderTexture {
-        RenderTexture {
-            texture: Self::create_empty_texture(
-                &self.device,
-                size,
-                self.surface.config.format,
-                wgpu::TextureUsages::RENDER_ATTACHMENT
-                    | wgpu::TextureUsages::TEXTURE_BINDING
-                    | wgpu::TextureUsages::COPY_DST,
-            ),
-            depth: Self::create_empty_texture(
-                &self.device,
-                size,
-                wgpu::TextureFormat::Depth32Float,
-                wgpu::TextureUsages::RENDER_ATTACHMENT
-                    | wgpu::TextureUsages::TEXTURE_BINDING
-                    | wgpu::TextureUsages::COPY_DST,
-            ),
+    /// Creates a [`RenderTarget`] of `size`, whose final resolved output is
+    /// `format` (bindable elsewhere once rendered into), while its internal
+    /// opaque pass and OIT accum/reveal buffers keep the same formats
+    /// [`Self`] uses for the swapchain, since every [`Material`](crate::Material)
+    /// pipeline is built expecting those regardless of target.
+    ///
+    /// `msaa` requests multisampled opaque/OIT/depth attachments that get
+    /// resolved down before compositing; using `MSAA::On` here only helps
+    /// if the materials drawn into this target were themselves built with
+    /// a matching `msaa`, since a pipeline's sample count is fixed at
+    /// creation.
+    pub fn create_render_target(&self, size: UVec2, format: wgpu::TextureFormat, msaa: MSAA) -> RenderTarget {
+        let sample_count = match msaa {
+            MSAA::Off => 1,
+            MSAA::On(samples) => samples,
+        };
+
+        let texture = Self::create_empty_texture(
+            &self.device,
+            size,
+            format,
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST,
+        );
+        let depth = Self::create_empty_texture(
+            &self.device,
+            size,
+            wgpu::TextureFormat::Depth32Float,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        );
+        let oit_opaque = Self::create_empty_texture(
+            &self.device,
+            size,
+            self.surface.config.format,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        );
+        let oit_accum = Self::create_oit_accum_texture(&self.device, size);
+        let oit_reveal = Self::create_oit_reveal_texture(&self.device, size);
+
+        let msaa =
+            Self::create_msaa_attachments(&self.device, size, sample_count, self.surface.config.format);
+
+        RenderTarget {
+            texture,
+            format,
+            depth,
+            oit_opaque,
+            oit_accum,
+            oit_reveal,
+            msaa,
         }
     }
 
+    /// Creates a new block-compressed [`Texture`] from already-encoded
+    /// data (e.g. BC1/BC3/BC7), uploading it to the GPU without any CPU-side
+    /// decompression.
+    pub fn create_compressed_texture(
+        &self,
+        data: &[u8],
+        size: UVec2,
+        format: wgpu::TextureFormat,
+    ) -> Texture {
+        self.create_compressed_texture_mips(&[data], size, format)
+    }
+
+    /// Like [`Self::create_compressed_texture`], but uploads a full mip
+    /// chain in one go, with `mips[0]` being the full-size level and each
+    /// subsequent entry half the resolution of the last, as block-compressed
+    /// DDS files store them.
+    pub fn create_compressed_texture_mips(
+        &self,
+        mips: &[&[u8]],
+        size: UVec2,
+        format: wgpu::TextureFormat,
+    ) -> Texture {
+        let block_size = format
+            .block_size(None)
+            .expect("compressed texture format must have a known block size");
+
+        let texture = Self::create_empty_texture_mips(
+            &self.device,
+            size,
+            mips.len() as u32,
+            format,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        );
+
+        let mut mip_size = size;
+        for (level, data) in mips.iter().enumerate() {
+            let blocks_wide = (mip_size.x + 3) / 4;
+            let blocks_high = (mip_size.y + 3) / 4;
+
+            self.queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture.texture,
+                    mip_level: level as u32,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                data,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(block_size * blocks_wide),
+                    rows_per_image: std::num::NonZeroU32::new(blocks_high),
+                },
+                wgpu::Extent3d {
+                    width: mip_size.x,
+                    height: mip_size.y,
+                    depth_or_array_layers: 1,
+                },
+            );
+
+            mip_size = UVec2::new((mip_size.x / 2).max(1), (mip_size.y / 2).max(1));
+        }
+
+        texture
+    }
+
     pub fn create_texture(&self, rgba8: &[u8], size: UVec2) -> Texture {
         let texture = Self::create_empty_texture(
             &self.device,
@@ -240,6 +673,235 @@ impl Context {
         texture
     }
 
+    /// Like [`Self::create_texture`], but builds a full mip chain on the
+    /// GPU instead of leaving the texture single-level: level 0 is
+    /// uploaded as given, then each subsequent level is generated by
+    /// rendering a fullscreen blit of the previous level through a linear
+    /// sampler (the standard box-filter downsample), so sampling the
+    /// result when minified doesn't shimmer the way [`Self::create_texture`]'s
+    /// single level does.
+    ///
+    /// Unlike [`Self::create_texture_mips`], the caller only supplies the
+    /// base level; use that method instead if mips are already available
+    /// (e.g. decoded from a DDS file) and don't need generating.
+    pub fn create_texture_with_mipmaps(&self, rgba8: &[u8], size: UVec2) -> Texture {
+        let mip_level_count = 32 - size.x.max(size.y).max(1).leading_zeros();
+
+        let texture = Self::create_empty_texture_mips(
+            &self.device,
+            size,
+            mip_level_count,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        );
+
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            rgba8,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(4 * size.x),
+                rows_per_image: std::num::NonZeroU32::new(size.y),
+            },
+            wgpu::Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        self.generate_mipmaps(&texture.texture, mip_level_count);
+
+        texture
+    }
+
+    /// Downsamples `texture`'s level 0 into every subsequent level up to
+    /// `mip_level_count`, each produced by blitting the previous level
+    /// through [`MIPMAP_BLIT`] onto a screen-filling quad. `texture` must
+    /// have been allocated with `RENDER_ATTACHMENT` usage and
+    /// [`wgpu::TextureFormat::Rgba8UnormSrgb`].
+    fn generate_mipmaps(&self, texture: &wgpu::Texture, mip_level_count: u32) {
+        if mip_level_count <= 1 {
+            return;
+        }
+
+        let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bgl_desc = [
+            BindGroupLayoutEntry::Texture {
+                dim: TextureDimensions::D2,
+                filtering: true,
+            },
+            BindGroupLayoutEntry::Sampler { filtering: true },
+        ];
+        let bgl = self.create_bind_group_layout(bgl_desc.to_vec());
+        let layout_descs: [&[BindGroupLayoutEntry]; 1] = [&bgl_desc];
+
+        let shader = self.create_shader(
+            MIPMAP_BLIT,
+            &[],
+            vec![&bgl],
+            &layout_descs,
+            SimplifiedPipelineConfig {
+                wireframe: false,
+                msaa: MSAA::Off,
+                targets: vec![wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+                depth_settings: None,
+                instanced: false,
+            },
+        );
+
+        let plane = Mesh::make_screen_plane(self);
+
+        for level in 1..mip_level_count {
+            let src_view = texture.create_view(&wgpu::TextureViewDescriptor {
+                format: Some(wgpu::TextureFormat::Rgba8UnormSrgb),
+                base_mip_level: level - 1,
+                mip_level_count: Some(1),
+                ..Default::default()
+            });
+            let dst_view = texture.create_view(&wgpu::TextureViewDescriptor {
+                format: Some(wgpu::TextureFormat::Rgba8UnormSrgb),
+                base_mip_level: level,
+                mip_level_count: Some(1),
+                ..Default::default()
+            });
+
+            let bind_group = self.create_bind_group(
+                &bgl,
+                vec![
+                    wgpu::BindingResource::TextureView(&src_view),
+                    wgpu::BindingResource::Sampler(&sampler),
+                ],
+            );
+
+            let mut encoder = self
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Mipmap Blit Encoder"),
+                });
+
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Mipmap Blit Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &dst_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                            store: true,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+
+                render_pass.set_pipeline(&shader.pipeline);
+                render_pass.set_bind_group(0, &bind_group, &[]);
+                render_pass.set_vertex_buffer(0, plane.vertex_buffer.slice(..));
+                render_pass
+                    .set_index_buffer(plane.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                render_pass.draw_indexed(0..plane.num_triangles, 0, 0..1);
+            }
+
+            self.queue.submit(std::iter::once(encoder.finish()));
+        }
+    }
+
+    /// Like [`Self::create_texture`], but uploads a full mip chain, with
+    /// `mips[0]` being the full-size level and each subsequent entry half
+    /// the resolution of the last.
+    ///
+    /// `filtering` controls how the sampler blends between those levels;
+    /// [`Self::create_texture`] always builds a single-level texture, so
+    /// it never needs that choice.
+    pub fn create_texture_mips(&self, mips: &[&[u8]], size: UVec2, filtering: MipFiltering) -> Texture {
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Texture"),
+            size: wgpu::Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: mips.len() as u32,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        let mut mip_size = size;
+        for (level, data) in mips.iter().enumerate() {
+            self.queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: level as u32,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                data,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(4 * mip_size.x),
+                    rows_per_image: std::num::NonZeroU32::new(mip_size.y),
+                },
+                wgpu::Extent3d {
+                    width: mip_size.x,
+                    height: mip_size.y,
+                    depth_or_array_layers: 1,
+                },
+            );
+
+            mip_size = UVec2::new((mip_size.x / 2).max(1), (mip_size.y / 2).max(1));
+        }
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            format: Some(wgpu::TextureFormat::Rgba8UnormSrgb),
+            ..Default::default()
+        });
+
+        let (mipmap_filter, anisotropy_clamp) = match filtering {
+            MipFiltering::Nearest => (wgpu::FilterMode::Nearest, 1),
+            MipFiltering::Trilinear => (wgpu::FilterMode::Linear, 1),
+            MipFiltering::Anisotropic { samples } => (wgpu::FilterMode::Linear, samples),
+        };
+        let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter,
+            anisotropy_clamp,
+            ..Default::default()
+        });
+
+        Texture {
+            texture,
+            view,
+            sampler,
+            dimensions: TextureDimensions::D2,
+            size,
+        }
+    }
+
     pub fn create_bind_group_layout(
         &self,
         layout: Vec<BindGroupLayoutEntry>,
@@ -248,16 +910,18 @@ impl Context {
 
         for entry in layout {
             match entry {
-                BindGroupLayoutEntry::Buffer => entries.push(wgpu::BindGroupLayoutEntry {
-                    binding: entries.len() as u32,
-                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }),
+                BindGroupLayoutEntry::Buffer { dynamic, min_binding_size } => {
+                    entries.push(wgpu::BindGroupLayoutEntry {
+                        binding: entries.len() as u32,
+                        visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: dynamic,
+                            min_binding_size,
+                        },
+                        count: None,
+                    })
+                }
 
                 BindGroupLayoutEntry::Sampler { filtering } => {
                     entries.push(wgpu::BindGroupLayoutEntry {
@@ -286,6 +950,41 @@ impl Context {
                         count: None,
                     })
                 }
+
+                BindGroupLayoutEntry::DepthTexture { dim } => {
+                    entries.push(wgpu::BindGroupLayoutEntry {
+                        binding: entries.len() as u32,
+                        visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: (&dim).into(),
+                            sample_type: wgpu::TextureSampleType::Depth,
+                        },
+                        count: None,
+                    })
+                }
+
+                BindGroupLayoutEntry::ComparisonSampler => {
+                    entries.push(wgpu::BindGroupLayoutEntry {
+                        binding: entries.len() as u32,
+                        visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                        count: None,
+                    })
+                }
+
+                BindGroupLayoutEntry::StorageBuffer { read_only } => {
+                    entries.push(wgpu::BindGroupLayoutEntry {
+                        binding: entries.len() as u32,
+                        visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Storage { read_only },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    })
+                }
             }
         }
 
@@ -329,6 +1028,8 @@ impl Context {
         module: &wgpu::ShaderModule,
         bind_group_layouts: Vec<&wgpu::BindGroupLayout>,
         config: SimplifiedPipelineConfig,
+        cache_key: u128,
+        cached_data: Option<Vec<u8>>,
     ) -> wgpu::RenderPipeline {
         let layout = self
             .device
@@ -338,14 +1039,37 @@ impl Context {
                 push_constant_ranges: &[],
             });
 
-        self.device
+        let vertex_buffers = if config.instanced {
+            vec![Vertex::desc(), InstanceData::desc()]
+        } else {
+            vec![Vertex::desc()]
+        };
+
+        // SAFETY: `data`, when present, was produced by a prior call to
+        // `wgpu::PipelineCache::get_data` on this same backend/driver; a
+        // mismatch is simply ignored by the driver and falls back to a full
+        // compile.
+        let pipeline_cache = self
+            .device
+            .features()
+            .contains(wgpu::Features::PIPELINE_CACHE)
+            .then(|| unsafe {
+                self.device.create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                    label: Some("Render Pipeline Cache"),
+                    data: cached_data.as_deref(),
+                    fallback: true,
+                })
+            });
+
+        let pipeline = self
+            .device
             .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
                 label: Some("Render Pipeline"),
                 layout: Some(&layout),
                 vertex: wgpu::VertexState {
                     module,
                     entry_point: "vs_main",
-                    buffers: &[Vertex::desc()],
+                    buffers: vertex_buffers.as_slice(),
                 },
                 fragment: Some(wgpu::FragmentState {
                     module,
@@ -388,7 +1112,18 @@ impl Context {
                     alpha_to_coverage_enabled: false,
                 },
                 multiview: None,
-            })
+                cache: pipeline_cache.as_ref(),
+            });
+
+        // Persist the freshly-built (or now warmed-up) pipeline cache blob so
+        // the next launch can skip straight to `create_pipeline_cache`.
+        if let (Some(pipeline_cache), Some(store)) = (&pipeline_cache, &self.pipeline_cache) {
+            if let Some(data) = pipeline_cache.get_data() {
+                store.insert(cache_key, &data);
+            }
+        }
+
+        pipeline
     }
 
     fn create_empty_texture(
@@ -396,6 +1131,16 @@ impl Context {
         size: UVec2,
         format: wgpu::TextureFormat,
         usage: wgpu::TextureUsages,
+    ) -> Texture {
+        Self::create_empty_texture_mips(device, size, 1, format, usage)
+    }
+
+    fn create_empty_texture_mips(
+        device: &wgpu::Device,
+        size: UVec2,
+        mip_level_count: u32,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsages,
     ) -> Texture {
         let extend = wgpu::Extent3d {
             width: size.x,
@@ -405,7 +1150,7 @@ impl Context {
 
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             size: extend,
-            mip_level_count: 1,
+            mip_level_count,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format,
@@ -424,7 +1169,12 @@ impl Context {
             address_mode_w: wgpu::AddressMode::Repeat,
             mag_filter: wgpu::FilterMode::Linear,
             min_filter: wgpu::FilterMode::Linear,
-            mipmap_filter: wgpu::FilterMode::Nearest,
+            // Most textures built through this helper only ever have one
+            // mip level, where the choice is moot; `create_texture_with_mipmaps`
+            // and `create_compressed_texture_mips` are the callers that
+            // actually benefit from blending
+            // between levels instead of snapping to the nearest one.
+            mipmap_filter: wgpu::FilterMode::Linear,
             ..Default::default()
         });
 
@@ -437,6 +1187,69 @@ impl Context {
         }
     }
 
+    /// Creates a bare multisampled attachment, rendered into directly and
+    /// resolved (or, for depth, simply discarded) once the pass ends. It
+    /// has no `TEXTURE_BINDING` usage and isn't wrapped in a [`Texture`]
+    /// since it's never sampled, only ever used as a render-pass view.
+    fn create_multisampled_view(
+        device: &wgpu::Device,
+        size: UVec2,
+        sample_count: u32,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::TextureView {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Multisampled attachment"),
+            size: wgpu::Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    /// Builds the multisampled depth/opaque/accum/reveal attachments a
+    /// [`RenderTarget`] or [`Context`]'s own swapchain path renders
+    /// directly into, resolved down to the matching single-sampled
+    /// textures once each pass ends. `None` for `sample_count <= 1`, where
+    /// the single-sampled textures are rendered into directly instead.
+    fn create_msaa_attachments(
+        device: &wgpu::Device,
+        size: UVec2,
+        sample_count: u32,
+        opaque_format: wgpu::TextureFormat,
+    ) -> Option<MsaaAttachments> {
+        (sample_count > 1).then(|| MsaaAttachments {
+            depth: Self::create_multisampled_view(
+                device,
+                size,
+                sample_count,
+                wgpu::TextureFormat::Depth32Float,
+            ),
+            oit_opaque: Self::create_multisampled_view(device, size, sample_count, opaque_format),
+            oit_accum: Self::create_multisampled_view(
+                device,
+                size,
+                sample_count,
+                wgpu::TextureFormat::Rgba16Float,
+            ),
+            oit_reveal: Self::create_multisampled_view(
+                device,
+                size,
+                sample_count,
+                wgpu::TextureFormat::R8Unorm,
+            ),
+            sample_count,
+        })
+    }
+
     fn create_surface_depth_texture(
         device: &wgpu::Device,
         config: &wgpu::SurfaceConfiguration,
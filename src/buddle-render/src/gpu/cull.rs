@@ -0,0 +1,180 @@
+//! GPU-driven frustum culling
+
+use bytemuck::Zeroable;
+
+use buddle_math::Mat4;
+
+use crate::camera::ModelMatrices;
+use crate::gpu::context::Context;
+use crate::gpu::ComputeShader;
+use crate::BindGroupLayoutEntry;
+
+pub const FRUSTUM_CULL: &str = include_str!("../shaders/frustum_cull.wgsl");
+
+/// One instance's worth of input to the frustum-cull compute shader: the
+/// mesh's local-space bounding sphere (shared by every instance of that
+/// mesh), paired with the instance's own already-computed [`ModelMatrices`],
+/// from which the shader reads `model_matrix` to transform the sphere into
+/// world space before testing it against the frustum planes.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct CullInstance {
+    pub(crate) local_center: [f32; 3],
+    pub(crate) local_radius: f32,
+    pub(crate) matrices: ModelMatrices,
+}
+
+/// The six frustum planes extracted from a combined view-projection
+/// matrix, in `ax + by + cz + d` form, ready for a `dot(plane, vec4(p, 1.0))`
+/// test against a world-space point.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct FrustumPlanes {
+    planes: [[f32; 4]; 6],
+}
+
+impl FrustumPlanes {
+    /// Extracts the planes from `view_proj` using the standard Gribb/
+    /// Hartmann row-combination method: each plane is the sum or
+    /// difference of `view_proj`'s last row with one of its other rows.
+    fn from_view_proj(view_proj: Mat4) -> Self {
+        let rows = [
+            view_proj.row(0),
+            view_proj.row(1),
+            view_proj.row(2),
+            view_proj.row(3),
+        ];
+
+        FrustumPlanes {
+            planes: [
+                (rows[3] + rows[0]).to_array(), // left
+                (rows[3] - rows[0]).to_array(), // right
+                (rows[3] + rows[1]).to_array(), // bottom
+                (rows[3] - rows[1]).to_array(), // top
+                (rows[3] + rows[2]).to_array(), // near
+                (rows[3] - rows[2]).to_array(), // far
+            ],
+        }
+    }
+}
+
+/// Matches the layout `wgpu::RenderPass::draw_indexed_indirect` expects
+/// to find in its buffer argument.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct IndirectArgs {
+    index_count: u32,
+    instance_count: u32,
+    first_index: u32,
+    base_vertex: i32,
+    first_instance: u32,
+}
+
+/// The GPU-compacted result of culling one batch of same-mesh instances:
+/// an indirect-draw buffer whose `instance_count` only covers the
+/// survivors, and the model bind group holding their compacted matrices,
+/// packed so the surviving instances are contiguous starting at index 0.
+pub(crate) struct CulledBatch {
+    pub(crate) indirect_buffer: wgpu::Buffer,
+    pub(crate) model_bind_group: wgpu::BindGroup,
+}
+
+/// Tests a batch of draw-call instances against the camera frustum on the
+/// GPU and compacts the survivors into an indirect draw, instead of
+/// walking every bounding sphere on the CPU every frame. The compute
+/// pipeline is built once and reused; only the per-batch buffers change.
+pub(crate) struct FrustumCuller {
+    shader: ComputeShader,
+    planes_layout: wgpu::BindGroupLayout,
+    instances_layout: wgpu::BindGroupLayout,
+}
+
+impl FrustumCuller {
+    pub(crate) fn new(ctx: &Context) -> Self {
+        let planes_layout = ctx.create_bind_group_layout(vec![BindGroupLayoutEntry::Buffer { dynamic: false, min_binding_size: None }]);
+        let instances_layout = ctx.create_bind_group_layout(vec![
+            BindGroupLayoutEntry::StorageBuffer { read_only: true },
+            BindGroupLayoutEntry::StorageBuffer { read_only: false },
+            BindGroupLayoutEntry::StorageBuffer { read_only: false },
+        ]);
+
+        let shader = ctx.create_compute_shader(
+            FRUSTUM_CULL,
+            &[],
+            vec![&planes_layout, &instances_layout],
+        );
+
+        FrustumCuller {
+            shader,
+            planes_layout,
+            instances_layout,
+        }
+    }
+
+    /// Culls `instances` (all sharing the same mesh, hence `index_count`)
+    /// against `view_proj`, returning a [`CulledBatch`] ready to be bound
+    /// at `@group(1)` and replayed with a single `draw_indexed_indirect`.
+    pub(crate) fn cull(
+        &self,
+        ctx: &Context,
+        instances: &[CullInstance],
+        index_count: u32,
+        view_proj: Mat4,
+    ) -> CulledBatch {
+        let planes = FrustumPlanes::from_view_proj(view_proj);
+        let planes_buffer = ctx.create_buffer(
+            &[planes],
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        );
+        let planes_bind_group =
+            ctx.create_bind_group(&self.planes_layout, vec![planes_buffer.as_entire_binding()]);
+
+        let input_buffer = ctx.create_buffer(instances, wgpu::BufferUsages::STORAGE);
+
+        let indirect_args = IndirectArgs {
+            index_count,
+            instance_count: 0,
+            first_index: 0,
+            base_vertex: 0,
+            first_instance: 0,
+        };
+        let indirect_buffer = ctx.create_buffer(
+            &[indirect_args],
+            wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::INDIRECT
+                | wgpu::BufferUsages::COPY_DST,
+        );
+
+        // Zeroed placeholder; every surviving instance's slot is filled in
+        // by the compute shader, and `indirect_buffer`'s `instance_count`
+        // caps the draw to exactly those slots.
+        let zeroed_matrices = vec![ModelMatrices::zeroed(); instances.len()];
+        let output_buffer =
+            ctx.create_buffer(&zeroed_matrices, wgpu::BufferUsages::STORAGE);
+
+        let instances_bind_group = ctx.create_bind_group(
+            &self.instances_layout,
+            vec![
+                input_buffer.as_entire_binding(),
+                indirect_buffer.as_entire_binding(),
+                output_buffer.as_entire_binding(),
+            ],
+        );
+
+        ctx.dispatch_compute(
+            &self.shader,
+            &[&planes_bind_group, &instances_bind_group],
+            instances.len() as u32,
+        );
+
+        let model_bind_group = ctx.create_bind_group(
+            &ctx.model_bind_group_layout(),
+            vec![output_buffer.as_entire_binding()],
+        );
+
+        CulledBatch {
+            indirect_buffer,
+            model_bind_group,
+        }
+    }
+}
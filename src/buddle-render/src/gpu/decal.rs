@@ -0,0 +1,237 @@
+//! Perspective-correct decals projected onto an arbitrary quad
+
+use std::rc::Rc;
+
+use buddle_math::Vec3;
+
+use crate::gpu::{Shader, DECAL};
+use crate::{
+    BindGroupLayoutEntry, Context, SimplifiedPipelineConfig, Texture, TextureDimensions, MSAA,
+};
+
+/// How a [`Decal`] blends its sampled color into the scene.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum DecalBlendMode {
+    /// Standard alpha-over blending, for marks that should read as part of
+    /// the surface underneath (damage, scorch marks, selection rings).
+    Normal,
+    /// Additive blending, for effects meant to brighten the surface
+    /// instead of replacing it (glows, energy trails).
+    Additive,
+}
+
+impl DecalBlendMode {
+    fn blend_state(self) -> wgpu::BlendState {
+        match self {
+            DecalBlendMode::Normal => wgpu::BlendState::ALPHA_BLENDING,
+            DecalBlendMode::Additive => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            },
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct DecalVertex {
+    position: [f32; 3],
+    /// `(u * q, v * q, q)`; the fragment shader divides the first two
+    /// components by the third to recover `(u, v)`. Plain per-corner
+    /// `(u, v)` interpolated across the quad's two triangles would kink
+    /// along their shared diagonal unless the quad happens to be a
+    /// parallelogram, since each triangle interpolates affinely on its
+    /// own; weighting by `q` (see [`quad_corner_weights`]) makes the two
+    /// triangles agree everywhere, including off-diagonal corners warped
+    /// out of plane.
+    tex_coords_q: [f32; 3],
+}
+
+impl DecalVertex {
+    const ATTRIBS: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
+
+    fn new(position: Vec3, u: f32, v: f32, q: f32) -> Self {
+        Self {
+            position: position.into(),
+            tex_coords_q: [u * q, v * q, q],
+        }
+    }
+
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct DecalTint {
+    color: [f32; 4],
+}
+
+/// A warped quad, projected onto existing geometry without needing the
+/// geometry itself to carry decal UVs, e.g. damage marks, footprints or
+/// selection highlights stamped onto arbitrary scenery.
+///
+/// Built from four corners handed to [`Context::create_decal`] in winding
+/// order (matching a unit square's `(0,0)`, `(1,0)`, `(1,1)`, `(0,1)`
+/// corners); the corners need not form a perfect rectangle or even stay
+/// planar; see [`quad_corner_weights`] for how that's still mapped onto the
+/// texture without distortion.
+pub struct Decal {
+    pub(crate) vertex_buffer: wgpu::Buffer,
+    pub(crate) index_buffer: wgpu::Buffer,
+    tint_buffer: wgpu::Buffer,
+    pub(crate) bind_group: wgpu::BindGroup,
+    pub(crate) pipeline: Rc<Shader>,
+}
+
+/// Computes the homogeneous weight `q` Heckbert's projective quad-warp
+/// assigns to each of a quad's four corners, so that linearly interpolating
+/// `(u * q, v * q, q)` per-triangle and dividing by `q` in the fragment
+/// shader reproduces the same bilinear warp a single (non-triangulated)
+/// quad would have.
+///
+/// `corners` are expected in the same winding order as the unit square's
+/// `(0,0)`, `(1,0)`, `(1,1)`, `(0,1)` corners. Works in the 2D coordinate
+/// system `corners[1] - corners[0]` and `corners[3] - corners[0]` define as
+/// its basis, projecting `corners[2]` onto it via least squares, so a
+/// mildly non-planar quad (the four corners don't quite share a plane)
+/// still gets a reasonable warp instead of requiring an exact fit.
+fn quad_corner_weights(corners: [Vec3; 4]) -> [f32; 4] {
+    let e1 = corners[1] - corners[0];
+    let e2 = corners[3] - corners[0];
+    let d = corners[2] - corners[0];
+
+    // Solve `d = p * e1 + q * e2` for (p, q) via the normal equations,
+    // since e1/e2 aren't assumed orthogonal.
+    let a = e1.dot(e1);
+    let b = e1.dot(e2);
+    let c = e2.dot(e2);
+    let e = e1.dot(d);
+    let f = e2.dot(d);
+    let det = a * c - b * b;
+
+    let (p, q) = if det.abs() < f32::EPSILON {
+        (1.0, 1.0)
+    } else {
+        ((e * c - f * b) / det, (a * f - e * b) / det)
+    };
+
+    // Heckbert's quad-to-quad projective mapping, specialized to a unit
+    // square source and corners (0,0), (1,0), (p,q), (0,1) in the basis
+    // above.
+    let denom = 1.0 - p - q;
+    let (g, h) = if denom.abs() < f32::EPSILON {
+        (0.0, 0.0)
+    } else {
+        ((q - 1.0) / denom, (p - 1.0) / denom)
+    };
+
+    [1.0, g + 1.0, g + h + 1.0, h + 1.0]
+}
+
+impl Context {
+    /// Builds a [`Decal`] stamping `texture` across the quad `corners`
+    /// describe, tinted by `tint` and blended per `blend_mode`.
+    pub fn create_decal(
+        &self,
+        corners: [Vec3; 4],
+        texture: &Texture,
+        tint: [f32; 4],
+        blend_mode: DecalBlendMode,
+    ) -> Decal {
+        let weights = quad_corner_weights(corners);
+        let uvs: [(f32, f32); 4] = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+
+        let vertices: Vec<DecalVertex> = corners
+            .into_iter()
+            .zip(uvs)
+            .zip(weights)
+            .map(|((corner, (u, v)), q)| DecalVertex::new(corner, u, v, q))
+            .collect();
+        let indices: Vec<u16> = vec![0, 1, 2, 0, 2, 3];
+
+        let vertex_buffer = self.create_buffer(&vertices, wgpu::BufferUsages::VERTEX);
+        let index_buffer = self.create_buffer(&indices, wgpu::BufferUsages::INDEX);
+
+        let tint_buffer = self.create_buffer(
+            &[DecalTint { color: tint }],
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        );
+
+        let camera_desc = [BindGroupLayoutEntry::Buffer {
+            dynamic: false,
+            min_binding_size: None,
+        }];
+        let material_desc = [
+            BindGroupLayoutEntry::Buffer {
+                dynamic: false,
+                min_binding_size: None,
+            },
+            BindGroupLayoutEntry::Texture {
+                dim: TextureDimensions::D2,
+                filtering: true,
+            },
+            BindGroupLayoutEntry::Sampler { filtering: true },
+        ];
+
+        let camera_gl = self.create_bind_group_layout(camera_desc.to_vec());
+        let material_gl = self.create_bind_group_layout(material_desc.to_vec());
+        let layout_descs: [&[BindGroupLayoutEntry]; 2] = [&camera_desc, &material_desc];
+
+        let pipeline = self.create_shader(
+            DECAL,
+            &[],
+            vec![&camera_gl, &material_gl],
+            &layout_descs,
+            SimplifiedPipelineConfig {
+                wireframe: false,
+                msaa: MSAA::Off,
+                targets: vec![wgpu::ColorTargetState {
+                    format: self.surface.config.format,
+                    blend: Some(blend_mode.blend_state()),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+                depth_settings: None,
+                instanced: false,
+            },
+        );
+
+        let bind_group = self.create_bind_group(
+            &material_gl,
+            vec![
+                tint_buffer.as_entire_binding(),
+                wgpu::BindingResource::TextureView(&texture.view),
+                wgpu::BindingResource::Sampler(&texture.sampler),
+            ],
+        );
+
+        Decal {
+            vertex_buffer,
+            index_buffer,
+            tint_buffer,
+            bind_group,
+            pipeline,
+        }
+    }
+}
+
+impl Decal {
+    /// Re-uploads this decal's tint color, e.g. to fade it out over time.
+    pub fn update_tint(&self, ctx: &Context, tint: [f32; 4]) {
+        ctx.update_buffer(&self.tint_buffer, &[DecalTint { color: tint }]);
+    }
+}
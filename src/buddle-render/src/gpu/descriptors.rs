@@ -1,6 +1,6 @@
 //! Describing what we want and have to the GPU
 
-use buddle_math::{Vec2, Vec3};
+use buddle_math::{Mat4, Vec2, Vec3};
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
@@ -9,11 +9,18 @@ pub struct Vertex {
     pub color: [f32; 3],
     pub normal: [f32; 3],
     pub tex_coords: [f32; 2],
+    /// The surface tangent, used by normal-mapped materials to build a
+    /// TBN basis. Left zeroed by [`Vertex::new`]; callers that need real
+    /// tangents compute and assign them afterwards, same as smooth
+    /// normals derived from triangle data, e.g. via [`compute_tangents`].
+    pub tangent: [f32; 3],
+    /// The surface bitangent, completing the TBN basis alongside `normal`
+    /// and `tangent`. Left zeroed by [`Vertex::new`]; see [`compute_tangents`].
+    pub bitangent: [f32; 3],
 }
 
 impl Vertex {
-    const ATTRIBS: [wgpu::VertexAttribute; 4] =
-        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Float32x3, 3 => Float32x2];
+    const ATTRIBS: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Float32x3, 3 => Float32x2, 4 => Float32x3, 5 => Float32x3];
 
     pub fn new(position: Vec3, color: Vec3, normal: Vec3, tex_coords: Vec2) -> Self {
         Self {
@@ -21,6 +28,8 @@ impl Vertex {
             color: color.into(),
             normal: normal.into(),
             tex_coords: tex_coords.into(),
+            tangent: [0.0, 0.0, 0.0],
+            bitangent: [0.0, 0.0, 0.0],
         }
     }
 
@@ -33,13 +42,121 @@ impl Vertex {
     }
 }
 
-#[derive(Copy, Clone, Hash, Eq, PartialEq)]
+/// Computes per-vertex tangents and bitangents for `vertices` from their
+/// positions and UVs, overwriting whatever `tangent`/`bitangent` they
+/// already carried (zeroed by [`Vertex::new`]).
+///
+/// For each triangle, the edge vectors and their UV deltas satisfy
+/// `[edge1; edge2] = [ΔUV1; ΔUV2] * [tangent; bitangent]`, so inverting
+/// the 2x2 `ΔUV` matrix solves for the triangle's tangent and bitangent;
+/// those are accumulated (summed, not averaged) into every vertex the
+/// triangle touches, then each vertex's accumulated tangent is
+/// Gram-Schmidt-orthonormalized against its normal
+/// (`T = normalize(T - N * dot(N, T))`) and re-signed by
+/// `dot(cross(N, T), B)` so mirrored UVs still produce a bitangent
+/// pointing the right way.
+///
+/// `vertices` and `indices` must describe the same triangle list
+/// [`Context::create_mesh`](crate::Context::create_mesh) would otherwise
+/// receive; this is meant to run once, right before handing both to it.
+pub fn compute_tangents(vertices: &mut [Vertex], indices: &[u16]) {
+    let mut tangents = vec![Vec3::ZERO; vertices.len()];
+    let mut bitangents = vec![Vec3::ZERO; vertices.len()];
+
+    for triangle in indices.chunks_exact(3) {
+        let [i0, i1, i2] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+
+        let p0 = Vec3::from(vertices[i0].position);
+        let p1 = Vec3::from(vertices[i1].position);
+        let p2 = Vec3::from(vertices[i2].position);
+
+        let uv0 = Vec2::from(vertices[i0].tex_coords);
+        let uv1 = Vec2::from(vertices[i1].tex_coords);
+        let uv2 = Vec2::from(vertices[i2].tex_coords);
+
+        let edge1 = p1 - p0;
+        let edge2 = p2 - p0;
+        let delta_uv1 = uv1 - uv0;
+        let delta_uv2 = uv2 - uv0;
+
+        let det = delta_uv1.x * delta_uv2.y - delta_uv2.x * delta_uv1.y;
+        if det.abs() < f32::EPSILON {
+            continue;
+        }
+        let inv_det = 1.0 / det;
+
+        let tangent = (edge1 * delta_uv2.y - edge2 * delta_uv1.y) * inv_det;
+        let bitangent = (edge2 * delta_uv1.x - edge1 * delta_uv2.x) * inv_det;
+
+        for i in [i0, i1, i2] {
+            tangents[i] += tangent;
+            bitangents[i] += bitangent;
+        }
+    }
+
+    for (vertex, (tangent, bitangent)) in vertices.iter_mut().zip(tangents.into_iter().zip(bitangents)) {
+        let normal = Vec3::from(vertex.normal);
+
+        let orthogonal_tangent = (tangent - normal * normal.dot(tangent)).normalize_or_zero();
+        let handedness = if normal.cross(orthogonal_tangent).dot(bitangent) < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+
+        vertex.tangent = orthogonal_tangent.into();
+        vertex.bitangent = (normal.cross(orthogonal_tangent) * handedness).into();
+    }
+}
+
+/// Per-instance model/normal matrices for a [`Mesh`](crate::Mesh) created
+/// via [`Context::create_instanced_mesh`](crate::Context::create_instanced_mesh),
+/// consumed by a second, `Instance`-stepped vertex buffer bound alongside
+/// [`Vertex`]'s own `Vertex`-stepped one. This is a cheaper alternative to
+/// [`Mesh`](crate::Mesh)'s default single-instance `model_bind_group` when
+/// the same mesh needs to be drawn thousands of times per frame with only
+/// its transform changing, since it folds every instance into one
+/// `draw_indexed` call instead of one per instance.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct InstanceData {
+    pub model_matrix: [[f32; 4]; 4],
+    pub normal_matrix: [[f32; 4]; 4],
+}
+
+impl InstanceData {
+    pub fn new(model_matrix: Mat4) -> Self {
+        Self {
+            model_matrix: model_matrix.to_cols_array_2d(),
+            normal_matrix: model_matrix.inverse().transpose().to_cols_array_2d(),
+        }
+    }
+
+    // `Vertex` occupies locations 0-5; continuing from 6 keeps the two
+    // buffers' attributes from colliding when bound together. A mat4 has
+    // to be split into four `Float32x4` attributes since wgpu has no
+    // single attribute format wide enough to carry it.
+    const ATTRIBS: [wgpu::VertexAttribute; 8] = wgpu::vertex_attr_array![
+        6 => Float32x4, 7 => Float32x4, 8 => Float32x4, 9 => Float32x4,
+        10 => Float32x4, 11 => Float32x4, 12 => Float32x4, 13 => Float32x4,
+    ];
+
+    pub(crate) fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub enum MSAA {
     Off,
     On(u32),
 }
 
-#[derive(Clone, Hash, Eq, PartialEq)]
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct DepthSettings {
     pub compare: wgpu::CompareFunction,
     pub write: bool
@@ -51,10 +168,87 @@ pub struct SimplifiedPipelineConfig {
     pub msaa: MSAA,
     pub targets: Vec<wgpu::ColorTargetState>,
     pub depth_settings: Option<DepthSettings>,
+    /// Adds [`InstanceData::desc`]'s `Instance`-stepped buffer as a second
+    /// vertex buffer alongside [`Vertex::desc`]'s, so the pipeline expects
+    /// a `set_vertex_buffer(1, ..)` call before every draw. Only meshes
+    /// created with [`Context::create_instanced_mesh`](crate::Context::create_instanced_mesh)
+    /// carry that second buffer, so pipelines built this way must only be
+    /// used to draw those.
+    pub instanced: bool,
+}
+
+/// How a texture's sampler filters between mip levels and handles
+/// grazing viewing angles.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum MipFiltering {
+    /// Snaps to the nearest mip level instead of blending between them;
+    /// fine for single-level textures, but aliases badly once a texture
+    /// actually has more than one.
+    Nearest,
+    /// Blends linearly between the two nearest mip levels as well as
+    /// within each one.
+    Trilinear,
+    /// Trilinear filtering plus anisotropic sampling, which keeps
+    /// surfaces viewed at a grazing angle sharp instead of over-blurring
+    /// them into a coarser mip level. `samples` is the anisotropy clamp
+    /// passed to the driver.
+    Anisotropic { samples: u16 },
+}
+
+/// How the swapchain paces presentation against the display's refresh rate.
+/// Mirrors [`wgpu::PresentMode`]'s variants that are meaningful to pick
+/// between without inspecting the surface's capabilities first; unsupported
+/// choices fall back to [`PresentMode::Fifo`], which every surface supports.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum PresentMode {
+    /// Vsync on, preferring a relaxed mode that can present a late frame
+    /// immediately instead of stalling for the next vblank, if the
+    /// platform has one.
+    AutoVsync,
+    /// Vsync off, preferring mailbox (replace the queued frame instead of
+    /// tearing) over immediate, if the platform has one.
+    AutoNoVsync,
+    /// Classic double/triple-buffered vsync; always supported, and what
+    /// [`PresentMode::AutoVsync`]/[`PresentMode::AutoNoVsync`] both fall
+    /// back to when the platform has nothing better.
+    Fifo,
+    /// Submits frames as fast as the GPU can produce them, replacing
+    /// whatever's queued instead of presenting it, so latency stays low
+    /// without tearing. Not universally supported.
+    Mailbox,
+    /// Submits frames as fast as the GPU can produce them with no queuing
+    /// at all, tearing if a new frame lands mid-scanout. Lowest latency,
+    /// useful for uncapped benchmarking. Not universally supported.
+    Immediate,
+}
+
+impl PresentMode {
+    /// Maps to the equivalent [`wgpu::PresentMode`], falling back to
+    /// [`wgpu::PresentMode::Fifo`] if `available` (a surface's
+    /// `SurfaceCapabilities::present_modes`) doesn't list the one this
+    /// variant wants. The `Auto*` variants are passed through as-is, since
+    /// wgpu resolves them (down to `Fifo` at worst) against the surface
+    /// itself and never lists them among its `present_modes`.
+    pub(crate) fn into_wgpu(self, available: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+        let wanted = match self {
+            PresentMode::AutoVsync => wgpu::PresentMode::AutoVsync,
+            PresentMode::AutoNoVsync => wgpu::PresentMode::AutoNoVsync,
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+        };
+
+        let auto = matches!(self, PresentMode::AutoVsync | PresentMode::AutoNoVsync);
+        if auto || available.contains(&wanted) {
+            wanted
+        } else {
+            wgpu::PresentMode::Fifo
+        }
+    }
 }
 
 /// See docs for [`wgpu::TextureViewDimension`]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub enum TextureDimensions {
     D1,
     D2,
@@ -64,10 +258,29 @@ pub enum TextureDimensions {
     D3,
 }
 
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub enum BindGroupLayoutEntry {
-    Buffer,
+    /// A uniform buffer. `dynamic` declares `has_dynamic_offset`, letting
+    /// one binding be re-pointed per draw via `set_bind_group(.., &[offset])`
+    /// instead of needing a separate bind group per object, e.g. a
+    /// [`UniformStorage`] slot. `min_binding_size` should be set alongside
+    /// `dynamic: true`, so the validation layer knows the size of a single
+    /// slot rather than assuming the whole buffer is bound at once.
+    Buffer{dynamic: bool, min_binding_size: Option<std::num::NonZeroU64>},
     Sampler{filtering: bool},
     Texture{dim: TextureDimensions, filtering: bool},
+    /// A depth texture sampled with `textureSampleCompare`, e.g. a shadow
+    /// map. Unlike [`BindGroupLayoutEntry::Texture`], this always declares
+    /// an unfilterable depth sample type, since depth comparison sampling
+    /// can't be combined with regular filtering.
+    DepthTexture{dim: TextureDimensions},
+    /// A `sampler_comparison`, the only kind of sampler a
+    /// [`BindGroupLayoutEntry::DepthTexture`] can be sampled with.
+    ComparisonSampler,
+    /// A read-write or read-only storage buffer, e.g. an instance buffer
+    /// indexed by `@builtin(instance_index)` or a compute shader's input/
+    /// output buffer.
+    StorageBuffer{read_only: bool},
 }
 
 impl Into<wgpu::TextureViewDimension> for &TextureDimensions {
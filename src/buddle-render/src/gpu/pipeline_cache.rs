@@ -0,0 +1,102 @@
+//! On-disk cache for compiled shader modules and render pipelines
+//!
+//! Building a [`wgpu::ShaderModule`] and its [`wgpu::RenderPipeline`] involves
+//! naga validation and driver-side compilation, both of which are repeated on
+//! every launch for the same handful of [`FLAT_TEXTURE`]/[`OIT_FLAT_TEXTURE`]
+//! shaders. [`PipelineCache`] stores the raw pipeline cache blob produced by
+//! the backend (where available) on disk, keyed by a content hash of the
+//! shader source and pipeline configuration, so repeat launches can skip
+//! straight to `wgpu::Device::create_pipeline_cache`.
+//!
+//! [`FLAT_TEXTURE`]: crate::FLAT_TEXTURE
+//! [`OIT_FLAT_TEXTURE`]: crate::OIT_FLAT_TEXTURE
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::gpu::{BindGroupLayoutEntry, SimplifiedPipelineConfig};
+
+/// Computes the cache key for a shader + pipeline configuration.
+///
+/// Hashes the shader source, the [`SimplifiedPipelineConfig`] and the
+/// descriptors of every bind group layout the pipeline is built against. A
+/// 128-bit BLAKE3 digest is used rather than [`buddle_utils::hash::djb2`] or
+/// [`buddle_utils::hash::string_id`], since both of those strip bits for
+/// compactness and are far too collision-prone to key a cache with.
+pub(crate) fn cache_key(
+    code: &str,
+    layout_descs: &[&[BindGroupLayoutEntry]],
+    config: &SimplifiedPipelineConfig,
+) -> u128 {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(code.as_bytes());
+
+    hasher.update(&(config.wireframe as u8).to_le_bytes());
+    hasher.update(&(config.instanced as u8).to_le_bytes());
+    match config.msaa {
+        crate::gpu::MSAA::Off => hasher.update(&[0]),
+        crate::gpu::MSAA::On(samples) => {
+            hasher.update(&[1]);
+            hasher.update(&samples.to_le_bytes())
+        }
+    };
+
+    for target in &config.targets {
+        hasher.update(&format!("{target:?}").into_bytes());
+    }
+    hasher.update(&format!("{:?}", config.depth_settings).into_bytes());
+
+    for layout in layout_descs {
+        hasher.update(&(layout.len() as u32).to_le_bytes());
+        for entry in *layout {
+            hasher.update(&format!("{entry:?}").into_bytes());
+        }
+    }
+
+    let digest = hasher.finalize();
+    u128::from_le_bytes(digest.as_bytes()[..16].try_into().unwrap())
+}
+
+/// A flat, file-backed key-value store for compiled pipeline cache blobs.
+///
+/// Each entry is stored as its own file named after the hex-encoded cache
+/// key, inside the platform user cache directory. There is intentionally no
+/// index file or locking: entries are small, immutable once written, and
+/// losing one simply results in a cache miss next launch.
+pub struct PipelineCache {
+    dir: PathBuf,
+}
+
+impl PipelineCache {
+    /// Opens the on-disk pipeline cache, creating its directory if
+    /// necessary.
+    ///
+    /// Returns `None` if the user cache directory can't be determined or
+    /// created, in which case callers should fall back to uncached
+    /// compilation.
+    pub fn open() -> Option<Self> {
+        let dir = directories::ProjectDirs::from("org", "buddle", "buddle")?
+            .cache_dir()
+            .join("pipelines");
+
+        fs::create_dir_all(&dir).ok()?;
+        Some(Self { dir })
+    }
+
+    fn path_for(&self, key: u128) -> PathBuf {
+        self.dir.join(format!("{key:032x}.bin"))
+    }
+
+    /// Returns the previously stored pipeline cache blob for `key`, if any.
+    pub fn get(&self, key: u128) -> Option<Vec<u8>> {
+        fs::read(self.path_for(key)).ok()
+    }
+
+    /// Persists `data` as the pipeline cache blob for `key`.
+    ///
+    /// Failures are silently ignored: the cache is a pure optimization, and
+    /// a failed write just means the pipeline will be recompiled next time.
+    pub fn insert(&self, key: u128, data: &[u8]) {
+        let _ = fs::write(self.path_for(key), data);
+    }
+}
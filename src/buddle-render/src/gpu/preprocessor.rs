@@ -0,0 +1,136 @@
+//! WGSL source preprocessing
+//!
+//! [`preprocess`] runs over a shader source string before it reaches
+//! [`wgpu::Device::create_shader_module`], expanding `#include "name"`
+//! directives against a table of shared snippets and resolving
+//! `#define`/`#ifdef`/`#else`/`#endif` blocks against a set of active
+//! defines. This lets one source file emit the right variant for a given
+//! [`SimplifiedPipelineConfig`](crate::SimplifiedPipelineConfig) (e.g.
+//! `#ifdef SHADOWS`) instead of the crate maintaining a hand-duplicated
+//! WGSL file per pipeline permutation.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, bail};
+
+/// Expands `source` against `includes` and `defines`, returning the final
+/// WGSL text to compile.
+///
+/// `includes` maps the name passed to `#include "name"` to its source text.
+/// `defines` seeds the set of active `#ifdef` flags; `#define NAME` in
+/// `source` (or anything it includes) extends that set for the rest of
+/// expansion.
+pub fn preprocess(
+    source: &str,
+    includes: &HashMap<&str, &str>,
+    defines: &HashSet<&str>,
+) -> anyhow::Result<String> {
+    let mut defines = defines.iter().map(|s| s.to_string()).collect();
+    let mut include_stack = Vec::new();
+    expand(source, includes, &mut defines, &mut include_stack)
+}
+
+struct IfFrame {
+    /// Whether the block containing this `#ifdef` was itself active.
+    parent_active: bool,
+    /// Whether the `#ifdef` condition held, i.e. whether the first branch
+    /// (before any `#else`) is the active one.
+    condition: bool,
+    /// Whether the current branch (accounting for a possible `#else`) is
+    /// active right now.
+    active: bool,
+}
+
+fn expand(
+    source: &str,
+    includes: &HashMap<&str, &str>,
+    defines: &mut HashSet<String>,
+    include_stack: &mut Vec<String>,
+) -> anyhow::Result<String> {
+    let mut out = String::with_capacity(source.len());
+    let mut if_stack: Vec<IfFrame> = Vec::new();
+
+    let is_active = |if_stack: &[IfFrame]| if_stack.last().map_or(true, |f| f.active);
+
+    for line in source.lines() {
+        let trimmed = line.trim();
+
+        if let Some(name) = trimmed.strip_prefix("#include") {
+            if is_active(&if_stack) {
+                let name = parse_quoted(name)
+                    .ok_or_else(|| anyhow!("malformed #include directive: `{line}`"))?;
+
+                if include_stack.iter().any(|included| included == name) {
+                    bail!(
+                        "include cycle detected: `{name}` is already being expanded (stack: {include_stack:?})"
+                    );
+                }
+
+                let included = includes
+                    .get(name)
+                    .ok_or_else(|| anyhow!("no shared include named `{name}`"))?;
+
+                include_stack.push(name.to_string());
+                let expanded = expand(included, includes, defines, include_stack)?;
+                include_stack.pop();
+
+                out.push_str(&expanded);
+                out.push('\n');
+            }
+            continue;
+        }
+
+        if let Some(name) = trimmed.strip_prefix("#define") {
+            if is_active(&if_stack) {
+                defines.insert(name.trim().to_string());
+            }
+            continue;
+        }
+
+        if let Some(name) = trimmed.strip_prefix("#ifdef") {
+            let parent_active = is_active(&if_stack);
+            let condition = defines.contains(name.trim());
+            if_stack.push(IfFrame {
+                parent_active,
+                condition,
+                active: parent_active && condition,
+            });
+            continue;
+        }
+
+        if trimmed.starts_with("#else") {
+            let frame = if_stack
+                .last_mut()
+                .ok_or_else(|| anyhow!("`#else` without a matching `#ifdef`"))?;
+            frame.active = frame.parent_active && !frame.condition;
+            continue;
+        }
+
+        if trimmed.starts_with("#endif") {
+            if if_stack.pop().is_none() {
+                bail!("`#endif` without a matching `#ifdef`");
+            }
+            continue;
+        }
+
+        if is_active(&if_stack) {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+
+    if !if_stack.is_empty() {
+        bail!(
+            "unterminated `#ifdef` block(s): {} still open at end of source",
+            if_stack.len()
+        );
+    }
+
+    Ok(out)
+}
+
+fn parse_quoted(rest: &str) -> Option<&str> {
+    let rest = rest.trim();
+    let rest = rest.strip_prefix('"')?;
+    rest.strip_suffix('"')
+}
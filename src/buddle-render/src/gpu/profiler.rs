@@ -0,0 +1,134 @@
+//! GPU timestamp-query profiling for `RenderBuffer`'s passes
+
+use std::cell::RefCell;
+
+/// How long each pass of a frame took on the GPU, in milliseconds. Fields
+/// are `None` if the adapter doesn't support
+/// [`wgpu::Features::TIMESTAMP_QUERY`], and until the first frame has been
+/// submitted. The readback is not asynchronous: `update_timings` blocks
+/// until the GPU has finished the frame and its queries are mapped, so the
+/// timings describe the most recently submitted frame.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FrameTimings {
+    pub opaque_ms: Option<f32>,
+    pub oit_ms: Option<f32>,
+    pub composite_ms: Option<f32>,
+}
+
+const OPAQUE_BEGIN: u32 = 0;
+const OPAQUE_END: u32 = 1;
+const OIT_BEGIN: u32 = 2;
+const OIT_END: u32 = 3;
+const COMPOSITE_BEGIN: u32 = 4;
+const COMPOSITE_END: u32 = 5;
+const QUERY_COUNT: u32 = 6;
+
+/// Drives timestamp queries across a frame's opaque/OIT/composite passes.
+/// Only constructed by [`Context::new_async`](crate::Context::new_async)
+/// when the adapter actually supports [`wgpu::Features::TIMESTAMP_QUERY`);
+/// everything else in the rendering path treats its absence as "profiling
+/// disabled" rather than an error.
+pub(crate) struct GpuProfiler {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    period: f32,
+    timings: RefCell<FrameTimings>,
+}
+
+impl GpuProfiler {
+    pub(crate) fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Frame Timing Query Set"),
+            ty: wgpu::QueryType::Timestamp,
+            count: QUERY_COUNT,
+        });
+
+        let buffer_size = QUERY_COUNT as u64 * std::mem::size_of::<u64>() as u64;
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Timing Resolve Buffer"),
+            size: buffer_size,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Timing Readback Buffer"),
+            size: buffer_size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        GpuProfiler {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            period: queue.get_timestamp_period(),
+            timings: RefCell::new(FrameTimings::default()),
+        }
+    }
+
+    pub(crate) fn opaque_writes(&self) -> wgpu::RenderPassTimestampWrites {
+        self.writes(OPAQUE_BEGIN, OPAQUE_END)
+    }
+
+    pub(crate) fn oit_writes(&self) -> wgpu::RenderPassTimestampWrites {
+        self.writes(OIT_BEGIN, OIT_END)
+    }
+
+    pub(crate) fn composite_writes(&self) -> wgpu::RenderPassTimestampWrites {
+        self.writes(COMPOSITE_BEGIN, COMPOSITE_END)
+    }
+
+    fn writes(&self, begin: u32, end: u32) -> wgpu::RenderPassTimestampWrites {
+        wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(begin),
+            end_of_pass_write_index: Some(end),
+        }
+    }
+
+    /// Resolves this frame's queries into the host-visible readback
+    /// buffer. Must be called from the composite pass's command encoder,
+    /// the last pass this frame that writes timestamps, since that's the
+    /// first point at which every timestamp has actually been written.
+    pub(crate) fn resolve(&self, encoder: &mut wgpu::CommandEncoder) {
+        encoder.resolve_query_set(&self.query_set, 0..QUERY_COUNT, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &self.readback_buffer,
+            0,
+            self.resolve_buffer.size(),
+        );
+    }
+
+    /// Maps the readback buffer and updates the cached [`FrameTimings`].
+    /// Called once per frame after the resolve above has been submitted.
+    pub(crate) fn update_timings(&self, device: &wgpu::Device) {
+        let slice = self.readback_buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::Wait);
+
+        {
+            let data = slice.get_mapped_range();
+            let stamps: &[u64] = bytemuck::cast_slice(&data);
+
+            let ms = |begin: u32, end: u32| {
+                stamps[end as usize].wrapping_sub(stamps[begin as usize]) as f32 * self.period
+                    / 1_000_000.0
+            };
+
+            *self.timings.borrow_mut() = FrameTimings {
+                opaque_ms: Some(ms(OPAQUE_BEGIN, OPAQUE_END)),
+                oit_ms: Some(ms(OIT_BEGIN, OIT_END)),
+                composite_ms: Some(ms(COMPOSITE_BEGIN, COMPOSITE_END)),
+            };
+        }
+
+        self.readback_buffer.unmap();
+    }
+
+    pub(crate) fn timings(&self) -> FrameTimings {
+        *self.timings.borrow()
+    }
+}
@@ -1,12 +1,17 @@
 //! Batches and dispatches draw calls to the GPU
 
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
 use wgpu::{BlendComponent, BlendFactor, BlendOperation};
 use buddle_math::{Mat4};
 
 use crate::camera::ModelMatrices;
-use crate::gpu::{context::Context, Mesh, OIT_COMPOSITE, SCREEN};
+use crate::gpu::context::StaticBundleCache;
+use crate::gpu::cull::{CullInstance, CulledBatch};
+use crate::gpu::{context::Context, Decal, Mesh, OIT_COMPOSITE, SCREEN};
 use crate::{
-    BindGroupLayoutEntry, Material, RenderTexture, SimplifiedPipelineConfig, Texture,
+    BindGroupLayoutEntry, Material, RenderTarget, SimplifiedPipelineConfig, Texture,
     TextureDimensions, MSAA,
 };
 
@@ -16,8 +21,35 @@ pub(crate) struct DrawCall<'a> {
     model_matrix: Mat4,
 }
 
+/// A draw call for a mesh created via [`crate::Context::create_instanced_mesh`],
+/// whose per-instance transforms already live in its own `Instance`-stepped
+/// vertex buffer instead of a `model_matrix` this call would otherwise
+/// carry. Unlike [`DrawCall`], these aren't batched across consecutive
+/// calls sharing the same mesh/material or run through [`FrustumCuller`]:
+/// the mesh's instance buffer already holds however many instances the
+/// caller wants drawn in one go.
+pub(crate) struct InstancedDrawCall<'a> {
+    mesh: &'a Mesh,
+    material: &'a Box<dyn Material>,
+}
+
+/// The pass formats a set of static draw calls is pre-encoded against.
+/// [`wgpu::RenderBundle`]s are only replayable into a pass whose color/
+/// depth attachment formats and sample count match the ones they were
+/// built with, so this is part of the cache invalidation key.
+struct PassLayout {
+    opaque_format: wgpu::TextureFormat,
+    accum_format: wgpu::TextureFormat,
+    reveal_format: wgpu::TextureFormat,
+    depth_format: wgpu::TextureFormat,
+    sample_count: u32,
+}
+
 pub struct RenderBuffer<'a, 'b> {
     pub(crate) draw_calls: Vec<DrawCall<'a>>,
+    static_draw_calls: Vec<DrawCall<'a>>,
+    instanced_draw_calls: Vec<InstancedDrawCall<'a>>,
+    decals: Vec<&'a Decal>,
     camera_bind_group: &'b wgpu::BindGroup,
     view_mat: Mat4,
     proj_mat: Mat4,
@@ -27,6 +59,9 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
     pub fn new(camera_bind_group: &'b wgpu::BindGroup, view_mat: Mat4, proj_mat: Mat4) -> Self {
         RenderBuffer {
             draw_calls: Vec::new(),
+            static_draw_calls: Vec::new(),
+            instanced_draw_calls: Vec::new(),
+            decals: Vec::new(),
             camera_bind_group,
             view_mat,
             proj_mat,
@@ -46,16 +81,289 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
         });
     }
 
-    pub fn render_to_texture(&self, ctx: &Context, texture: &RenderTexture) {
-        self.render_to_view(ctx, &texture.texture.view, &texture.depth.view)
+    /// Queues a draw call for a mesh created via
+    /// [`crate::Context::create_instanced_mesh`], issuing a single
+    /// `draw_indexed` covering every instance its buffer currently holds
+    /// instead of one draw call per instance. `material`'s pipeline must
+    /// have been built from a [`SimplifiedPipelineConfig`] with
+    /// `instanced: true`.
+    pub fn add_instanced_draw_call(&mut self, mesh: &'a Mesh, material: &'a Box<dyn Material>) {
+        self.instanced_draw_calls.push(InstancedDrawCall { mesh, material });
+    }
+
+    /// Queues `decal` to be stamped on top of the frame's fully resolved
+    /// color target, after the opaque/OIT passes have already been
+    /// composited together. Unlike [`Self::add_draw_call`], decals aren't
+    /// depth-tested against the scene, so they always render over
+    /// whatever's already there.
+    pub fn add_decal(&mut self, decal: &'a Decal) {
+        self.decals.push(decal);
+    }
+
+    /// Queues a draw call whose pipeline, bind groups and vertex/index
+    /// buffers never change frame to frame (e.g. static level geometry).
+    ///
+    /// Draws added this way are pre-encoded once into a [`wgpu::RenderBundle`]
+    /// per pass and replayed with `execute_bundles` instead of re-recording
+    /// `set_pipeline`/`set_bind_group`/`draw_indexed` on the CPU every
+    /// frame. Only the model matrix is still uploaded each frame, since
+    /// "static" here only promises a stable draw-call set, not a frozen
+    /// transform. The cached bundles are rebuilt automatically the next
+    /// time this set of mesh/material pairs changes.
+    pub fn add_static_draw_call(
+        &mut self,
+        mesh: &'a Mesh,
+        material: &'a Box<dyn Material>,
+        model_matrix: Mat4,
+    ) {
+        self.static_draw_calls.push(DrawCall {
+            mesh,
+            material,
+            model_matrix,
+        });
+    }
+
+    /// Runs the same opaque/OIT/composite pipeline as [`Self::submit`]
+    /// into `target` instead of presenting to the swapchain, resolving
+    /// `target`'s MSAA attachments (if any) down before compositing. The
+    /// draw calls queued on this [`RenderBuffer`] aren't consumed, so the
+    /// same frame can still be `.submit()`ed to the screen afterward.
+    pub fn render_to(&self, ctx: &Context, target: &RenderTarget) {
+        let (opaque_view, opaque_resolve, accum_view, accum_resolve, reveal_view, reveal_resolve, depth_view) =
+            match &target.msaa {
+                Some(msaa) => (
+                    &msaa.oit_opaque,
+                    Some(&target.oit_opaque.view),
+                    &msaa.oit_accum,
+                    Some(&target.oit_accum.view),
+                    &msaa.oit_reveal,
+                    Some(&target.oit_reveal.view),
+                    &msaa.depth,
+                ),
+                None => (
+                    &target.oit_opaque.view,
+                    None,
+                    &target.oit_accum.view,
+                    None,
+                    &target.oit_reveal.view,
+                    None,
+                    &target.depth.view,
+                ),
+            };
+
+        let layout = PassLayout {
+            opaque_format: target.oit_opaque.texture.format(),
+            accum_format: target.oit_accum.texture.format(),
+            reveal_format: target.oit_reveal.texture.format(),
+            depth_format: target.depth.texture.format(),
+            sample_count: target.msaa.as_ref().map_or(1, |msaa| msaa.sample_count),
+        };
+
+        self.render_to_view(ctx, opaque_view, opaque_resolve, depth_view, &layout);
+        self.render_to_view_oit(ctx, accum_view, accum_resolve, reveal_view, reveal_resolve, depth_view, &layout);
+        self.render_oit_composite(
+            ctx,
+            &target.texture.view,
+            target.format,
+            &target.oit_opaque,
+            &target.oit_accum,
+            &target.oit_reveal,
+        );
+        self.render_decals(ctx, &target.texture.view);
+    }
+
+    /// Computes a key identifying the current static draw-call set (by
+    /// mesh/material identity) against the pass it would be replayed
+    /// into, so [`Self::ensure_static_bundles`] can tell whether the
+    /// cached [`wgpu::RenderBundle`]s are still valid.
+    fn static_batch_key(&self, layout: &PassLayout) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for draw_call in &self.static_draw_calls {
+            (draw_call.mesh as *const Mesh).hash(&mut hasher);
+            (draw_call.material as *const Box<dyn Material>).hash(&mut hasher);
+        }
+        layout.opaque_format.hash(&mut hasher);
+        layout.accum_format.hash(&mut hasher);
+        layout.reveal_format.hash(&mut hasher);
+        layout.depth_format.hash(&mut hasher);
+        layout.sample_count.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Rebuilds `ctx`'s cached static [`wgpu::RenderBundle`]s if the set of
+    /// static draw calls (or the pass formats/sample count they're
+    /// replayed into) has changed since the last frame; otherwise leaves
+    /// the cache untouched.
+    fn ensure_static_bundles(&self, ctx: &Context, layout: &PassLayout) {
+        if self.static_draw_calls.is_empty() {
+            return;
+        }
+
+        let key = self.static_batch_key(layout);
+        if matches!(&*ctx.static_bundle_cache.borrow(), Some(cache) if cache.key == key) {
+            return;
+        }
+
+        let opaque = self.build_static_opaque_bundle(ctx, layout);
+        let oit = self.build_static_oit_bundle(ctx, layout);
+        *ctx.static_bundle_cache.borrow_mut() = Some(StaticBundleCache { key, opaque, oit });
+    }
+
+    fn build_static_opaque_bundle(&self, ctx: &Context, layout: &PassLayout) -> wgpu::RenderBundle {
+        let mut encoder =
+            ctx.device
+                .create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
+                    label: Some("Static Opaque Bundle Encoder"),
+                    color_formats: &[Some(layout.opaque_format)],
+                    depth_stencil: Some(wgpu::RenderBundleDepthStencil {
+                        format: layout.depth_format,
+                        depth_read_only: false,
+                        stencil_read_only: true,
+                    }),
+                    sample_count: layout.sample_count,
+                    multiview: None,
+                });
+
+        for draw_call in &self.static_draw_calls {
+            if !draw_call.material.has_opaque_pixels() {
+                continue;
+            }
+
+            encoder.set_pipeline(&draw_call.material.get_shader().pipeline);
+
+            encoder.set_bind_group(0, self.camera_bind_group, &[]);
+            encoder.set_bind_group(1, &draw_call.mesh.model_bind_group, &[]);
+            encoder.set_bind_group(2, &draw_call.material.get_bind_group(), &[]);
+
+            encoder.set_vertex_buffer(0, draw_call.mesh.vertex_buffer.slice(..));
+            encoder.set_index_buffer(
+                draw_call.mesh.index_buffer.slice(..),
+                wgpu::IndexFormat::Uint16,
+            );
+
+            encoder.draw_indexed(0..draw_call.mesh.num_triangles, 0, 0..1);
+        }
+
+        encoder.finish(&wgpu::RenderBundleDescriptor {
+            label: Some("Static Opaque Bundle"),
+        })
+    }
+
+    fn build_static_oit_bundle(&self, ctx: &Context, layout: &PassLayout) -> wgpu::RenderBundle {
+        let mut encoder =
+            ctx.device
+                .create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
+                    label: Some("Static OIT Bundle Encoder"),
+                    color_formats: &[Some(layout.accum_format), Some(layout.reveal_format)],
+                    depth_stencil: Some(wgpu::RenderBundleDepthStencil {
+                        format: layout.depth_format,
+                        depth_read_only: true,
+                        stencil_read_only: true,
+                    }),
+                    sample_count: layout.sample_count,
+                    multiview: None,
+                });
+
+        for draw_call in &self.static_draw_calls {
+            if !draw_call.material.has_transparent_pixels() {
+                continue;
+            }
+
+            encoder.set_pipeline(&draw_call.material.get_transparent_shader().pipeline);
+
+            encoder.set_bind_group(0, self.camera_bind_group, &[]);
+            encoder.set_bind_group(1, &draw_call.mesh.model_bind_group, &[]);
+            encoder.set_bind_group(2, &draw_call.material.get_bind_group(), &[]);
+
+            encoder.set_vertex_buffer(0, draw_call.mesh.vertex_buffer.slice(..));
+            encoder.set_index_buffer(
+                draw_call.mesh.index_buffer.slice(..),
+                wgpu::IndexFormat::Uint16,
+            );
+
+            encoder.draw_indexed(0..draw_call.mesh.num_triangles, 0, 0..1);
+        }
+
+        encoder.finish(&wgpu::RenderBundleDescriptor {
+            label: Some("Static OIT Bundle"),
+        })
+    }
+
+    /// Groups consecutive entries of [`Self::draw_calls`] matching `filter`
+    /// that share the same [`Mesh`] and [`Material`] pointer into a single
+    /// batch, then hands each batch to [`FrustumCuller::cull`] so the
+    /// camera-frustum test and instance compaction happen on the GPU
+    /// instead of walking every bounding sphere here.
+    ///
+    /// Returns `(start, end, batch)` triples identifying each batch's
+    /// half-open range into [`Self::draw_calls`] and the [`CulledBatch`]
+    /// its single `draw_indexed_indirect` call should use. Batches are
+    /// built eagerly and returned together rather than lazily inside the
+    /// draw loop, since each one must outlive the render pass it gets
+    /// replayed into.
+    fn batch_draw_calls(
+        &self,
+        ctx: &Context,
+        filter: impl Fn(&DrawCall<'a>) -> bool,
+    ) -> Vec<(usize, usize, CulledBatch)> {
+        let mut batches = Vec::new();
+        let mut i = 0;
+
+        while i < self.draw_calls.len() {
+            if !filter(&self.draw_calls[i]) {
+                i += 1;
+                continue;
+            }
+
+            let mesh_ptr = self.draw_calls[i].mesh as *const Mesh;
+            let material_ptr = self.draw_calls[i].material as *const Box<dyn Material>;
+
+            let mut j = i + 1;
+            while j < self.draw_calls.len()
+                && self.draw_calls[j].mesh as *const Mesh == mesh_ptr
+                && self.draw_calls[j].material as *const Box<dyn Material> == material_ptr
+            {
+                j += 1;
+            }
+
+            let mesh = self.draw_calls[i].mesh;
+            let instances: Vec<CullInstance> = self.draw_calls[i..j]
+                .iter()
+                .map(|draw_call| CullInstance {
+                    local_center: [
+                        mesh.bounding_sphere[0],
+                        mesh.bounding_sphere[1],
+                        mesh.bounding_sphere[2],
+                    ],
+                    local_radius: mesh.bounding_sphere[3],
+                    matrices: ModelMatrices::new(self.view_mat, self.proj_mat, draw_call.model_matrix),
+                })
+                .collect();
+
+            let culled = ctx.frustum_culler().cull(
+                ctx,
+                &instances,
+                mesh.num_triangles,
+                self.proj_mat * self.view_mat,
+            );
+
+            batches.push((i, j, culled));
+            i = j;
+        }
+
+        batches
     }
 
-    fn draw_to_pass<'c>(&self, ctx: &Context, mut render_pass: wgpu::RenderPass<'c>)
-    where
+    fn draw_to_pass<'c>(
+        &self,
+        ctx: &Context,
+        mut render_pass: wgpu::RenderPass<'c>,
+        static_bundle: Option<&'c wgpu::RenderBundle>,
+    ) where
         'b: 'c,
         'a: 'c,
     {
-        for draw_call in &self.draw_calls {
+        for draw_call in &self.static_draw_calls {
             if !draw_call.material.has_opaque_pixels() {
                 continue;
             }
@@ -68,11 +376,20 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
                     draw_call.model_matrix,
                 )],
             );
+        }
+
+        if let Some(bundle) = static_bundle {
+            render_pass.execute_bundles(std::iter::once(bundle));
+        }
+
+        let batches = self.batch_draw_calls(ctx, |draw_call| draw_call.material.has_opaque_pixels());
+        for (start, _end, culled) in &batches {
+            let draw_call = &self.draw_calls[*start];
 
             render_pass.set_pipeline(&draw_call.material.get_shader().pipeline);
 
             render_pass.set_bind_group(0, self.camera_bind_group, &[]);
-            render_pass.set_bind_group(1, &draw_call.mesh.model_bind_group, &[]);
+            render_pass.set_bind_group(1, &culled.model_bind_group, &[]);
             render_pass.set_bind_group(2, &draw_call.material.get_bind_group(), &[]);
 
             render_pass.set_vertex_buffer(0, draw_call.mesh.vertex_buffer.slice(..));
@@ -81,16 +398,48 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
                 wgpu::IndexFormat::Uint16,
             );
 
-            render_pass.draw_indexed(0..draw_call.mesh.num_triangles, 0, 0..1);
+            render_pass.draw_indexed_indirect(&culled.indirect_buffer, 0);
+        }
+
+        for instanced in &self.instanced_draw_calls {
+            if !instanced.material.has_opaque_pixels() {
+                continue;
+            }
+            let Some(instance_buffer) = &instanced.mesh.instance_buffer else {
+                continue;
+            };
+
+            render_pass.set_pipeline(&instanced.material.get_shader().pipeline);
+
+            render_pass.set_bind_group(0, self.camera_bind_group, &[]);
+            render_pass.set_bind_group(1, &instanced.mesh.model_bind_group, &[]);
+            render_pass.set_bind_group(2, &instanced.material.get_bind_group(), &[]);
+
+            render_pass.set_vertex_buffer(0, instanced.mesh.vertex_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+            render_pass.set_index_buffer(
+                instanced.mesh.index_buffer.slice(..),
+                wgpu::IndexFormat::Uint16,
+            );
+
+            render_pass.draw_indexed(
+                0..instanced.mesh.num_triangles,
+                0,
+                0..instanced.mesh.instance_count,
+            );
         }
     }
 
-    fn draw_to_pass_oit<'c>(&self, ctx: &Context, mut render_pass: wgpu::RenderPass<'c>)
-    where
+    fn draw_to_pass_oit<'c>(
+        &self,
+        ctx: &Context,
+        mut render_pass: wgpu::RenderPass<'c>,
+        static_bundle: Option<&'c wgpu::RenderBundle>,
+    ) where
         'b: 'c,
         'a: 'c,
     {
-        for draw_call in &self.draw_calls {
+        for draw_call in &self.static_draw_calls {
             if !draw_call.material.has_transparent_pixels() {
                 continue;
             }
@@ -103,11 +452,20 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
                     draw_call.model_matrix,
                 )],
             );
+        }
+
+        if let Some(bundle) = static_bundle {
+            render_pass.execute_bundles(std::iter::once(bundle));
+        }
+
+        let batches = self.batch_draw_calls(ctx, |draw_call| draw_call.material.has_transparent_pixels());
+        for (start, _end, culled) in &batches {
+            let draw_call = &self.draw_calls[*start];
 
             render_pass.set_pipeline(&draw_call.material.get_transparent_shader().pipeline);
 
             render_pass.set_bind_group(0, self.camera_bind_group, &[]);
-            render_pass.set_bind_group(1, &draw_call.mesh.model_bind_group, &[]);
+            render_pass.set_bind_group(1, &culled.model_bind_group, &[]);
             render_pass.set_bind_group(2, &draw_call.material.get_bind_group(), &[]);
 
             render_pass.set_vertex_buffer(0, draw_call.mesh.vertex_buffer.slice(..));
@@ -116,11 +474,48 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
                 wgpu::IndexFormat::Uint16,
             );
 
-            render_pass.draw_indexed(0..draw_call.mesh.num_triangles, 0, 0..1);
+            render_pass.draw_indexed_indirect(&culled.indirect_buffer, 0);
+        }
+
+        for instanced in &self.instanced_draw_calls {
+            if !instanced.material.has_transparent_pixels() {
+                continue;
+            }
+            let Some(instance_buffer) = &instanced.mesh.instance_buffer else {
+                continue;
+            };
+
+            render_pass.set_pipeline(&instanced.material.get_transparent_shader().pipeline);
+
+            render_pass.set_bind_group(0, self.camera_bind_group, &[]);
+            render_pass.set_bind_group(1, &instanced.mesh.model_bind_group, &[]);
+            render_pass.set_bind_group(2, &instanced.material.get_bind_group(), &[]);
+
+            render_pass.set_vertex_buffer(0, instanced.mesh.vertex_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+            render_pass.set_index_buffer(
+                instanced.mesh.index_buffer.slice(..),
+                wgpu::IndexFormat::Uint16,
+            );
+
+            render_pass.draw_indexed(
+                0..instanced.mesh.num_triangles,
+                0,
+                0..instanced.mesh.instance_count,
+            );
         }
     }
 
-    fn render_to_view(&self, ctx: &Context, view: &wgpu::TextureView, depth: &wgpu::TextureView) {
+    fn render_to_view(
+        &self,
+        ctx: &Context,
+        view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
+        depth: &wgpu::TextureView,
+        layout: &PassLayout,
+    ) {
+        self.ensure_static_bundles(ctx, layout);
+
         let mut encoder = ctx
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -132,7 +527,7 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &view,
-                    resolve_target: None,
+                    resolve_target,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
                         store: true,
@@ -146,9 +541,13 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
                     }),
                     stencil_ops: None,
                 }),
+                timestamp_writes: ctx.profiler.as_ref().map(|profiler| profiler.opaque_writes()),
+                occlusion_query_set: None,
             });
 
-            self.draw_to_pass(ctx, render_pass);
+            let cache = ctx.static_bundle_cache.borrow();
+            let static_bundle = cache.as_ref().map(|cache| &cache.opaque);
+            self.draw_to_pass(ctx, render_pass, static_bundle);
         }
 
         ctx.queue.submit(std::iter::once(encoder.finish()));
@@ -158,9 +557,14 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
         &self,
         ctx: &Context,
         accum: &wgpu::TextureView,
+        accum_resolve: Option<&wgpu::TextureView>,
         reveal: &wgpu::TextureView,
+        reveal_resolve: Option<&wgpu::TextureView>,
         depth: &wgpu::TextureView,
+        layout: &PassLayout,
     ) {
+        self.ensure_static_bundles(ctx, layout);
+
         let mut encoder = ctx
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -173,7 +577,7 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
                 color_attachments: &[
                     Some(wgpu::RenderPassColorAttachment {
                         view: accum,
-                        resolve_target: None,
+                        resolve_target: accum_resolve,
                         ops: wgpu::Operations {
                             load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
                             store: true,
@@ -181,7 +585,7 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
                     }),
                     Some(wgpu::RenderPassColorAttachment {
                         view: reveal,
-                        resolve_target: None,
+                        resolve_target: reveal_resolve,
                         ops: wgpu::Operations {
                             load: wgpu::LoadOp::Clear(wgpu::Color {
                                 r: 1.0,
@@ -198,9 +602,13 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
                     depth_ops: None,
                     stencil_ops: None,
                 }),
+                timestamp_writes: ctx.profiler.as_ref().map(|profiler| profiler.oit_writes()),
+                occlusion_query_set: None,
             });
 
-            self.draw_to_pass_oit(ctx, render_pass);
+            let cache = ctx.static_bundle_cache.borrow();
+            let static_bundle = cache.as_ref().map(|cache| &cache.oit);
+            self.draw_to_pass_oit(ctx, render_pass, static_bundle);
         }
 
         ctx.queue.submit(std::iter::once(encoder.finish()));
@@ -210,6 +618,7 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
         &self,
         ctx: &Context,
         target: &wgpu::TextureView,
+        target_format: wgpu::TextureFormat,
         opaque: &Texture,
         accum: &Texture,
         reveal: &Texture,
@@ -221,19 +630,23 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
             });
 
         let plane = Mesh::make_screen_plane(ctx);
-        let bgl = ctx.create_bind_group_layout(vec![
+        let bgl_desc = [
             BindGroupLayoutEntry::Texture{dim: TextureDimensions::D2, filtering: true},
             BindGroupLayoutEntry::Sampler{filtering: true},
-        ]);
+        ];
+        let bgl = ctx.create_bind_group_layout(bgl_desc.to_vec());
+        let layout_descs: [&[BindGroupLayoutEntry]; 2] = [&bgl_desc, &bgl_desc];
 
         let composite_shader = ctx.create_shader(
             OIT_COMPOSITE,
+            &[],
             vec![&bgl, &bgl],
+            &layout_descs,
             SimplifiedPipelineConfig {
                 wireframe: false,
                 msaa: MSAA::Off,
                 targets: vec![wgpu::ColorTargetState {
-                    format: ctx.surface.config.format,
+                    format: target_format,
                     blend: Some(wgpu::BlendState {
                         color: BlendComponent {
                             src_factor: BlendFactor::SrcAlpha,
@@ -249,21 +662,25 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
                     write_mask: wgpu::ColorWrites::ALL,
                 }],
                 depth_settings: None,
+                instanced: false,
             },
         );
 
         let screen_shader = ctx.create_shader(
             SCREEN,
+            &[],
             vec![&bgl],
+            &layout_descs[..1],
             SimplifiedPipelineConfig {
                 wireframe: false,
                 msaa: MSAA::Off,
                 targets: vec![wgpu::ColorTargetState {
-                    format: ctx.surface.config.format,
+                    format: target_format,
                     blend: Some(wgpu::BlendState::ALPHA_BLENDING),
                     write_mask: wgpu::ColorWrites::ALL,
                 }],
                 depth_settings: None,
+                instanced: false,
             },
         );
 
@@ -303,6 +720,11 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
                     },
                 })],
                 depth_stencil_attachment: None,
+                timestamp_writes: ctx
+                    .profiler
+                    .as_ref()
+                    .map(|profiler| profiler.composite_writes()),
+                occlusion_query_set: None,
             });
 
             // Copy opaque to screen
@@ -323,6 +745,59 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
             render_pass.draw_indexed(0..plane.num_triangles, 0, 0..1);
         }
 
+        if let Some(profiler) = &ctx.profiler {
+            profiler.resolve(&mut encoder);
+        }
+
+        ctx.queue.submit(std::iter::once(encoder.finish()));
+
+        if let Some(profiler) = &ctx.profiler {
+            profiler.update_timings(&ctx.device);
+        }
+    }
+
+    /// Draws every queued [`Decal`] straight into `view`, loading (not
+    /// clearing) whatever [`Self::render_oit_composite`] just resolved
+    /// into it, so decals land on top of the finished frame instead of
+    /// being composited underneath transparent geometry.
+    fn render_decals(&self, ctx: &Context, view: &wgpu::TextureView) {
+        if self.decals.is_empty() {
+            return;
+        }
+
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Decal Command Encoder"),
+            });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Decal Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            for decal in &self.decals {
+                render_pass.set_pipeline(&decal.pipeline.pipeline);
+                render_pass.set_bind_group(0, self.camera_bind_group, &[]);
+                render_pass.set_bind_group(1, &decal.bind_group, &[]);
+                render_pass.set_vertex_buffer(0, decal.vertex_buffer.slice(..));
+                render_pass
+                    .set_index_buffer(decal.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                render_pass.draw_indexed(0..6, 0, 0..1);
+            }
+        }
+
         ctx.queue.submit(std::iter::once(encoder.finish()));
     }
 
@@ -352,15 +827,56 @@ impl<'a, 'b> RenderBuffer<'a, 'b> {
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        self.render_to_view(ctx, &ctx.oit_opaque.view, &ctx.depth_buffer.view);
+        let (opaque_view, opaque_resolve, accum_view, accum_resolve, reveal_view, reveal_resolve, depth_view) =
+            match &ctx.msaa {
+                Some(msaa) => (
+                    &msaa.oit_opaque,
+                    Some(&ctx.oit_opaque.view),
+                    &msaa.oit_accum,
+                    Some(&ctx.oit_accum.view),
+                    &msaa.oit_reveal,
+                    Some(&ctx.oit_reveal.view),
+                    &msaa.depth,
+                ),
+                None => (
+                    &ctx.oit_opaque.view,
+                    None,
+                    &ctx.oit_accum.view,
+                    None,
+                    &ctx.oit_reveal.view,
+                    None,
+                    &ctx.depth_buffer.view,
+                ),
+            };
+
+        let layout = PassLayout {
+            opaque_format: ctx.oit_opaque.texture.format(),
+            accum_format: ctx.oit_accum.texture.format(),
+            reveal_format: ctx.oit_reveal.texture.format(),
+            depth_format: ctx.depth_buffer.texture.format(),
+            sample_count: ctx.msaa.as_ref().map_or(1, |msaa| msaa.sample_count),
+        };
+
+        self.render_to_view(ctx, opaque_view, opaque_resolve, depth_view, &layout);
         self.render_to_view_oit(
             ctx,
-            &ctx.oit_accum.view,
-            &ctx.oit_reveal.view,
-            &ctx.depth_buffer.view,
+            accum_view,
+            accum_resolve,
+            reveal_view,
+            reveal_resolve,
+            depth_view,
+            &layout,
         );
 
-        self.render_oit_composite(ctx, &view, &ctx.oit_opaque, &ctx.oit_accum, &ctx.oit_reveal);
+        self.render_oit_composite(
+            ctx,
+            &view,
+            ctx.surface.config.format,
+            &ctx.oit_opaque,
+            &ctx.oit_accum,
+            &ctx.oit_reveal,
+        );
+        self.render_decals(ctx, &view);
 
         output.present();
 
@@ -1,6 +1,29 @@
 //! Shader code and utilities
 
+use std::collections::HashMap;
+
 pub const FLAT_TEXTURE: &str = include_str!("../shaders/flat_texture.wgsl");
 pub const OIT_FLAT_TEXTURE: &str = include_str!("../shaders/oit_flat_texture.wgsl");
 pub const OIT_COMPOSITE: &str = include_str!("../shaders/oit_composite.wgsl");
 pub const SCREEN: &str = include_str!("../shaders/screen.wgsl");
+pub const PBR_METALLIC_ROUGHNESS: &str =
+    include_str!("../shaders/pbr_metallic_roughness.wgsl");
+pub const OIT_PBR_METALLIC_ROUGHNESS: &str =
+    include_str!("../shaders/oit_pbr_metallic_roughness.wgsl");
+pub const SHADOW_DEPTH: &str = include_str!("../shaders/shadow_depth.wgsl");
+pub const MIPMAP_BLIT: &str = include_str!("../shaders/mipmap_blit.wgsl");
+pub const DECAL: &str = include_str!("../shaders/decal.wgsl");
+
+pub const VERTEX_INCLUDE: &str = include_str!("../shaders/common/vertex.wgsl");
+pub const LIGHTING_INCLUDE: &str = include_str!("../shaders/common/lighting.wgsl");
+pub const SHADOW_SAMPLING_INCLUDE: &str = include_str!("../shaders/common/shadow_sampling.wgsl");
+
+/// Shared WGSL snippets available to every shader source via
+/// `#include "name"`, keyed by the name used in the directive.
+pub fn shared_includes() -> HashMap<&'static str, &'static str> {
+    HashMap::from([
+        ("vertex", VERTEX_INCLUDE),
+        ("lighting", LIGHTING_INCLUDE),
+        ("shadow_sampling", SHADOW_SAMPLING_INCLUDE),
+    ])
+}
@@ -0,0 +1,268 @@
+//! Shadow mapping for directional and spot lights
+//!
+//! [`ShadowCaster`] renders scene depth from a light's point of view into a
+//! dedicated depth [`Texture`]-like map, then exposes it as a bind group
+//! material shaders can sample with `textureSampleCompare` to attenuate
+//! lighting in shadow. [`ShadowSettings`] selects between hardware 2x2
+//! comparison filtering, a wider PCF kernel, or PCSS, which derives the PCF
+//! kernel radius from an estimated penumbra width instead of using a fixed
+//! one.
+
+use std::rc::Rc;
+
+use buddle_math::{Mat4, UVec2};
+
+use crate::gpu::{Mesh, Shader, SHADOW_DEPTH};
+use crate::{BindGroupLayoutEntry, Context, DepthSettings, SimplifiedPipelineConfig, TextureDimensions, MSAA};
+
+/// How a [`ShadowCaster`]'s depth map is turned into a `[0, 1]` visibility
+/// term while sampling.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ShadowMode {
+    /// A single `textureSampleCompare` tap, relying on the sampler's
+    /// built-in 2x2 hardware comparison filtering.
+    Hardware,
+    /// Averages `taps * taps` comparison samples offset by one texel
+    /// around the projected coordinate.
+    Pcf { taps: u32 },
+    /// Percentage-closer soft shadows: a blocker search first estimates
+    /// the penumbra width from occluders closer than the receiver, then
+    /// [`ShadowMode::Pcf`] runs with a kernel radius scaled by that width,
+    /// so shadows near the caster are sharp and soften with distance.
+    Pcss { light_size: f32, taps: u32 },
+}
+
+/// Per-light shadow configuration.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ShadowSettings {
+    pub mode: ShadowMode,
+    /// Subtracted from the receiver's light-space depth before the
+    /// comparison, so surfaces don't self-shadow from their own rasterized
+    /// depth ("shadow acne").
+    pub bias: f32,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            mode: ShadowMode::Pcf { taps: 3 },
+            bias: 0.005,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct DepthMvp {
+    mvp: [[f32; 4]; 4],
+}
+
+impl DepthMvp {
+    fn new(mvp: Mat4) -> Self {
+        Self {
+            mvp: mvp.to_cols_array_2d(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct ShadowUniform {
+    light_view_proj: [[f32; 4]; 4],
+    // x = bias, y = texel size, z = light_size (PCSS only), w = mode tag
+    // (0 = hardware, 1 = PCF, 2 = PCSS).
+    params: [f32; 4],
+    // x = taps, rest unused; kept in its own vector so `params` stays a
+    // tidy "filter shape" group and this stays a "filter size" group.
+    taps: [f32; 4],
+}
+
+/// A depth-only render target for a single shadow-casting light, plus the
+/// bind group material shaders sample it through.
+pub struct ShadowCaster {
+    pub settings: ShadowSettings,
+
+    size: UVec2,
+    light_view_proj: Mat4,
+    depth_view: wgpu::TextureView,
+
+    pipeline: Rc<Shader>,
+    mvp_buffer: wgpu::Buffer,
+    mvp_bind_group: wgpu::BindGroup,
+
+    uniform_buffer: wgpu::Buffer,
+    /// Binds `{DepthTexture, ComparisonSampler, Buffer}`, in that order, so
+    /// a material's pipeline layout can append it as one more bind group.
+    pub sample_bind_group: wgpu::BindGroup,
+}
+
+impl Context {
+    /// Creates a [`ShadowCaster`] with a `size`x`size` depth map.
+    pub fn create_shadow_caster(&self, size: UVec2, settings: ShadowSettings) -> ShadowCaster {
+        let depth_texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Shadow Map"),
+            size: wgpu::Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor {
+            format: Some(wgpu::TextureFormat::Depth32Float),
+            ..Default::default()
+        });
+
+        let comparison_sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToBorder,
+            address_mode_v: wgpu::AddressMode::ClampToBorder,
+            address_mode_w: wgpu::AddressMode::ClampToBorder,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            compare: Some(wgpu::CompareFunction::Less),
+            ..Default::default()
+        });
+
+        let mvp_gl = self.create_bind_group_layout(vec![BindGroupLayoutEntry::Buffer { dynamic: false, min_binding_size: None }]);
+        let mvp_buffer = self.create_buffer(
+            &[DepthMvp::new(Mat4::IDENTITY)],
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        );
+        let mvp_bind_group =
+            self.create_bind_group(&mvp_gl, vec![mvp_buffer.as_entire_binding()]);
+
+        let layout_descs: [&[BindGroupLayoutEntry]; 1] = [&[BindGroupLayoutEntry::Buffer { dynamic: false, min_binding_size: None }]];
+        let pipeline = self.create_shader(
+            SHADOW_DEPTH,
+            &[],
+            vec![&mvp_gl],
+            &layout_descs,
+            SimplifiedPipelineConfig {
+                wireframe: false,
+                msaa: MSAA::Off,
+                targets: vec![],
+                depth_settings: Some(DepthSettings {
+                    compare: wgpu::CompareFunction::Less,
+                    write: true,
+                }),
+                instanced: false,
+            },
+        );
+
+        let sample_gl = self.create_bind_group_layout(vec![
+            BindGroupLayoutEntry::DepthTexture {
+                dim: TextureDimensions::D2,
+            },
+            BindGroupLayoutEntry::ComparisonSampler,
+            BindGroupLayoutEntry::Buffer { dynamic: false, min_binding_size: None },
+        ]);
+        let uniform_buffer = self.create_buffer(
+            &[ShadowUniform::new(Mat4::IDENTITY, size, settings)],
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        );
+        let sample_bind_group = self.create_bind_group(
+            &sample_gl,
+            vec![
+                wgpu::BindingResource::TextureView(&depth_view),
+                wgpu::BindingResource::Sampler(&comparison_sampler),
+                uniform_buffer.as_entire_binding(),
+            ],
+        );
+
+        ShadowCaster {
+            settings,
+            size,
+            light_view_proj: Mat4::IDENTITY,
+            depth_view,
+            pipeline,
+            mvp_buffer,
+            mvp_bind_group,
+            uniform_buffer,
+            sample_bind_group,
+        }
+    }
+}
+
+impl ShadowUniform {
+    fn new(light_view_proj: Mat4, size: UVec2, settings: ShadowSettings) -> Self {
+        let (mode, light_size, taps) = match settings.mode {
+            ShadowMode::Hardware => (0.0, 0.0, 1.0),
+            ShadowMode::Pcf { taps } => (1.0, 0.0, taps as f32),
+            ShadowMode::Pcss { light_size, taps } => (2.0, light_size, taps as f32),
+        };
+
+        Self {
+            light_view_proj: light_view_proj.to_cols_array_2d(),
+            params: [settings.bias, 1.0 / size.x as f32, light_size, mode],
+            taps: [taps, 0.0, 0.0, 0.0],
+        }
+    }
+}
+
+impl ShadowCaster {
+    /// Gets the size, in texels, of the depth map.
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Updates the light's view-projection matrix, re-uploading the
+    /// sampling-side uniform; [`Self::render`] combines the stored matrix
+    /// with each mesh's model matrix when it fills the per-draw buffer.
+    pub fn set_light_view_proj(&mut self, ctx: &Context, light_view_proj: Mat4) {
+        ctx.update_buffer(
+            &self.uniform_buffer,
+            &[ShadowUniform::new(light_view_proj, self.size, self.settings)],
+        );
+        self.light_view_proj = light_view_proj;
+    }
+
+    /// Renders `meshes` (each paired with its world-space model matrix)
+    /// into the depth map from the light's point of view.
+    ///
+    /// Must run before the main pass that samples [`Self::sample_bind_group`],
+    /// since both share the GPU queue's submission order.
+    pub fn render(&self, ctx: &Context, meshes: &[(&Mesh, Mat4)]) {
+        let mut encoder = ctx
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Shadow Pass Encoder"),
+            });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Shadow Pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.pipeline.pipeline);
+
+            for (mesh, model_matrix) in meshes {
+                let mvp = self.light_view_proj * *model_matrix;
+                ctx.update_buffer(&self.mvp_buffer, &[DepthMvp::new(mvp)]);
+
+                render_pass.set_bind_group(0, &self.mvp_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+                render_pass
+                    .set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                render_pass.draw_indexed(0..mesh.num_triangles, 0, 0..1);
+            }
+        }
+
+        ctx.queue.submit(std::iter::once(encoder.finish()));
+    }
+}
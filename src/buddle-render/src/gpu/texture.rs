@@ -1,6 +1,9 @@
+use anyhow::bail;
+
 use crate::{Context, TextureDimensions};
 use buddle_math::UVec2;
 
+#[derive(Clone)]
 pub struct Texture {
     pub(crate) texture: wgpu::Texture,
     pub(crate) view: wgpu::TextureView,
@@ -18,4 +21,133 @@ impl Texture {
             UVec2::new(2, 2),
         )
     }
+
+    /// Loads a [`Texture`] from encoded image bytes, sniffing the
+    /// container format from its leading magic bytes rather than trusting
+    /// a filename extension.
+    ///
+    /// Supports PNG, JPEG and TGA by decoding to RGBA8 on the CPU before
+    /// upload, and DDS by preserving BC1/BC3/BC7 block-compressed data and
+    /// uploading its full mip chain directly, since that's how most game
+    /// assets ship.
+    ///
+    /// Returns the texture alongside whether it contains any transparent
+    /// and/or opaque texels, so callers can feed the result straight into
+    /// material/blend-state selection. Block-compressed DDS data is
+    /// uploaded without CPU-side decoding, so its texels can't be scanned;
+    /// both flags are conservatively reported `true` instead.
+    ///
+    /// Fails when the magic bytes match none of the supported formats, or
+    /// when the DDS pixel format isn't one of the supported BC variants.
+    pub fn from_encoded_bytes(ctx: &Context, bytes: &[u8]) -> anyhow::Result<(Self, bool, bool)> {
+        if bytes.starts_with(b"DDS ") {
+            return Self::from_dds_bytes(ctx, bytes);
+        }
+
+        if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+            return Self::from_dynamic_image(ctx, image::load_from_memory_with_format(
+                bytes,
+                image::ImageFormat::Png,
+            )?);
+        }
+
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            return Self::from_dynamic_image(ctx, image::load_from_memory_with_format(
+                bytes,
+                image::ImageFormat::Jpeg,
+            )?);
+        }
+
+        // TGA has no magic bytes; its 18-byte header instead has a fixed
+        // layout we can sanity-check: the color map type must be 0 or 1,
+        // and the image type must be one of the documented values.
+        if bytes.len() >= 18 && bytes[1] <= 1 && matches!(bytes[2], 1..=3 | 9..=11) {
+            return Self::from_dynamic_image(
+                ctx,
+                image::load_from_memory_with_format(bytes, image::ImageFormat::Tga)?,
+            );
+        }
+
+        bail!("unrecognized image format: no matching magic bytes");
+    }
+
+    fn from_dynamic_image(ctx: &Context, image: image::DynamicImage) -> anyhow::Result<(Self, bool, bool)> {
+        let rgba = image.to_rgba8();
+        let size = UVec2::new(rgba.width(), rgba.height());
+
+        let mut transparent = false;
+        let mut opaque = false;
+        for alpha in rgba.iter().skip(3).step_by(4) {
+            if *alpha < 255 {
+                transparent = true;
+            } else {
+                opaque = true;
+            }
+
+            if transparent && opaque {
+                break;
+            }
+        }
+
+        Ok((ctx.create_texture(&rgba, size), transparent, opaque))
+    }
+
+    fn from_dds_bytes(ctx: &Context, bytes: &[u8]) -> anyhow::Result<(Self, bool, bool)> {
+        let dds = ddsfile::Dds::read(&mut std::io::Cursor::new(bytes))?;
+        let size = UVec2::new(dds.get_width(), dds.get_height());
+        let mip_count = dds.get_num_mipmap_levels().max(1);
+
+        let format = if let Some(dxgi) = dds.get_dxgi_format() {
+            match dxgi {
+                ddsfile::DxgiFormat::BC1_UNorm
+                | ddsfile::DxgiFormat::BC1_UNorm_sRGB
+                | ddsfile::DxgiFormat::BC1_Typeless => wgpu::TextureFormat::Bc1RgbaUnormSrgb,
+                ddsfile::DxgiFormat::BC3_UNorm
+                | ddsfile::DxgiFormat::BC3_UNorm_sRGB
+                | ddsfile::DxgiFormat::BC3_Typeless => wgpu::TextureFormat::Bc3RgbaUnormSrgb,
+                ddsfile::DxgiFormat::BC7_UNorm
+                | ddsfile::DxgiFormat::BC7_UNorm_sRGB
+                | ddsfile::DxgiFormat::BC7_Typeless => wgpu::TextureFormat::Bc7RgbaUnormSrgb,
+                _ => bail!("unsupported DDS pixel format: only BC1/BC3/BC7 are supported"),
+            }
+        } else if let Some(d3d) = dds.get_d3d_format() {
+            // Classic DX9-era DDS files encode BC1/BC3 as the "DXT1"/"DXT5"
+            // FourCC instead of a DXGI format.
+            match d3d {
+                ddsfile::D3DFormat::DXT1 => wgpu::TextureFormat::Bc1RgbaUnormSrgb,
+                ddsfile::D3DFormat::DXT5 => wgpu::TextureFormat::Bc3RgbaUnormSrgb,
+                _ => bail!("unsupported DDS pixel format: only BC1/BC3/BC7 are supported"),
+            }
+        } else {
+            bail!("DDS file specifies neither a DXGI nor a D3D pixel format");
+        };
+
+        let block_size = format
+            .block_size(None)
+            .expect("compressed texture format must have a known block size");
+        let mips = dds_mip_slices(&dds.data, size, mip_count, block_size);
+
+        Ok((ctx.create_compressed_texture_mips(&mips, size, format), true, true))
+    }
+}
+
+/// Splits a DDS file's concatenated mip chain into one block-compressed
+/// slice per level, using the standard "each level is half the resolution
+/// of the last, rounded down to at least 1" convention.
+fn dds_mip_slices(data: &[u8], size: UVec2, mip_count: u32, block_size: u32) -> Vec<&[u8]> {
+    let mut slices = Vec::with_capacity(mip_count as usize);
+
+    let mut offset = 0usize;
+    let mut mip_size = size;
+    for _ in 0..mip_count {
+        let blocks_wide = ((mip_size.x + 3) / 4) as usize;
+        let blocks_high = ((mip_size.y + 3) / 4) as usize;
+        let len = blocks_wide * blocks_high * block_size as usize;
+
+        slices.push(&data[offset..offset + len]);
+        offset += len;
+        mip_size = UVec2::new((mip_size.x / 2).max(1), (mip_size.y / 2).max(1));
+    }
+
+    slices
 }
@@ -0,0 +1,126 @@
+//! A growable, dynamic-offset uniform buffer for batching many small
+//! per-object uniforms into a single binding.
+
+use std::marker::PhantomData;
+use std::num::NonZeroU64;
+
+use crate::gpu::context::Context;
+
+/// Packs many `T`s into one uniform buffer, each at an offset aligned to
+/// the device's `min_uniform_buffer_offset_alignment`, so every instance
+/// can be bound through a single [`wgpu::BindGroup`] (built once per
+/// frame via [`Self::create_bind_group`]) and selected per draw with
+/// `set_bind_group(.., &[offset])`, instead of each object owning its own
+/// buffer and bind group.
+///
+/// This only manages the storage itself; building the bind group layout
+/// is the caller's job, since the visibility and binding index it needs
+/// depend on where it's plugged into a pipeline (see
+/// [`BindGroupLayoutEntry::Buffer`](crate::BindGroupLayoutEntry::Buffer)'s
+/// `dynamic`/`min_binding_size` fields).
+pub struct UniformStorage<T> {
+    buffer: wgpu::Buffer,
+    slot_size: wgpu::BufferAddress,
+    capacity: wgpu::BufferAddress,
+    len: wgpu::BufferAddress,
+    _marker: PhantomData<T>,
+}
+
+impl<T: bytemuck::Pod + bytemuck::Zeroable> UniformStorage<T> {
+    /// Creates a [`UniformStorage`] with room for `capacity` instances of
+    /// `T` before its first grow.
+    pub fn new(ctx: &Context, capacity: u32) -> Self {
+        let slot_size = Self::slot_size(ctx);
+        let buffer_size = slot_size * capacity.max(1) as wgpu::BufferAddress;
+
+        UniformStorage {
+            buffer: Self::create_buffer(ctx, buffer_size),
+            slot_size,
+            capacity: buffer_size,
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// `T`'s size, rounded up to `ctx`'s `min_uniform_buffer_offset_alignment`,
+    /// so every slot can be used as a dynamic bind-group offset.
+    fn slot_size(ctx: &Context) -> wgpu::BufferAddress {
+        let size = std::mem::size_of::<T>() as wgpu::BufferAddress;
+        let alignment = ctx.device.limits().min_uniform_buffer_offset_alignment as wgpu::BufferAddress;
+        ((size + alignment - 1) / alignment) * alignment
+    }
+
+    fn create_buffer(ctx: &Context, size: wgpu::BufferAddress) -> wgpu::Buffer {
+        ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Uniform Storage"),
+            size,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Clears every registered instance so the next round of [`Self::push`]
+    /// calls starts back at offset zero. Doesn't shrink the underlying
+    /// buffer, so a storage that's already grown to fit a frame's worth of
+    /// draws doesn't reallocate again next frame.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Appends `value`, growing the underlying buffer (preserving
+    /// everything already pushed this round) if it's out of room, and
+    /// returns the byte offset to pass to `set_bind_group(.., &[offset])`
+    /// when drawing with it.
+    pub fn push(&mut self, ctx: &Context, value: T) -> wgpu::DynamicOffset {
+        let offset = self.len;
+
+        if offset + self.slot_size > self.capacity {
+            let new_capacity = (self.capacity * 2).max(offset + self.slot_size);
+            let new_buffer = Self::create_buffer(ctx, new_capacity);
+
+            if self.len > 0 {
+                let mut encoder = ctx
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                        label: Some("Uniform Storage Grow Encoder"),
+                    });
+                encoder.copy_buffer_to_buffer(&self.buffer, 0, &new_buffer, 0, self.len);
+                ctx.queue.submit(std::iter::once(encoder.finish()));
+            }
+
+            self.buffer = new_buffer;
+            self.capacity = new_capacity;
+        }
+
+        ctx.update_buffer_at(&self.buffer, offset, &[value]);
+        self.len += self.slot_size;
+
+        offset as wgpu::DynamicOffset
+    }
+
+    /// The size a bind group binding this storage must declare via
+    /// [`BindGroupLayoutEntry::Buffer`](crate::BindGroupLayoutEntry::Buffer)'s
+    /// `min_binding_size`, so it can be indexed with a dynamic offset one
+    /// `T` at a time.
+    pub fn binding_size(&self) -> NonZeroU64 {
+        NonZeroU64::new(std::mem::size_of::<T>() as u64).expect("T must be non-zero-sized")
+    }
+
+    /// Builds the bind group for this storage as a whole, to be bound once
+    /// per frame (`set_bind_group(group, &bind_group, &[])`) and indexed
+    /// per draw via the dynamic offset each [`Self::push`] returned.
+    pub fn create_bind_group(&self, ctx: &Context, layout: &wgpu::BindGroupLayout) -> wgpu::BindGroup {
+        ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Uniform Storage Bind Group"),
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &self.buffer,
+                    offset: 0,
+                    size: Some(self.binding_size()),
+                }),
+            }],
+        })
+    }
+}
@@ -1,8 +1,9 @@
+use buddle_math::Vec3;
 use buddle_nif::enums::AlphaFunction;
 use buddle_nif::objects::NiAlphaProperty;
 use std::rc::Rc;
 
-use crate::gpu::{FLAT_TEXTURE, OIT_FLAT_TEXTURE};
+use crate::gpu::{FLAT_TEXTURE, OIT_FLAT_TEXTURE, OIT_PBR_METALLIC_ROUGHNESS, PBR_METALLIC_ROUGHNESS};
 use crate::{
     BindGroupLayoutEntry, Context, DepthSettings, Shader, SimplifiedPipelineConfig, Texture,
     TextureDimensions, MSAA,
@@ -72,10 +73,11 @@ impl FlatMaterial {
         blend: Option<wgpu::BlendState>,
         mut transparent: bool,
         mut opaque: bool,
+        msaa: MSAA,
     ) -> Self {
         let config = SimplifiedPipelineConfig {
             wireframe: false,
-            msaa: MSAA::Off,
+            msaa,
             targets: vec![wgpu::ColorTargetState {
                 format: ctx.surface.config.format,
                 blend,
@@ -85,11 +87,12 @@ impl FlatMaterial {
                 compare: wgpu::CompareFunction::Less,
                 write: true,
             }),
+            instanced: false,
         };
 
         let transparent_config = SimplifiedPipelineConfig {
             wireframe: false,
-            msaa: MSAA::Off,
+            msaa,
             targets: vec![
                 wgpu::ColorTargetState {
                     format: wgpu::TextureFormat::Rgba16Float,
@@ -124,26 +127,39 @@ impl FlatMaterial {
                 compare: wgpu::CompareFunction::Less,
                 write: false,
             }),
+            instanced: false,
         };
 
-        let buffer_bind_gl = ctx.create_bind_group_layout(vec![BindGroupLayoutEntry::Buffer]);
-        let texture_gl = ctx.create_bind_group_layout(vec![
+        let buffer_desc = [BindGroupLayoutEntry::Buffer { dynamic: false, min_binding_size: None }];
+        let model_desc = [BindGroupLayoutEntry::StorageBuffer { read_only: true }];
+        let texture_desc = [
             BindGroupLayoutEntry::Texture {
                 dim: TextureDimensions::D2,
                 filtering: true,
             },
             BindGroupLayoutEntry::Sampler { filtering: true },
-        ]);
+        ];
+
+        let buffer_bind_gl = ctx.create_bind_group_layout(buffer_desc.to_vec());
+        let model_gl = ctx.model_bind_group_layout();
+        let texture_gl = ctx.create_bind_group_layout(texture_desc.to_vec());
+
+        let layout_descs: [&[BindGroupLayoutEntry]; 3] =
+            [&buffer_desc, &model_desc, &texture_desc];
 
         let shader = ctx.create_shader(
             FLAT_TEXTURE,
-            vec![&buffer_bind_gl, &buffer_bind_gl, &texture_gl],
+            &[],
+            vec![&buffer_bind_gl, &model_gl, &texture_gl],
+            &layout_descs,
             config,
         );
 
         let transparent_shader = ctx.create_shader(
             OIT_FLAT_TEXTURE,
-            vec![&buffer_bind_gl, &buffer_bind_gl, &texture_gl],
+            &[],
+            vec![&buffer_bind_gl, &model_gl, &texture_gl],
+            &layout_descs,
             transparent_config,
         );
 
@@ -186,3 +202,264 @@ impl Material for FlatMaterial {
         self.opaque
     }
 }
+
+/// Scalar factors that scale a [`PbrMaterial`]'s textures, plus the single
+/// directional light it is lit by.
+///
+/// Mirrors glTF's metallic-roughness model: `base_color`/`metallic`/
+/// `roughness`/`emissive` multiply their respective texture samples (a
+/// value of `1.0` leaves the texture unmodified), while `normal_scale`
+/// and `occlusion_strength` attenuate the normal and (the metallic-
+/// roughness texture's red channel as) occlusion contributions.
+#[derive(Copy, Clone, Debug)]
+pub struct PbrFactors {
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub normal_scale: f32,
+    pub occlusion_strength: f32,
+    pub emissive: Vec3,
+    pub light_direction: Vec3,
+    pub light_color: Vec3,
+}
+
+impl Default for PbrFactors {
+    fn default() -> Self {
+        Self {
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            metallic: 1.0,
+            roughness: 1.0,
+            normal_scale: 1.0,
+            occlusion_strength: 1.0,
+            emissive: Vec3::ZERO,
+            light_direction: Vec3::new(-0.3, -1.0, -0.3).normalize(),
+            light_color: Vec3::ONE,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct PbrUniform {
+    base_color: [f32; 4],
+    // x = metallic, y = roughness, z = normal_scale, w = occlusion_strength.
+    metallic_roughness_normal_occlusion: [f32; 4],
+    emissive: [f32; 4],
+    light_direction: [f32; 4],
+    light_color: [f32; 4],
+}
+
+impl PbrUniform {
+    fn new(factors: PbrFactors) -> Self {
+        Self {
+            base_color: factors.base_color,
+            metallic_roughness_normal_occlusion: [
+                factors.metallic,
+                factors.roughness,
+                factors.normal_scale,
+                factors.occlusion_strength,
+            ],
+            emissive: [factors.emissive.x, factors.emissive.y, factors.emissive.z, 0.0],
+            light_direction: [
+                factors.light_direction.x,
+                factors.light_direction.y,
+                factors.light_direction.z,
+                0.0,
+            ],
+            light_color: [factors.light_color.x, factors.light_color.y, factors.light_color.z, 0.0],
+        }
+    }
+}
+
+/// A glTF-style metallic-roughness PBR material, lit by a single
+/// directional light via a Cook-Torrance BRDF.
+///
+/// Unlike [`FlatMaterial`], which only samples a diffuse texture, this
+/// combines a base color, a combined metallic-roughness-occlusion
+/// texture (glTF's `ORM` packing: R = occlusion, G = roughness,
+/// B = metallic), a tangent-space normal map and an emissive texture,
+/// alongside the scalar [`PbrFactors`] that scale them.
+pub struct PbrMaterial {
+    shader: Rc<Shader>,
+    transparent_shader: Rc<Shader>,
+    transparent: bool,
+    opaque: bool,
+    bind_group: wgpu::BindGroup,
+    factors_buffer: wgpu::Buffer,
+}
+
+impl PbrMaterial {
+    pub fn new(
+        ctx: &Context,
+        base_color: &Texture,
+        metallic_roughness: &Texture,
+        normal: &Texture,
+        emissive: &Texture,
+        factors: PbrFactors,
+        blend: Option<wgpu::BlendState>,
+        mut transparent: bool,
+        mut opaque: bool,
+        msaa: MSAA,
+    ) -> Self {
+        let config = SimplifiedPipelineConfig {
+            wireframe: false,
+            msaa,
+            targets: vec![wgpu::ColorTargetState {
+                format: ctx.surface.config.format,
+                blend,
+                write_mask: wgpu::ColorWrites::ALL,
+            }],
+            depth_settings: Some(DepthSettings {
+                compare: wgpu::CompareFunction::Less,
+                write: true,
+            }),
+            instanced: false,
+        };
+
+        let transparent_config = SimplifiedPipelineConfig {
+            wireframe: false,
+            msaa,
+            targets: vec![
+                wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba16Float,
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::One,
+                            dst_factor: wgpu::BlendFactor::One,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                        alpha: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::One,
+                            dst_factor: wgpu::BlendFactor::One,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                },
+                wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::R8Unorm,
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::Zero,
+                            dst_factor: wgpu::BlendFactor::OneMinusSrc,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                        alpha: wgpu::BlendComponent::REPLACE,
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                },
+            ],
+            depth_settings: Some(DepthSettings {
+                compare: wgpu::CompareFunction::Less,
+                write: false,
+            }),
+            instanced: false,
+        };
+
+        let buffer_desc = [BindGroupLayoutEntry::Buffer { dynamic: false, min_binding_size: None }];
+        let texture_desc = [
+            BindGroupLayoutEntry::Buffer { dynamic: false, min_binding_size: None },
+            BindGroupLayoutEntry::Texture {
+                dim: TextureDimensions::D2,
+                filtering: true,
+            },
+            BindGroupLayoutEntry::Sampler { filtering: true },
+            BindGroupLayoutEntry::Texture {
+                dim: TextureDimensions::D2,
+                filtering: true,
+            },
+            BindGroupLayoutEntry::Sampler { filtering: true },
+            BindGroupLayoutEntry::Texture {
+                dim: TextureDimensions::D2,
+                filtering: true,
+            },
+            BindGroupLayoutEntry::Sampler { filtering: true },
+            BindGroupLayoutEntry::Texture {
+                dim: TextureDimensions::D2,
+                filtering: true,
+            },
+            BindGroupLayoutEntry::Sampler { filtering: true },
+        ];
+
+        let model_desc = [BindGroupLayoutEntry::StorageBuffer { read_only: true }];
+        let buffer_bind_gl = ctx.create_bind_group_layout(buffer_desc.to_vec());
+        let model_gl = ctx.model_bind_group_layout();
+        let material_gl = ctx.create_bind_group_layout(texture_desc.to_vec());
+
+        let layout_descs: [&[BindGroupLayoutEntry]; 3] =
+            [&buffer_desc, &model_desc, &texture_desc];
+
+        let shader = ctx.create_shader(
+            PBR_METALLIC_ROUGHNESS,
+            &[],
+            vec![&buffer_bind_gl, &model_gl, &material_gl],
+            &layout_descs,
+            config,
+        );
+
+        let transparent_shader = ctx.create_shader(
+            OIT_PBR_METALLIC_ROUGHNESS,
+            &[],
+            vec![&buffer_bind_gl, &model_gl, &material_gl],
+            &layout_descs,
+            transparent_config,
+        );
+
+        let factors_buffer = ctx.create_buffer(
+            &[PbrUniform::new(factors)],
+            wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        );
+
+        let bind_group = ctx.create_bind_group(
+            &material_gl,
+            vec![
+                factors_buffer.as_entire_binding(),
+                wgpu::BindingResource::TextureView(&base_color.view),
+                wgpu::BindingResource::Sampler(&base_color.sampler),
+                wgpu::BindingResource::TextureView(&metallic_roughness.view),
+                wgpu::BindingResource::Sampler(&metallic_roughness.sampler),
+                wgpu::BindingResource::TextureView(&normal.view),
+                wgpu::BindingResource::Sampler(&normal.sampler),
+                wgpu::BindingResource::TextureView(&emissive.view),
+                wgpu::BindingResource::Sampler(&emissive.sampler),
+            ],
+        );
+
+        PbrMaterial {
+            shader,
+            transparent_shader,
+            bind_group,
+            factors_buffer,
+            transparent: transparent || blend.is_some(),
+            opaque,
+        }
+    }
+
+    /// Re-uploads `factors` to the GPU, e.g. after animating the emissive
+    /// color or toggling the light's direction.
+    pub fn update_factors(&self, ctx: &Context, factors: PbrFactors) {
+        ctx.update_buffer(&self.factors_buffer, &[PbrUniform::new(factors)]);
+    }
+}
+
+impl Material for PbrMaterial {
+    fn get_shader(&self) -> &Rc<Shader> {
+        &self.shader
+    }
+
+    fn get_transparent_shader(&self) -> &Rc<Shader> {
+        &self.transparent_shader
+    }
+
+    fn get_bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+
+    fn has_transparent_pixels(&self) -> bool {
+        self.transparent
+    }
+
+    fn has_opaque_pixels(&self) -> bool {
+        self.opaque
+    }
+}
@@ -38,7 +38,7 @@ fn generate_property(writer: &mut BufWriter<File>, property: &Property) -> anyho
         "#[property(name = \"{}\", flags({:?})",
         property.name, property.flags
     )?;
-    if let Some(info) = rust_ty.info {
+    if let Some((_, info)) = rust_ty.info.first() {
         write!(writer, ", info = {info}")?;
     }
     writeln!(writer, ")]")?;
@@ -79,56 +79,100 @@ fn cpp_type_to_rust_type_impl(name: &str) -> (Option<&'static str>, String) {
 }
 
 /// A translated C++ type.
-pub struct CppType<'a> {
-    /// Type info override, if necessary.
-    pub info: Option<&'a str>,
+pub struct CppType {
+    /// Type info overrides for leaf types that don't roundtrip through
+    /// Rust's type system losslessly (e.g. C++ `long`/`unsigned long`,
+    /// which both collapse to 32-bit Rust integers), keyed by the
+    /// depth-first position of the generic argument they were found at,
+    /// with position `0` being the outer type itself.
+    pub info: Vec<(usize, &'static str)>,
     /// The translated Rust type path.
     pub rust_ident: String,
 }
 
-/// Translates a given C++ type to its Rust equivalent.
-pub fn cpp_type_to_rust_type(name: &str, container: bool) -> CppType<'_> {
-    const DELIMITERS: &[char] = &['<', '>', ','];
+/// A node in the generic-argument tree of a parsed C++ type name, e.g.
+/// `Container<long, Foo<unsigned long>>`.
+struct TypeNode<'a> {
+    path: &'a str,
+    args: Vec<TypeNode<'a>>,
+}
 
-    let mut rust_type = CppType {
-        info: None,
-        rust_ident: String::new(),
-    };
+/// Parses the leading type out of `input`, recursing into its generic
+/// arguments if any, and returns it along with the unparsed remainder.
+fn parse_type_node(input: &str) -> (TypeNode<'_>, &str) {
+    let path_end = input.find(['<', '>', ',']).unwrap_or(input.len());
+    let path = input[..path_end].trim();
+    let mut rest = &input[path_end..];
+
+    let mut args = Vec::new();
+    if let Some(stripped) = rest.strip_prefix('<') {
+        rest = stripped;
+        loop {
+            let (arg, new_rest) = parse_type_node(rest);
+            args.push(arg);
+            rest = new_rest;
+
+            match rest.strip_prefix(',') {
+                Some(stripped) => rest = stripped,
+                None => break,
+            }
+        }
 
-    if container {
-        rust_type.rust_ident.push_str("Vec<");
+        rest = rest
+            .strip_prefix('>')
+            .expect("unbalanced `<>` in C++ type name");
     }
 
-    for s in name.split_inclusive(DELIMITERS) {
-        // If this string is only a single identifier without type parameters,
-        // we can take a shortcut here.
-        if !s.ends_with(DELIMITERS) {
-            let (info, ident) = cpp_type_to_rust_type_impl(s);
-            rust_type.info = info;
-            rust_type.rust_ident += &ident;
-
-            break;
-        }
+    (TypeNode { path, args }, rest)
+}
 
-        // Split the string into the current path to translate and its delimiter.
-        let (path, delim) = s.split_at(s.len() - 1);
-        let path = path.trim();
-
-        if !path.is_empty() {
-            let (info, ident) = cpp_type_to_rust_type_impl(path);
-            if info.is_some() {
-                // To ensure the info ends up being correct, we need to use
-                // the real name of the whole type and not just the override
-                // for the path we got here.
-                // TODO: This is broken. Fix later.
-                rust_type.info = Some(name);
+/// Translates a parsed node and its generic arguments into `out`,
+/// depth-first, recording any type info override under its position.
+///
+/// Returns the next free position after this node and everything nested
+/// inside it.
+fn translate_type_node(
+    node: &TypeNode<'_>,
+    position: usize,
+    info: &mut Vec<(usize, &'static str)>,
+    out: &mut String,
+) -> usize {
+    let (override_info, ident) = cpp_type_to_rust_type_impl(node.path);
+    if let Some(override_info) = override_info {
+        info.push((position, override_info));
+    }
+    out.push_str(&ident);
+
+    let mut next_position = position + 1;
+    if !node.args.is_empty() {
+        out.push('<');
+        for (i, arg) in node.args.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
             }
-            rust_type.rust_ident += &ident;
+            next_position = translate_type_node(arg, next_position, info, out);
         }
+        out.push('>');
+    }
+
+    next_position
+}
+
+/// Translates a given C++ type to its Rust equivalent.
+pub fn cpp_type_to_rust_type(name: &str, container: bool) -> CppType {
+    let mut rust_type = CppType {
+        info: Vec::new(),
+        rust_ident: String::new(),
+    };
 
-        rust_type.rust_ident.push_str(delim);
+    if container {
+        rust_type.rust_ident.push_str("Vec<");
     }
 
+    let (root, rest) = parse_type_node(name);
+    assert!(rest.is_empty(), "trailing data after parsed C++ type name");
+    translate_type_node(&root, 0, &mut rust_type.info, &mut rust_type.rust_ident);
+
     if container {
         rust_type.rust_ident.push('>');
     }
@@ -56,6 +56,96 @@ impl Color {
             a: interpolate_value(self.a, rhs.a, fraction),
         }
     }
+
+    /// Interpolates two colors in linear (gamma-decoded) light.
+    ///
+    /// Unlike [`Color::interpolate`], which blends the raw sRGB-encoded
+    /// `u8` channels directly, this decodes each RGB channel to linear
+    /// light before blending and re-encodes the result, avoiding the
+    /// muddy midtones a naive blend produces. The alpha channel stays
+    /// linear, since it isn't sRGB-encoded to begin with.
+    ///
+    /// `fraction` is a factor that denotes how much interpolation is
+    /// desired. `0.0` is the full `self` color while `1.0` is the full
+    /// `rhs`.
+    pub fn lerp_linear(&self, rhs: &Self, fraction: f32) -> Self {
+        let lerp_channel = |value: u8, other: u8| {
+            let value = srgb_to_linear(value);
+            let other = srgb_to_linear(other);
+            linear_to_srgb(value + fraction * (other - value))
+        };
+
+        Self {
+            r: lerp_channel(self.r, rhs.r),
+            g: lerp_channel(self.g, rhs.g),
+            b: lerp_channel(self.b, rhs.b),
+            a: (self.a as f32 + fraction * (rhs.a as f32 - self.a as f32)) as u8,
+        }
+    }
+
+    /// Gets the perceptual distance between two colors as ΔE76, the
+    /// Euclidean distance between their CIELAB representations.
+    ///
+    /// Unlike [`Color::distance_from`], which compares raw sRGB channels
+    /// and is cheap but perceptually inaccurate, this tracks how
+    /// different the colors actually look.
+    pub fn delta_e(&self, rhs: &Self) -> f32 {
+        let (l1, a1, b1) = self.to_lab();
+        let (l2, a2, b2) = rhs.to_lab();
+
+        ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+    }
+
+    /// Converts this color's RGB channels to CIELAB under the D65
+    /// illuminant, by way of linear-light sRGB and CIEXYZ.
+    fn to_lab(&self) -> (f32, f32, f32) {
+        let r = srgb_to_linear(self.r);
+        let g = srgb_to_linear(self.g);
+        let b = srgb_to_linear(self.b);
+
+        // Linear sRGB -> CIEXYZ, D65.
+        let x = 0.4124564 * r + 0.3575761 * g + 0.1804375 * b;
+        let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+        let z = 0.0193339 * r + 0.1191920 * g + 0.9503041 * b;
+
+        // CIEXYZ -> CIELAB, relative to the D65 white point.
+        const XN: f32 = 0.95047;
+        const YN: f32 = 1.00000;
+        const ZN: f32 = 1.08883;
+        const DELTA: f32 = 6.0 / 29.0;
+        let f = |t: f32| {
+            if t > DELTA.powi(3) {
+                t.cbrt()
+            } else {
+                t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+            }
+        };
+
+        let (fx, fy, fz) = (f(x / XN), f(y / YN), f(z / ZN));
+        (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+    }
+}
+
+/// Decodes an 8-bit sRGB-encoded channel value into linear light.
+fn srgb_to_linear(value: u8) -> f32 {
+    let value = value as f32 / 255.0;
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encodes a linear light channel value back into 8-bit sRGB.
+fn linear_to_srgb(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let encoded = if value > 0.0031308 {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    } else {
+        12.92 * value
+    };
+
+    (encoded * 255.0).round() as u8
 }
 
 impl Color {
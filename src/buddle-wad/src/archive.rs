@@ -1,10 +1,12 @@
 use std::{
     collections::BTreeMap,
     fs::{self, File},
-    io, mem,
+    io::{self, BufReader, Cursor, Read, Seek, SeekFrom},
+    mem,
     path::Path,
 };
 
+use flate2::read::ZlibDecoder;
 use memmap2::{Mmap, MmapOptions};
 
 use crate::types as wad_types;
@@ -31,6 +33,19 @@ impl Archive {
         HeapArchive::open(path, verify_crc).map(|a| Self(ArchiveInner::Heap(a)))
     }
 
+    /// Builds an archive directly from already-loaded `data`, without going
+    /// through the filesystem.
+    ///
+    /// This is for platforms without local file access, like a `wasm32`
+    /// build that fetches WAD files over HTTP: the bytes are already in
+    /// memory by the time this is called.
+    ///
+    /// `verify_crc` will optionally run validation of all encoded CRCs in
+    /// archive files when `true`.
+    pub fn from_bytes(data: Vec<u8>, verify_crc: bool) -> anyhow::Result<Self> {
+        HeapArchive::from_bytes(data, verify_crc).map(|a| Self(ArchiveInner::Heap(a)))
+    }
+
     /// Opens a file at the given `path` and operates on it
     /// from a memory mapping.
     ///
@@ -85,6 +100,169 @@ impl Archive {
             .find(name)
             .map(|f| (f.compressed, f.extract(self.raw_archive())))
     }
+
+    /// Gets the raw contents of an archived file by its string name,
+    /// verifying its CRC32 against the decompressed contents on access.
+    ///
+    /// Unlike the `verify_crc` flag on [`Archive::heap`]/[`Archive::mmap`],
+    /// which checks every file eagerly when the archive is opened, this
+    /// only hashes a file the first time it's looked up here, caching
+    /// the pass/fail result on the underlying journal entry so repeat
+    /// lookups are free. Useful when a caller only ever touches a handful
+    /// of entries out of a huge archive, while still catching file-
+    /// transfer corruption before it's acted upon.
+    ///
+    /// Returns [`None`] when no file named `name` exists in the archive.
+    pub fn file_checked(&self, name: &str) -> Option<anyhow::Result<&[u8]>> {
+        let file = self.journal().find(name)?;
+        Some(file.checked(self.raw_archive()))
+    }
+
+    /// Gets an iterator over the names of every file in the archive, in
+    /// sorted order.
+    ///
+    /// Use this to enumerate what's in the archive before deciding which
+    /// files to extract via [`Archive::file_raw`] or [`Interner::intern`],
+    /// without eagerly inflating anything up front.
+    ///
+    /// [`Interner::intern`]: crate::Interner::intern
+    pub fn file_names(&self) -> impl Iterator<Item = &str> {
+        self.journal().names()
+    }
+
+    /// Opens a streaming [`Read`] + [`Seek`] view over the file named
+    /// `name`.
+    ///
+    /// Stored files are handed back as a plain cursor over their raw
+    /// bytes. Compressed files are streamed through a buffered zlib
+    /// decoder instead of being decompressed into memory up front, so
+    /// callers can `read_exact` a handful of bytes out of a large entry
+    /// cheaply without materializing the whole decompressed file - this
+    /// pairs naturally with the mmap backend, which never copies the full
+    /// archive onto the heap either.
+    ///
+    /// Returns [`None`] when no file named `name` exists in the archive.
+    pub fn open_file(&self, name: &str) -> Option<impl Read + Seek + '_> {
+        let file = self.journal().find(name)?;
+        let data = file.extract(self.raw_archive());
+
+        Some(if file.compressed {
+            FileReader::Compressed(CompressedFileReader::new(
+                data,
+                file.uncompressed_size as u64,
+            ))
+        } else {
+            FileReader::Stored(Cursor::new(data))
+        })
+    }
+}
+
+/// A uniform [`Read`] + [`Seek`] view over a single archived file's
+/// contents, returned by [`Archive::open_file`].
+enum FileReader<'a> {
+    Stored(Cursor<&'a [u8]>),
+    Compressed(CompressedFileReader<'a>),
+}
+
+impl<'a> Read for FileReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Stored(cursor) => cursor.read(buf),
+            Self::Compressed(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl<'a> Seek for FileReader<'a> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Self::Stored(cursor) => cursor.seek(pos),
+            Self::Compressed(reader) => reader.seek(pos),
+        }
+    }
+}
+
+/// Streams a compressed file through a buffered [`ZlibDecoder`], re-creating
+/// the decoder from the original compressed bytes whenever a backward seek
+/// is requested, since zlib streams can't be rewound in place.
+struct CompressedFileReader<'a> {
+    compressed: &'a [u8],
+    uncompressed_size: u64,
+    reader: BufReader<ZlibDecoder<Cursor<&'a [u8]>>>,
+    pos: u64,
+}
+
+impl<'a> CompressedFileReader<'a> {
+    fn new(compressed: &'a [u8], uncompressed_size: u64) -> Self {
+        Self {
+            compressed,
+            uncompressed_size,
+            reader: BufReader::new(ZlibDecoder::new(Cursor::new(compressed))),
+            pos: 0,
+        }
+    }
+
+    fn rewind(&mut self) {
+        self.reader = BufReader::new(ZlibDecoder::new(Cursor::new(self.compressed)));
+        self.pos = 0;
+    }
+
+    /// Reads and discards up to `n` bytes, stopping early if the stream
+    /// ends first.
+    fn discard(&mut self, mut n: u64) -> io::Result<()> {
+        let mut scratch = [0u8; 4096];
+        while n > 0 {
+            let chunk = n.min(scratch.len() as u64) as usize;
+            let read = self.reader.read(&mut scratch[..chunk])?;
+            if read == 0 {
+                break;
+            }
+
+            n -= read as u64;
+            self.pos += read as u64;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Read for CompressedFileReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<'a> Seek for CompressedFileReader<'a> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => n,
+            SeekFrom::Current(delta) => checked_offset(self.pos, delta)?,
+            SeekFrom::End(delta) => checked_offset(self.uncompressed_size, delta)?,
+        };
+
+        if target < self.pos {
+            self.rewind();
+        }
+        self.discard(target - self.pos)?;
+
+        Ok(self.pos)
+    }
+}
+
+/// Computes `base as i128 + delta`, failing instead of wrapping when the
+/// result doesn't fit in a [`u64`].
+fn checked_offset(base: u64, delta: i64) -> io::Result<u64> {
+    let target = base as i128 + delta as i128;
+    if target < 0 || target > u64::MAX as i128 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "seek position out of range",
+        ));
+    }
+
+    Ok(target as u64)
 }
 
 impl AsRef<Archive> for Archive {
@@ -111,6 +289,10 @@ impl Journal {
     pub fn find(&self, file: &str) -> Option<&wad_types::File> {
         self.inner.get(file)
     }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.inner.keys().map(String::as_str)
+    }
 }
 
 struct MemoryMappedArchive {
@@ -170,12 +352,15 @@ struct HeapArchive {
 
 impl HeapArchive {
     fn open<P: AsRef<Path>>(path: P, verify_crc: bool) -> anyhow::Result<Self> {
-        // Attempt to read the given file into a byte vector.
+        Self::from_bytes(fs::read(path)?, verify_crc)
+    }
+
+    fn from_bytes(data: Vec<u8>, verify_crc: bool) -> anyhow::Result<Self> {
         let mut this = Self {
             journal: Journal {
                 inner: BTreeMap::new(),
             },
-            data: fs::read(path)?.into_boxed_slice(),
+            data: data.into_boxed_slice(),
         };
 
         // Parse the archive and build the file journal.
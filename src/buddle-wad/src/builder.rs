@@ -0,0 +1,120 @@
+//! Building KIWAD archives from scratch.
+
+use flate2::{write::ZlibEncoder, Compression};
+use std::io::Write;
+
+use crate::crc;
+
+struct PendingFile {
+    name: String,
+    uncompressed: Vec<u8>,
+    compressed: Option<Vec<u8>>,
+}
+
+/// A builder for producing a valid KIWAD archive byte stream.
+///
+/// Files are added via [`ArchiveBuilder::add_file`] and may optionally be
+/// zlib-compressed; sizes, the CRC32 and the final file table offsets are
+/// all computed automatically by [`ArchiveBuilder::build`].
+pub struct ArchiveBuilder {
+    version: u32,
+    flags: Option<u8>,
+    files: Vec<PendingFile>,
+}
+
+impl ArchiveBuilder {
+    /// Creates a new, empty builder for an archive of the given format
+    /// `version`.
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            flags: None,
+            files: Vec::new(),
+        }
+    }
+
+    /// Sets the configuration flags of the archive.
+    ///
+    /// These are only encoded when [`ArchiveBuilder`] was created with a
+    /// `version` of `2` or greater; see [`crate::types::Header::flags`].
+    pub fn flags(mut self, flags: u8) -> Self {
+        self.flags = Some(flags);
+        self
+    }
+
+    /// Adds a file named `name` with the given `contents` to the archive.
+    ///
+    /// When `compress` is `true`, the contents are stored zlib-compressed.
+    pub fn add_file(&mut self, name: impl Into<String>, contents: Vec<u8>, compress: bool) -> &mut Self {
+        let compressed = compress.then(|| {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder
+                .write_all(&contents)
+                .expect("writing to an in-memory buffer cannot fail");
+            encoder.finish().expect("in-memory zlib stream cannot fail")
+        });
+
+        self.files.push(PendingFile {
+            name: name.into(),
+            uncompressed: contents,
+            compressed,
+        });
+
+        self
+    }
+
+    /// Serializes the archive into a valid KIWAD byte stream.
+    pub fn build(&self) -> Vec<u8> {
+        const MAGIC: &[u8; 5] = b"KIWAD";
+
+        // The file table entry is fixed-size, save for the trailing,
+        // null-terminated name.
+        let table_size: usize = self
+            .files
+            .iter()
+            .map(|f| 4 + 4 + 4 + 1 + 4 + 4 + f.name.len() + 1)
+            .sum();
+
+        let header_size = crate::types::HEADER_LEN
+            + MAGIC.len()
+            + 4
+            + 4
+            + if self.version >= 2 { 1 } else { 0 };
+        let mut offset = (header_size + table_size) as u32;
+
+        let mut out = Vec::new();
+        crate::types::write_header(&mut out);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&self.version.to_le_bytes());
+        out.extend_from_slice(&(self.files.len() as u32).to_le_bytes());
+        if self.version >= 2 {
+            out.push(self.flags.unwrap_or(0));
+        }
+
+        for file in &self.files {
+            let uncompressed_size = file.uncompressed.len() as u32;
+            let compressed_size = file
+                .compressed
+                .as_ref()
+                .map_or(uncompressed_size, |c| c.len() as u32);
+            let crc = crc::hash(&file.uncompressed);
+
+            out.extend_from_slice(&offset.to_le_bytes());
+            out.extend_from_slice(&uncompressed_size.to_le_bytes());
+            out.extend_from_slice(&compressed_size.to_le_bytes());
+            out.push(file.compressed.is_some() as u8);
+            out.extend_from_slice(&crc.to_le_bytes());
+            out.extend_from_slice(&((file.name.len() + 1) as u32).to_le_bytes());
+            out.extend_from_slice(file.name.as_bytes());
+            out.push(0);
+
+            offset += compressed_size;
+        }
+
+        for file in &self.files {
+            out.extend_from_slice(file.compressed.as_deref().unwrap_or(&file.uncompressed));
+        }
+
+        out
+    }
+}
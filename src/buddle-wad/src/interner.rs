@@ -1,4 +1,7 @@
+use std::borrow::Cow;
+
 use anyhow::{anyhow, bail};
+use cipher::StreamCipher;
 use flate2::{Decompress, FlushDecompress, Status};
 
 use crate::archive::Archive;
@@ -38,10 +41,40 @@ impl<A: AsRef<Archive>> Interner<A> {
                 buf: Vec::new(),
                 ends: Vec::new(),
                 inflater: Decompress::new(true),
+                max_decompressed_size: None,
+                cipher: None,
             },
         }
     }
 
+    /// Configures a keyed stream cipher used to decrypt every file's raw
+    /// bytes before the compressed/uncompressed branch is applied.
+    ///
+    /// Use this when the archive stores encrypted (and possibly also
+    /// compressed) file records, as opposed to the plain KIWAD format.
+    /// `cipher` advances its keystream once per interned file, in the
+    /// order [`Interner::intern`] is called, so it must be seeded
+    /// consistently with however the archive itself was encrypted.
+    ///
+    /// Has no effect on files already interned before this is called.
+    pub fn with_cipher(mut self, cipher: impl StreamCipher + 'static) -> Self {
+        self.inner.cipher = Some(Box::new(cipher));
+        self
+    }
+
+    /// Caps the uncompressed size a single interned file may declare for
+    /// itself to `max` bytes.
+    ///
+    /// A crafted archive may understate a file's compressed size while
+    /// claiming an enormous `uncompressed_size`, forcing an equally
+    /// enormous allocation before decompression is even attempted. When
+    /// set, [`Interner::intern`] fails for any file whose declared size
+    /// exceeds `max` instead of honoring it.
+    pub fn with_max_decompressed_size(mut self, max: usize) -> Self {
+        self.inner.max_decompressed_size = Some(max);
+        self
+    }
+
     /// Invalidates all currently interned files and their associated
     /// [`FileHandle`]s.
     ///
@@ -84,6 +117,9 @@ impl<A: AsRef<Archive>> Interner<A> {
     ///
     /// - decompressing the file falied, either due ot invalid data or
     ///   invalid encoded size expectations
+    ///
+    /// - the file's declared uncompressed size exceeds the limit set via
+    ///   [`Interner::with_max_decompressed_size`], if any
     pub fn intern(&mut self, file: &str) -> anyhow::Result<FileHandle> {
         self.inner.intern(self.archive.as_ref(), file)
     }
@@ -106,6 +142,15 @@ struct InnerInterner {
 
     // The zlib inflater state for data decompression.
     inflater: Decompress,
+
+    // An optional cap on the uncompressed size a single file may declare,
+    // checked before any memory is reserved for decompressing it.
+    max_decompressed_size: Option<usize>,
+
+    // An optional stream cipher applied to a file's raw bytes before the
+    // compressed/uncompressed branch, for archives whose file records are
+    // encrypted.
+    cipher: Option<Box<dyn StreamCipher>>,
 }
 
 impl InnerInterner {
@@ -124,25 +169,45 @@ impl InnerInterner {
         let size_hint = file.uncompressed_size as usize;
         self.ends.push(self.buf.len() + size_hint);
 
-        // Extract the file contents from the archive data.
+        // Extract the file contents from the archive data, decrypting
+        // them first if a cipher is configured, so the compressed/
+        // uncompressed branch below always sees plaintext.
         let data = file.extract(raw_archive);
+        let data = match &mut self.cipher {
+            Some(cipher) => {
+                let mut data = data.to_vec();
+                cipher.apply_keystream(&mut data);
+                Cow::Owned(data)
+            }
+            None => Cow::Borrowed(data),
+        };
+
         if file.compressed {
             // Decompress the data into our internal buffer.
-            self.decompress_to_buf(data, size_hint)?;
+            self.decompress_to_buf(&data, size_hint)?;
         } else {
             // The file is not compressed, so we just grow the buffer.
-            self.buf.extend_from_slice(data);
+            self.buf.extend_from_slice(&data);
         }
 
         Ok(handle)
     }
 
     fn decompress_to_buf(&mut self, data: &[u8], hint: usize) -> anyhow::Result<()> {
+        // Reject the file upfront if its declared size exceeds the
+        // configured cap, before reserving any memory for it.
+        if let Some(max) = self.max_decompressed_size {
+            if hint > max {
+                bail!("file's declared uncompressed size of {hint} bytes exceeds the configured limit of {max} bytes");
+            }
+        }
+
         // Reserve enough memory for decompressing the file.
         let start = self.buf.len();
         self.buf.resize(start + hint, 0);
 
         // Decompress the data into the internal buffer.
+        let before = self.inflater.total_out();
         if self
             .inflater
             .decompress(data, &mut self.buf[start..], FlushDecompress::Finish)?
@@ -150,6 +215,10 @@ impl InnerInterner {
         {
             bail!("received incomplete zlib stream or wrong size expectation");
         }
+        let decompressed = (self.inflater.total_out() - before) as usize;
+        if decompressed != hint {
+            bail!("decompressed size of {decompressed} bytes did not match the archive's declared size of {hint} bytes");
+        }
 
         // Reset decompress object for next usage.
         self.inflater.reset(true);
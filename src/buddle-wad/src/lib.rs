@@ -17,6 +17,9 @@
 mod archive;
 pub use archive::Archive;
 
+mod builder;
+pub use builder::ArchiveBuilder;
+
 pub mod crc;
 
 mod interner;
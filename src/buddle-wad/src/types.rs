@@ -1,6 +1,8 @@
 //! Common types and structures in the KIWAD format.
 
-use anyhow::bail;
+use std::{borrow::Cow, cell::Cell};
+
+use anyhow::{anyhow, bail};
 use binrw::{
     binread,
     io::{Read, Seek, SeekFrom},
@@ -9,6 +11,32 @@ use binrw::{
 
 use crate::crc;
 
+/// Defensive signature prepended to every archive, in the same spirit as
+/// PNG's own: a high-bit byte (`0x89`) that gets stripped by naive
+/// 7-bit-clean transports, a `CR LF` pair that ASCII/text-mode line-ending
+/// translation mangles, a DOS EOF byte, and a trailing `LF` that catches
+/// transports which translate between a bare `LF` and `CR LF`.
+const SIGNATURE: [u8; 8] = [0x89, b'W', b'A', b'D', b'\r', b'\n', 0x1a, b'\n'];
+
+/// The container version written directly after [`SIGNATURE`].
+///
+/// This is distinct from [`Header::version`], which describes the layout
+/// of the KIWAD header and file table rather than the defensive signature
+/// wrapped around it.
+pub(crate) const HEADER_VERSION: u8 = 1;
+
+/// Total length in bytes of [`SIGNATURE`] plus the [`HEADER_VERSION`] byte
+/// following it, i.e. everything [`write_header`] emits.
+pub(crate) const HEADER_LEN: usize = SIGNATURE.len() + 1;
+
+/// Writes [`SIGNATURE`] followed by the current [`HEADER_VERSION`] byte to
+/// `out`, so that archives produced this way are self-identifying to
+/// [`Archive::parse`].
+pub(crate) fn write_header(out: &mut Vec<u8>) {
+    out.extend_from_slice(&SIGNATURE);
+    out.push(HEADER_VERSION);
+}
+
 /// The header of a KIWAD archive.
 #[binread]
 pub struct Header {
@@ -46,6 +74,10 @@ pub struct File {
     /// The name of the file in the archive.
     #[br(args(name_len as usize), parse_with = parse_file_name)]
     pub name: String,
+    // Cached result of a lazy CRC check performed via `File::checked`,
+    // so repeated lookups of the same file don't re-hash its contents.
+    #[br(calc = Cell::new(None))]
+    crc_checked: Cell<Option<bool>>,
 }
 
 impl File {
@@ -71,6 +103,65 @@ impl File {
 
         &raw_archive[offset..offset + size]
     }
+
+    /// Extracts and decompresses this file from the given raw archive
+    /// bytes.
+    ///
+    /// When [`File::compressed`] is `false`, this is equivalent to
+    /// [`File::extract`] and borrows directly from `raw_archive`.
+    /// Otherwise, the `compressed_size` bytes are inflated (zlib/DEFLATE)
+    /// into an owned buffer.
+    ///
+    /// Fails when the inflated data does not match
+    /// [`File::uncompressed_size`].
+    ///
+    /// # Panics
+    ///
+    /// This may panic when `raw_archive` is indexed incorrectly with
+    /// offset and length of the described file bytes.
+    pub fn decompress<'wad>(&self, raw_archive: &'wad [u8]) -> anyhow::Result<Cow<'wad, [u8]>> {
+        let compressed = self.extract(raw_archive);
+        if !self.compressed {
+            return Ok(Cow::Borrowed(compressed));
+        }
+
+        let mut decoder = flate2::read::ZlibDecoder::new(compressed);
+        let mut out = Vec::with_capacity(self.uncompressed_size as usize);
+        std::io::Read::read_to_end(&mut decoder, &mut out)?;
+
+        if out.len() != self.uncompressed_size as usize {
+            bail!(
+                "decompressed {} bytes, expected {}",
+                out.len(),
+                self.uncompressed_size
+            );
+        }
+
+        Ok(Cow::Owned(out))
+    }
+
+    /// Extracts this file like [`File::extract`], but first verifies its
+    /// CRC32 against the decompressed contents, failing instead of
+    /// returning corrupted data.
+    ///
+    /// The result of the check is cached on this [`File`], so repeated
+    /// calls only hash the contents once.
+    pub fn checked<'wad>(&self, raw_archive: &'wad [u8]) -> anyhow::Result<&'wad [u8]> {
+        let ok = match self.crc_checked.get() {
+            Some(ok) => ok,
+            None => {
+                let ok = crc::hash(&self.decompress(raw_archive)?) == self.crc;
+                self.crc_checked.set(Some(ok));
+                ok
+            }
+        };
+
+        if ok {
+            Ok(self.extract(raw_archive))
+        } else {
+            bail!("CRC mismatch - expected {}", self.crc)
+        }
+    }
 }
 
 /// Representation of a KIWAD archive.
@@ -92,7 +183,28 @@ pub struct Archive {
 
 impl Archive {
     /// Parses the archive from the given [`Read`]er.
+    ///
+    /// This first validates the defensive [`SIGNATURE`] and
+    /// [`HEADER_VERSION`] byte written by [`write_header`], failing with a
+    /// distinct error depending on whether the signature doesn't match at
+    /// all versus whether it matches but names an unsupported version.
     pub fn parse<R: Read + Seek>(reader: &mut R) -> anyhow::Result<Self> {
+        let mut signature = [0u8; SIGNATURE.len()];
+        reader
+            .read_exact(&mut signature)
+            .map_err(|_| anyhow!("not a WAD archive"))?;
+        if signature != SIGNATURE {
+            bail!("not a WAD archive");
+        }
+
+        let mut version = [0u8; 1];
+        reader
+            .read_exact(&mut version)
+            .map_err(|_| anyhow!("not a WAD archive"))?;
+        if version[0] != HEADER_VERSION {
+            bail!("unsupported archive version {}", version[0]);
+        }
+
         reader.read_le().map_err(Into::into)
     }
 
@@ -100,7 +212,9 @@ impl Archive {
     /// raw bytes of the archive file.
     pub fn verify_crcs(&self, raw_archive: &[u8]) -> anyhow::Result<()> {
         self.files.iter().try_for_each(|f| {
-            let hash = crc::hash(f.extract(raw_archive));
+            // The CRC is documented as being computed over uncompressed
+            // contents, so compressed files must be inflated first.
+            let hash = crc::hash(&f.decompress(raw_archive)?);
             if hash == f.crc {
                 Ok(())
             } else {
@@ -92,6 +92,16 @@ fn invalidate() {
     assert_eq!(interner.fetch(text1_new).unwrap(), b"this is text1\n");
 }
 
+#[test]
+fn file_names() {
+    let archive = Archive::heap("tests/data/Test.wad", true).unwrap();
+
+    let names: Vec<_> = archive.file_names().collect();
+    assert_eq!(names.len(), archive.len());
+    assert!(names.contains(&"text1.txt"));
+    assert!(names.contains(&"subdir/subdir_text1.txt"));
+}
+
 #[test]
 fn arc_interner() {
     let archive = Archive::heap("tests/data/Test.wad", true)
@@ -0,0 +1,45 @@
+use buddle_wad::types::Archive as RawArchive;
+use buddle_wad::ArchiveBuilder;
+use std::io::Cursor;
+
+#[test]
+fn round_trip_uncompressed() {
+    let mut builder = ArchiveBuilder::new(1);
+    builder.add_file("text1.txt", b"this is text1\n".to_vec(), false);
+
+    let bytes = builder.build();
+    let archive = RawArchive::parse(&mut Cursor::new(&bytes)).unwrap();
+    archive.verify_crcs(&bytes).unwrap();
+
+    assert_eq!(archive.files.len(), 1);
+    assert_eq!(archive.files[0].name, "text1.txt");
+    assert!(!archive.files[0].compressed);
+    assert_eq!(archive.files[0].extract(&bytes), b"this is text1\n");
+}
+
+#[test]
+fn round_trip_compressed() {
+    let mut builder = ArchiveBuilder::new(2).flags(0);
+    builder.add_file("compressed.txt", b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_vec(), true);
+
+    let bytes = builder.build();
+    let archive = RawArchive::parse(&mut Cursor::new(&bytes)).unwrap();
+    archive.verify_crcs(&bytes).unwrap();
+
+    assert!(archive.files[0].compressed);
+    assert!(archive.files[0].compressed_size < archive.files[0].uncompressed_size);
+}
+
+#[test]
+fn round_trip_multiple_files() {
+    let mut builder = ArchiveBuilder::new(1);
+    builder
+        .add_file("a.txt", b"hello".to_vec(), false)
+        .add_file("subdir/b.txt", b"world".to_vec(), false);
+
+    let bytes = builder.build();
+    let archive = RawArchive::parse(&mut Cursor::new(&bytes)).unwrap();
+
+    assert_eq!(archive.files[0].extract(&bytes), b"hello");
+    assert_eq!(archive.files[1].extract(&bytes), b"world");
+}
@@ -1,14 +1,18 @@
 //! Convert NIF objects to buddle-render ones
 
-use std::io;
+use std::collections::HashMap;
+
 use buddle_math::{UVec2, Vec2, Vec3, Vec4, Vec4Swizzles};
 use buddle_nif::enums::{AlphaFunction, PixelFormat};
 use buddle_nif::objects::{NiAlphaProperty, NiObject, NiPixelData};
 use buddle_nif::Nif;
-use buddle_render::{Context, FlatMaterial, Material, Model, Texture, Transform, Vertex};
+use buddle_render::{
+    AtlasBuilder, AtlasRect, Context, FlatMaterial, Material, MipFiltering, Model, Texture,
+    Transform, Vertex, MSAA,
+};
 
 use bcndecode::{BcnDecoderFormat, BcnEncoding};
-use buddle_wad::{Archive, Interner};
+use buddle_wad::{Archive, FileHandle, Interner};
 
 pub trait ToModel {
     type Error;
@@ -22,6 +26,53 @@ pub trait ToTexture {
     fn to_texture(self, ctx: &Context) -> Result<(Texture, bool, bool), Self::Error>;
 }
 
+/// Caches GPU textures decoded from interned WAD files, so multiple
+/// `NiSourceTexture` references to the same filename share one upload.
+#[derive(Default)]
+struct TextureCache {
+    // `Interner::intern` issues a fresh handle on every call, so each
+    // filename's first handle is remembered and reused as the cache key.
+    handles: HashMap<String, FileHandle>,
+    entries: HashMap<FileHandle, (Texture, bool, bool)>,
+}
+
+impl TextureCache {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns `file_name` through `intern` and decodes and uploads it as a
+    /// GPU texture the first time it's seen, returning the cached upload
+    /// on every later reference to the same file.
+    fn resolve(
+        &mut self,
+        ctx: &Context,
+        intern: &mut Interner<&Archive>,
+        file_name: &str,
+    ) -> anyhow::Result<(Texture, bool, bool)> {
+        let handle = match self.handles.get(file_name) {
+            Some(handle) => *handle,
+            None => {
+                let handle = intern.intern(file_name)?;
+                self.handles.insert(file_name.to_string(), handle);
+                handle
+            }
+        };
+
+        if let Some(entry) = self.entries.get(&handle) {
+            return Ok(entry.clone());
+        }
+
+        let data = intern
+            .fetch(handle)
+            .ok_or_else(|| anyhow::anyhow!("'{file_name}' was invalidated before it decoded"))?;
+        let entry = Texture::from_encoded_bytes(ctx, data)?;
+
+        self.entries.insert(handle, entry.clone());
+        Ok(entry)
+    }
+}
+
 // Todo: Speedups
 fn get_child_meshes_with_transforms<'a>(
     nif: &'a Nif,
@@ -37,13 +88,13 @@ fn get_child_meshes_with_transforms<'a>(
     let mut res = Vec::new();
 
     for child in children {
-        if let Some(child_obj) = child.get(&nif.blocks) {
+        if let Some(child_obj) = child.raw(&nif.blocks) {
             if let NiObject::NiMesh(mesh) = child_obj {
                 res.push((child_obj, transform * Transform::from_nif(&mesh.base.base)));
             } else {
                 res.append(&mut get_child_meshes_with_transforms(
                     nif,
-                    child.get(&nif.blocks).unwrap(),
+                    child.raw(&nif.blocks).unwrap(),
                     transform,
                 ))
             }
@@ -106,6 +157,119 @@ fn blend_state_from_alpha_property(alpha: &NiAlphaProperty) -> Option<wgpu::Blen
     Some(res)
 }
 
+/// Computes smooth per-vertex normals for a triangle mesh: each
+/// triangle's face normal is accumulated into its three vertices, then
+/// every vertex normal is normalized. Triangles with a near-zero-area
+/// face normal (degenerate positions) are skipped so they don't pollute
+/// their vertices' accumulators with a meaningless direction.
+fn compute_smooth_normals(vertices: &mut [Vertex], indices: &[u16]) {
+    let mut accum = vec![Vec3::ZERO; vertices.len()];
+
+    for tri in indices.chunks_exact(3) {
+        let (i0, i1, i2) = (tri[0] as usize, tri[1] as usize, tri[2] as usize);
+        let p0 = Vec3::from(vertices[i0].position);
+        let p1 = Vec3::from(vertices[i1].position);
+        let p2 = Vec3::from(vertices[i2].position);
+
+        let face_normal = (p1 - p0).cross(p2 - p0);
+        if face_normal.length_squared() < 1e-12 {
+            continue;
+        }
+
+        accum[i0] += face_normal;
+        accum[i1] += face_normal;
+        accum[i2] += face_normal;
+    }
+
+    for (vertex, normal) in vertices.iter_mut().zip(accum) {
+        if normal.length_squared() > 1e-12 {
+            vertex.normal = normal.normalize().into();
+        }
+    }
+}
+
+/// Computes per-vertex tangents with the standard texcoord-based method:
+/// each triangle's tangent is derived from its position/UV deltas,
+/// accumulated into its three vertices, then Gram-Schmidt orthogonalized
+/// against the vertex normal (which must already be final by the time
+/// this runs). Triangles whose UVs have zero area contribute nothing,
+/// since the method's `1 / (du1*dv2 - du2*dv1)` term is undefined there.
+fn compute_tangents(vertices: &mut [Vertex], indices: &[u16]) {
+    let mut accum = vec![Vec3::ZERO; vertices.len()];
+
+    for tri in indices.chunks_exact(3) {
+        let (i0, i1, i2) = (tri[0] as usize, tri[1] as usize, tri[2] as usize);
+        let p0 = Vec3::from(vertices[i0].position);
+        let p1 = Vec3::from(vertices[i1].position);
+        let p2 = Vec3::from(vertices[i2].position);
+        let uv0 = Vec2::from(vertices[i0].tex_coords);
+        let uv1 = Vec2::from(vertices[i1].tex_coords);
+        let uv2 = Vec2::from(vertices[i2].tex_coords);
+
+        let edge1 = p1 - p0;
+        let edge2 = p2 - p0;
+        let (du1, dv1) = (uv1.x - uv0.x, uv1.y - uv0.y);
+        let (du2, dv2) = (uv2.x - uv0.x, uv2.y - uv0.y);
+
+        let denom = du1 * dv2 - du2 * dv1;
+        if denom.abs() < 1e-12 {
+            continue;
+        }
+
+        let r = 1.0 / denom;
+        let tangent = (edge1 * dv2 - edge2 * dv1) * r;
+
+        accum[i0] += tangent;
+        accum[i1] += tangent;
+        accum[i2] += tangent;
+    }
+
+    for (vertex, tangent) in vertices.iter_mut().zip(accum) {
+        let normal = Vec3::from(vertex.normal);
+        let orthogonal = tangent - normal * normal.dot(tangent);
+
+        vertex.tangent = if orthogonal.length_squared() > 1e-12 {
+            orthogonal.normalize().into()
+        } else {
+            [0.0, 0.0, 0.0]
+        };
+    }
+}
+
+/// The largest single embedded texture edge (in texels) eligible for
+/// atlas packing; larger ones are uploaded standalone instead, since
+/// packing them would dominate the atlas and defeat the point.
+const ATLAS_MAX_TILE: u32 = 512;
+
+/// Where a mesh's base texture ended up, decided after every mesh in the
+/// NIF has been walked so atlas packing sees every candidate at once.
+enum PendingTexture {
+    /// Packed into the shared atlas; `key` indexes into the
+    /// [`AtlasBuilder`] and the resulting rect map.
+    Atlas {
+        key: usize,
+        transparent: bool,
+        opaque: bool,
+    },
+    /// Uploaded as its own [`Texture`], either because it came from an
+    /// external file, didn't fit the atlas tile budget, or needs its own
+    /// blend state.
+    Standalone {
+        texture: Texture,
+        transparent: bool,
+        opaque: bool,
+    },
+}
+
+/// A mesh's CPU-side data plus its texture resolution, deferred until the
+/// atlas (if any) has been packed.
+struct PendingMesh {
+    vertices: Vec<Vertex>,
+    indices: Vec<u16>,
+    blend: Option<wgpu::BlendState>,
+    texture: PendingTexture,
+}
+
 impl ToModel for (Nif, &mut Interner<&Archive>) {
     type Error = ();
 
@@ -115,6 +279,10 @@ impl ToModel for (Nif, &mut Interner<&Archive>) {
         let mut meshes = Vec::new();
         let mut materials = Vec::new();
         let mut textures = Vec::new();
+        let mut texture_cache = TextureCache::new();
+
+        let mut atlas_builder = AtlasBuilder::new(UVec2::new(1024, 1024));
+        let mut pending_meshes = Vec::new();
 
         let ni_meshes = get_meshes_with_transforms(&nif);
 
@@ -133,12 +301,7 @@ impl ToModel for (Nif, &mut Interner<&Archive>) {
             let mut color_regions = Vec::new();
 
             for ds_ref in &ni_mesh.datastreams {
-                let datastream = {
-                    match nif.blocks.get(ds_ref.stream.0 as usize).ok_or(())? {
-                        NiObject::NiDataStream(datastream) => datastream,
-                        _ => return Err(()),
-                    }
-                };
+                let datastream = ds_ref.stream.get(&nif.blocks).map_err(|_| ())?;
 
                 let semantic_data = ds_ref.component_semantics.get(0).ok_or(())?;
                 let kind = nif
@@ -183,15 +346,17 @@ impl ToModel for (Nif, &mut Interner<&Archive>) {
                 }
             }
 
-            if vertex_regions.len() > normal_regions.len() {
+            let normals_missing = vertex_regions.len() > normal_regions.len();
+
+            if normals_missing {
                 let start = normal_regions.len();
 
                 for vertex_region in vertex_regions.iter().skip(start) {
                     let mut normals = Vec::new();
 
                     for _ in vertex_region {
-                        // How much harm could that possibly do, we're not even shading yet
-                        // Todo: actually calculate the normals
+                        // Overwritten with real smooth normals below once
+                        // `vertices`/`indices` for this mesh are complete.
                         normals.push(Vec3::new(0.0, 0.0, 0.0));
                     }
 
@@ -246,7 +411,13 @@ impl ToModel for (Nif, &mut Interner<&Archive>) {
                 base_index += count as u16;
             }
 
-            let mut texture = Err(());
+            if normals_missing {
+                compute_smooth_normals(&mut vertices, &indices);
+            }
+            compute_tangents(&mut vertices, &indices);
+
+            let mut external_texture = Err(());
+            let mut embedded_rgba = Err(());
             let mut alpha = None;
 
             for property in properties {
@@ -254,7 +425,7 @@ impl ToModel for (Nif, &mut Interner<&Archive>) {
                     alpha = Some(alpha_prop);
                 }
 
-                if texture.is_err() {
+                if external_texture.is_err() && embedded_rgba.is_err() {
                     let texturing = match property {
                         NiObject::NiTexturingProperty(prop) => prop,
                         NiObject::NiMultiTextureProperty(multi_prop) => &multi_prop.base,
@@ -263,32 +434,25 @@ impl ToModel for (Nif, &mut Interner<&Archive>) {
 
                     let base_texture = texturing.base_texture.as_ref().ok_or(())?;
 
-                    let NiObject::NiSourceTexture(source) = base_texture.source.get_or(&nif.blocks, ())? else {
+                    let Ok(source) = base_texture.source.get_or(&nif.blocks, ()) else {
                         continue;
                     };
 
-                    let pixel_data = if source.use_external == 1 {
+                    if source.use_external == 1 {
                         let file_name = "Textures/".to_string()
                             + &nif.header.strings[source.file_name.index.0 as usize]
-                            .data
-                            .clone();
-                        let handle = intern.intern(&file_name).map_err(|_| ())?;
-                        let data = intern.fetch_mut(handle).unwrap();
-                        let mut cursor = io::Cursor::new(data);
-                        let nif = Nif::parse(&mut cursor).map_err(|_| ())?;
-
-                        match &nif.root_objects()[0] {
-                            NiObject::NiPixelData(pd) => pd.clone(),
-                            _ => continue,
-                        }
+                                .data
+                                .clone();
+
+                        external_texture =
+                            texture_cache.resolve(ctx, intern, &file_name).map_err(|_| ());
                     } else {
-                        match source.pixel_data.get_or(&nif.blocks, ())? {
-                            NiObject::NiPixelData(pd) => pd.clone(),
-                            _ => continue,
-                        }
-                    };
+                        let Ok(pixel_data) = source.pixel_data.get_or(&nif.blocks, ()) else {
+                            continue;
+                        };
 
-                    texture = pixel_data.to_texture(ctx);
+                        embedded_rgba = pixel_data.decode_rgba();
+                    };
                 }
             }
 
@@ -298,70 +462,393 @@ impl ToModel for (Nif, &mut Interner<&Archive>) {
                 None
             };
 
+            let mesh_index = pending_meshes.len();
+
             // fixme: there exist models without textures that are duplicates of and at the same
             //  position as other models. why?
-            let texture = texture.unwrap_or_else(|_| (Texture::missing(ctx), false, true));
-            let material: Box<dyn Material> = Box::new(FlatMaterial::new(ctx, &texture.0, blend, texture.1, texture.2));
+            let texture = if let Ok((rgba, size, transparent, opaque)) = embedded_rgba {
+                if blend.is_none() && size.x <= ATLAS_MAX_TILE && size.y <= ATLAS_MAX_TILE {
+                    atlas_builder.add(mesh_index, size, rgba);
+                    PendingTexture::Atlas {
+                        key: mesh_index,
+                        transparent,
+                        opaque,
+                    }
+                } else {
+                    PendingTexture::Standalone {
+                        texture: ctx.create_texture(&rgba, size),
+                        transparent,
+                        opaque,
+                    }
+                }
+            } else if let Ok((texture, transparent, opaque)) = external_texture {
+                PendingTexture::Standalone {
+                    texture,
+                    transparent,
+                    opaque,
+                }
+            } else {
+                PendingTexture::Standalone {
+                    texture: Texture::missing(ctx),
+                    transparent: false,
+                    opaque: true,
+                }
+            };
 
-            let mesh = ctx.create_mesh(vertices, indices);
+            pending_meshes.push(PendingMesh {
+                vertices,
+                indices,
+                blend,
+                texture,
+            });
+        }
 
-            meshes.push(mesh);
+        let atlas: Option<(Texture, HashMap<usize, AtlasRect>)> = if pending_meshes
+            .iter()
+            .any(|m| matches!(m.texture, PendingTexture::Atlas { .. }))
+        {
+            let (texture, rects) = atlas_builder.build(ctx);
+            textures.push(texture.clone());
+            Some((texture, rects))
+        } else {
+            None
+        };
+
+        for pending in pending_meshes {
+            let PendingMesh {
+                mut vertices,
+                indices,
+                blend,
+                texture,
+            } = pending;
+
+            let material: Box<dyn Material> = match texture {
+                PendingTexture::Atlas {
+                    key,
+                    transparent,
+                    opaque,
+                } => {
+                    let (atlas_texture, rects) = atlas.as_ref().expect("atlas was queued but never built");
+                    let rect = rects.get(&key).expect("atlas mesh missing its packed rect");
+
+                    for vertex in &mut vertices {
+                        let uv = Vec2::from(vertex.tex_coords);
+                        vertex.tex_coords = (rect.offset + uv * rect.scale).into();
+                    }
+
+                    Box::new(FlatMaterial::new(ctx, atlas_texture, None, transparent, opaque, MSAA::Off))
+                }
+                PendingTexture::Standalone {
+                    texture,
+                    transparent,
+                    opaque,
+                } => {
+                    let material = Box::new(FlatMaterial::new(ctx, &texture, blend, transparent, opaque, MSAA::Off));
+                    textures.push(texture);
+                    material
+                }
+            };
+
+            meshes.push(ctx.create_mesh(vertices, indices));
             materials.push(material);
-            textures.push(texture.0);
         }
 
         Ok(Model::new(meshes, materials, textures))
     }
 }
 
-impl ToTexture for NiPixelData {
-    type Error = ();
+/// A parsed glTF/GLB asset, ready to be turned into a [`Model`].
+///
+/// Holds the document alongside its resolved buffer and image data, as
+/// returned by [`gltf::import`].
+pub struct GltfAsset {
+    document: gltf::Document,
+    buffers: Vec<gltf::buffer::Data>,
+    images: Vec<gltf::image::Data>,
+}
 
-    fn to_texture(self, ctx: &Context) -> Result<(Texture, bool, bool), Self::Error> {
-        let mm = self.mipmaps.get(0).ok_or(())?;
-        let size = UVec2::new(mm.width, mm.height);
+impl GltfAsset {
+    /// Imports a glTF/GLB file from `path`, resolving all external buffers
+    /// and images relative to it.
+    pub fn import(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let (document, buffers, images) = gltf::import(path)?;
+        Ok(Self {
+            document,
+            buffers,
+            images,
+        })
+    }
+}
 
-        let pixels;
+impl ToModel for GltfAsset {
+    type Error = anyhow::Error;
 
-        if self.base.pixel_format == PixelFormat::PX_FMT_DXT1
-            || self.base.pixel_format == PixelFormat::PX_FMT_DXT3
-            || self.base.pixel_format == PixelFormat::PX_FMT_DXT5
-        {
-            pixels = bcndecode::decode(
-                &self.pixel_data,
-                mm.width as usize,
-                mm.height as usize,
-                match self.base.pixel_format {
-                    PixelFormat::PX_FMT_DXT1 => BcnEncoding::Bc1,
-                    PixelFormat::PX_FMT_DXT3 => BcnEncoding::Bc2,
-                    PixelFormat::PX_FMT_DXT5 => BcnEncoding::Bc3,
-                    _ => unreachable!(),
-                },
-                BcnDecoderFormat::RGBA,
-            )
-            .map_err(|_| ())?;
+    fn to_model(self, ctx: &Context) -> Result<Model, Self::Error> {
+        let GltfAsset {
+            document,
+            buffers,
+            images,
+        } = self;
+
+        let mut meshes = Vec::new();
+        let mut materials: Vec<Box<dyn Material>> = Vec::new();
+        let mut textures = Vec::new();
+
+        for mesh in document.meshes() {
+            for primitive in mesh.primitives() {
+                if primitive.mode() != gltf::mesh::Mode::Triangles {
+                    continue;
+                }
+
+                let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+                let positions: Vec<[f32; 3]> = reader
+                    .read_positions()
+                    .ok_or_else(|| anyhow::anyhow!("primitive is missing POSITION attribute"))?
+                    .collect();
+
+                let normals: Vec<[f32; 3]> = reader
+                    .read_normals()
+                    .map(|it| it.collect())
+                    .unwrap_or_else(|| vec![[0.0, 0.0, 0.0]; positions.len()]);
+
+                let tex_coords: Vec<[f32; 2]> = reader
+                    .read_tex_coords(0)
+                    .map(|it| it.into_f32().collect())
+                    .unwrap_or_else(|| vec![[0.0, 0.0]; positions.len()]);
+
+                let indices: Vec<u16> = reader
+                    .read_indices()
+                    .ok_or_else(|| anyhow::anyhow!("primitive is missing indices"))?
+                    .into_u32()
+                    .map(|i| i as u16)
+                    .collect();
+
+                let vertices = positions
+                    .iter()
+                    .zip(&normals)
+                    .zip(&tex_coords)
+                    .map(|((position, normal), tex_coords)| {
+                        Vertex::new(
+                            Vec3::from(*position),
+                            Vec3::new(1.0, 1.0, 1.0),
+                            Vec3::from(*normal),
+                            Vec2::from(*tex_coords),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                let gltf_material = primitive.material();
+                let pbr = gltf_material.pbr_metallic_roughness();
+
+                let (texture, transparent, opaque) = match pbr.base_color_texture() {
+                    Some(info) => {
+                        image_to_texture(ctx, &images[info.texture().source().index()])?
+                    }
+                    None => (Texture::missing(ctx), false, true),
+                };
+
+                let blend = match gltf_material.alpha_mode() {
+                    gltf::material::AlphaMode::Blend => Some(wgpu::BlendState::ALPHA_BLENDING),
+                    gltf::material::AlphaMode::Opaque | gltf::material::AlphaMode::Mask => None,
+                };
+
+                let material: Box<dyn Material> =
+                    Box::new(FlatMaterial::new(ctx, &texture, blend, transparent, opaque, MSAA::Off));
+
+                meshes.push(ctx.create_mesh(vertices, indices));
+                materials.push(material);
+                textures.push(texture);
+            }
+        }
+
+        Ok(Model::new(meshes, materials, textures))
+    }
+}
+
+/// Uploads a decoded glTF image as an RGBA8 [`Texture`], reporting whether
+/// it contains any transparent/opaque texels (mirrors [`NiPixelData`]'s
+/// `to_texture`).
+fn image_to_texture(ctx: &Context, image: &gltf::image::Data) -> anyhow::Result<(Texture, bool, bool)> {
+    use gltf::image::Format;
+
+    let rgba: Vec<u8> = match image.format {
+        Format::R8G8B8A8 => image.pixels.clone(),
+        Format::R8G8B8 => image
+            .pixels
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        other => anyhow::bail!("unsupported glTF image pixel format: {other:?}"),
+    };
+
+    let mut transparent = false;
+    let mut opaque = false;
+    for alpha in rgba.iter().skip(3).step_by(4) {
+        if *alpha < 255 {
+            transparent = true;
         } else {
-            return Err(());
+            opaque = true;
         }
 
-        let mut iter = pixels.iter();
-        iter.advance_by(3).expect("TODO: panic message");
+        if transparent && opaque {
+            break;
+        }
+    }
 
-        let mut transparent = false;
-        let mut opaque = false;
+    Ok((
+        ctx.create_texture(&rgba, UVec2::new(image.width, image.height)),
+        transparent,
+        opaque,
+    ))
+}
 
-        for d in iter.step_by(4) {
-            if *d < 255u8 {
-                transparent = true;
-            } else {
-                opaque = true;
+/// How many bytes one block-compressed mip level of `size` occupies,
+/// given its format's block size in bytes (8 for DXT1, 16 for DXT3/DXT5).
+fn mip_byte_len(size: UVec2, block_size: usize) -> usize {
+    (((size.x + 3) / 4) * ((size.y + 3) / 4)) as usize * block_size
+}
+
+fn block_size_for(format: PixelFormat) -> Option<usize> {
+    match format {
+        PixelFormat::PX_FMT_DXT1 => Some(8),
+        PixelFormat::PX_FMT_DXT3 | PixelFormat::PX_FMT_DXT5 => Some(16),
+        _ => None,
+    }
+}
+
+fn bcn_encoding_for(format: PixelFormat) -> Option<BcnEncoding> {
+    match format {
+        PixelFormat::PX_FMT_DXT1 => Some(BcnEncoding::Bc1),
+        PixelFormat::PX_FMT_DXT3 => Some(BcnEncoding::Bc2),
+        PixelFormat::PX_FMT_DXT5 => Some(BcnEncoding::Bc3),
+        _ => None,
+    }
+}
+
+fn scan_transparency(rgba: &[u8]) -> (bool, bool) {
+    let mut transparent = false;
+    let mut opaque = false;
+
+    for alpha in rgba.iter().skip(3).step_by(4) {
+        if *alpha < 255u8 {
+            transparent = true;
+        } else {
+            opaque = true;
+        }
+
+        if transparent && opaque {
+            break;
+        }
+    }
+
+    (transparent, opaque)
+}
+
+/// Halves `rgba`'s resolution by averaging each 2x2 block of texels
+/// (edge texels repeat when a dimension is odd), producing the next
+/// level down when a NIF doesn't supply one itself.
+fn box_downsample(rgba: &[u8], size: UVec2) -> (Vec<u8>, UVec2) {
+    let next_size = UVec2::new((size.x / 2).max(1), (size.y / 2).max(1));
+    let mut out = vec![0u8; (next_size.x * next_size.y * 4) as usize];
+
+    let texel = |x: u32, y: u32, c: usize| -> u32 {
+        let x = x.min(size.x - 1);
+        let y = y.min(size.y - 1);
+        rgba[((y * size.x + x) * 4) as usize + c] as u32
+    };
+
+    for y in 0..next_size.y {
+        for x in 0..next_size.x {
+            let (sx, sy) = (x * 2, y * 2);
+            let out_start = ((y * next_size.x + x) * 4) as usize;
+
+            for c in 0..4 {
+                let sum = texel(sx, sy, c) + texel(sx + 1, sy, c) + texel(sx, sy + 1, c) + texel(sx + 1, sy + 1, c);
+                out[out_start + c] = (sum / 4) as u8;
             }
+        }
+    }
 
-            if transparent == true && opaque == true {
-                break;
+    (out, next_size)
+}
+
+impl NiPixelData {
+    /// Decodes this pixel data's base mip level to RGBA8, without
+    /// uploading it anywhere, so callers can pack it into an atlas instead
+    /// of giving it a standalone [`Texture`].
+    ///
+    /// Returns `(rgba8, size, transparent, opaque)`.
+    fn decode_rgba(&self) -> Result<(Vec<u8>, UVec2, bool, bool), ()> {
+        let mm = self.mipmaps.get(0).ok_or(())?;
+        let size = UVec2::new(mm.width, mm.height);
+
+        let block_size = block_size_for(self.base.pixel_format).ok_or(())?;
+        let encoding = bcn_encoding_for(self.base.pixel_format).ok_or(())?;
+        let len = mip_byte_len(size, block_size);
+        let data = self.pixel_data.get(..len).ok_or(())?;
+
+        let pixels = bcndecode::decode(data, mm.width as usize, mm.height as usize, encoding, BcnDecoderFormat::RGBA)
+            .map_err(|_| ())?;
+
+        let (transparent, opaque) = scan_transparency(&pixels);
+        Ok((pixels, size, transparent, opaque))
+    }
+
+    /// Decodes every mip level this pixel data provides to RGBA8.
+    ///
+    /// NIFs that embed a full chain pack each level's block-compressed
+    /// data back-to-back right after the base level, the same way DDS
+    /// files do. NIFs that only embed the base level (the common case)
+    /// get the rest of the chain synthesized by repeatedly
+    /// [box-downsampling](box_downsample) it in software.
+    ///
+    /// The transparent/opaque flags are still only computed from the base
+    /// level, same as [`Self::decode_rgba`].
+    fn decode_mip_chain(&self) -> Result<(Vec<Vec<u8>>, UVec2, bool, bool), ()> {
+        let (base, size, transparent, opaque) = self.decode_rgba()?;
+        let mut levels = vec![base];
+
+        if self.mipmaps.len() > 1 {
+            let block_size = block_size_for(self.base.pixel_format).ok_or(())?;
+            let encoding = bcn_encoding_for(self.base.pixel_format).ok_or(())?;
+
+            let mut offset = mip_byte_len(size, block_size);
+            for mm in self.mipmaps.iter().skip(1) {
+                let mm_size = UVec2::new(mm.width, mm.height);
+                let len = mip_byte_len(mm_size, block_size);
+                let data = self.pixel_data.get(offset..offset + len).ok_or(())?;
+
+                levels.push(
+                    bcndecode::decode(data, mm.width as usize, mm.height as usize, encoding, BcnDecoderFormat::RGBA)
+                        .map_err(|_| ())?,
+                );
+                offset += len;
+            }
+        } else {
+            let mut cur_size = size;
+            while cur_size.x > 1 || cur_size.y > 1 {
+                let (next, next_size) = box_downsample(levels.last().unwrap(), cur_size);
+                levels.push(next);
+                cur_size = next_size;
             }
         }
 
-        Ok((ctx.create_texture(&pixels, size), transparent, opaque))
+        Ok((levels, size, transparent, opaque))
+    }
+}
+
+impl ToTexture for NiPixelData {
+    type Error = ();
+
+    fn to_texture(self, ctx: &Context) -> Result<(Texture, bool, bool), Self::Error> {
+        let (levels, size, transparent, opaque) = self.decode_mip_chain()?;
+        let mips: Vec<&[u8]> = levels.iter().map(Vec::as_slice).collect();
+
+        Ok((
+            ctx.create_texture_mips(&mips, size, MipFiltering::Trilinear),
+            transparent,
+            opaque,
+        ))
     }
 }
@@ -1,37 +1,112 @@
-#![feature(iter_advance_by)]
-
 mod controller;
 mod loader;
 
-use std::error::Error;
-use std::io;
 use winit::dpi::PhysicalPosition;
-
 use winit::event::{DeviceEvent, ElementState, Event, VirtualKeyCode, WindowEvent};
 use winit::event_loop::EventLoop;
-use winit::window::{CursorGrabMode, WindowBuilder};
+use winit::window::{CursorGrabMode, Window, WindowBuilder};
 
 use crate::controller::CameraController;
 use crate::loader::ToModel;
 use buddle_math::{Mat4, UVec2, Vec2, Vec3};
 use buddle_nif::Nif;
-use buddle_render::Camera;
-use buddle_render::Context;
+use buddle_render::{Camera, Context, Model};
 use buddle_wad::{Archive, Interner};
 
-fn main() -> Result<(), Box<dyn Error>> {
+/// The NIF the viewer loads on startup. It's shipped inside `Root.wad`,
+/// which native builds read straight off disk and web builds fetch over
+/// HTTP into the same [`Archive`]/[`Interner`] pipeline.
+const MODEL_NAME: &str = "WC_Z01_Golem_Court.nif";
+
+fn window_size(window: &Window) -> UVec2 {
+    let size = window.inner_size();
+    UVec2::new(size.width, size.height)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
     let event_loop = EventLoop::new();
     let window = WindowBuilder::new()
         .with_title("buddle")
         .build(&event_loop)
         .unwrap();
 
-    let physical_size = window.inner_size();
-    let mut ctx = Context::new(
-        &window,
-        UVec2::new(physical_size.width, physical_size.height),
-    );
+    let ctx = Context::new(&window, window_size(&window));
+
+    let root = Archive::heap("Root.wad", false).unwrap();
+    let mut intern = Interner::new(&root);
+
+    let handle = intern.intern(MODEL_NAME).unwrap();
+    let data = intern.fetch(handle).unwrap();
+    let nif = Nif::parse(&mut std::io::Cursor::new(data)).unwrap();
 
+    let model = (nif, &mut intern).to_model(&ctx).unwrap();
+
+    run(event_loop, window, ctx, model);
+}
+
+/// Entry point for the `wasm32` build, invoked by the browser once the
+/// module has loaded.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen(start)]
+pub fn main_web() {
+    console_error_panic_hook::set_once();
+    wasm_bindgen_futures::spawn_local(run_web());
+}
+
+/// Async counterpart of native [`main`], since the browser never hands
+/// control back synchronously: the canvas, GPU and asset fetch all have to
+/// be awaited from this single async task instead of blocking a thread.
+#[cfg(target_arch = "wasm32")]
+async fn run_web() {
+    use winit::platform::web::WindowExtWebSys;
+
+    let event_loop = EventLoop::new();
+    let window = WindowBuilder::new()
+        .with_title("buddle")
+        .build(&event_loop)
+        .unwrap();
+
+    // winit creates the canvas but doesn't place it anywhere; attach it to
+    // the page ourselves so there's something to actually render into.
+    web_sys::window()
+        .and_then(|win| win.document())
+        .and_then(|doc| doc.body())
+        .and_then(|body| body.append_child(&web_sys::Element::from(window.canvas())).ok())
+        .expect("couldn't append canvas to document body");
+
+    let ctx = Context::new_async(&window, window_size(&window)).await;
+
+    // WAD files are multiple gigabytes and can't be bundled into the wasm
+    // module, so the web build fetches `Root.wad` over HTTP at startup and
+    // feeds the whole file into the same `Interner` the native build uses.
+    let wad_bytes = fetch_bytes("Root.wad")
+        .await
+        .expect("failed to fetch Root.wad");
+    let root = Archive::from_bytes(wad_bytes, false).unwrap();
+    let mut intern = Interner::new(&root);
+
+    let handle = intern.intern(MODEL_NAME).unwrap();
+    let data = intern.fetch(handle).unwrap();
+    let nif = Nif::parse(&mut std::io::Cursor::new(data)).unwrap();
+
+    let model = (nif, &mut intern).to_model(&ctx).unwrap();
+
+    run(event_loop, window, ctx, model);
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn fetch_bytes(path: &str) -> anyhow::Result<Vec<u8>> {
+    use gloo_net::http::Request;
+
+    let response = Request::get(path).send().await?;
+    Ok(response.binary().await?)
+}
+
+/// Drives the camera controller and render loop shared by both the native
+/// and web entry points, once each has finished setting up its own
+/// [`Context`] and loading its own [`Model`].
+fn run(event_loop: EventLoop<()>, window: Window, mut ctx: Context, model: Model) -> ! {
     let camera = Camera::perspective(
         Vec3::new(-100.0, 75.0, 0.0),
         Vec3::new(0.0, 50.0, -1.0),
@@ -42,16 +117,6 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let mut rast = camera.rasterize(&ctx);
 
-    let root = Archive::heap("Root.wad", false).unwrap();
-    let mut intern = Interner::new(&root);
-
-    let handle = intern.intern("WC_Z01_Golem_Court.nif").unwrap();
-    let data = intern.fetch_mut(handle).unwrap();
-    let mut cursor = io::Cursor::new(data);
-    let nif = Nif::parse(&mut cursor).unwrap();
-
-    let model = (nif, &mut intern).to_model(&ctx).unwrap();
-
     let mut capture_mouse = true;
 
     window.set_cursor_visible(false);